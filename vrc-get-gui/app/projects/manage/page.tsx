@@ -153,7 +153,9 @@ function PageBody() {
 		return packageRowsData.filter(row =>
 			row.displayName.toLowerCase().includes(searchLower)
 			|| row.id.toLowerCase().includes(searchLower)
-			|| row.aliases.some(alias => alias.toLowerCase().includes(searchLower)))
+			|| row.aliases.some(alias => alias.toLowerCase().includes(searchLower))
+			|| row.keywords.some(keyword => keyword.toLowerCase().includes(searchLower))
+			|| (row.author?.toLowerCase()?.includes(searchLower) ?? false))
 	}, [packageRowsData, search]);
 
 	const hiddenUserRepositories = useMemo(() => new Set(repositoriesInfo.status == 'success' ? repositoriesInfo.data.hidden_user_repositories : []), [repositoriesInfo]);
@@ -248,7 +250,8 @@ function PageBody() {
 		}) => {
 		try {
 			setInstallStatus({status: "applyingChanges"});
-			await projectApplyPendingChanges(projectPath, changes.changes_version);
+			// the dialog requires confirmation before applying changes overwriting local modifications
+			await projectApplyPendingChanges(projectPath, changes.changes_version, changes.modified_packages.length > 0);
 			setInstallStatus({status: "normal"});
 			detailsResult.refetch();
 
@@ -703,6 +706,8 @@ function ProjectChangesDialog(
 	const {t} = useTranslation();
 	const versionConflicts = changes.conflicts.filter(([_, c]) => c.packages.length > 0);
 	const unityConflicts = changes.conflicts.filter(([_, c]) => c.unity_conflict);
+	const [overwriteConfirmed, setOverwriteConfirmed] = useState(false);
+	const requireOverwriteConfirm = changes.modified_packages.length > 0 && !overwriteConfirmed;
 
 	const getPackageDisplayName = useMemo(() => {
 		const packagesById = new Map(packages.map(p => [p.id, p]));
@@ -826,10 +831,33 @@ function ProjectChangesDialog(
 						</>
 					) : null
 				}
+				{
+					changes.modified_packages.length > 0 ? (
+						<>
+							<Typography className={"text-red-700"}>
+								{t("local modifications of the following packages will be lost")}
+							</Typography>
+							<List>
+								{changes.modified_packages.map(pkgId => (
+									<ListItem key={pkgId}>
+										{getPackageDisplayName(pkgId)}
+									</ListItem>
+								))}
+							</List>
+							<label className={"flex cursor-pointer items-center gap-2 p-2"}>
+								<Checkbox ripple={false} containerProps={{className: "p-0 rounded-none"}}
+													checked={overwriteConfirmed}
+													onChange={e => setOverwriteConfirmed(e.target.checked)}
+													className="hover:before:content-none"/>
+								{t("discard the local modifications")}
+							</label>
+						</>
+					) : null
+				}
 			</DialogBody>
 			<DialogFooter>
 				<Button onClick={cancel} className="mr-1">{t("cancel")}</Button>
-				<Button onClick={apply} color={"red"}>{t("apply")}</Button>
+				<Button onClick={apply} color={"red"} disabled={requireOverwriteConfirm}>{t("apply")}</Button>
 			</DialogFooter>
 		</Dialog>
 	);
@@ -912,6 +940,8 @@ interface PackageRowInfo {
 	infoSource: TauriVersion;
 	displayName: string;
 	aliases: string[];
+	keywords: string[];
+	author: string | null;
 	unityCompatible: Map<string, TauriPackage>;
 	unityIncompatible: Map<string, TauriPackage>;
 	sources: Set<string>;
@@ -941,18 +971,10 @@ function combinePackagesAndProjectDetails(
 
 	function isUnityCompatible(pkg: TauriPackage, unityVersion: [number, number] | null) {
 		if (unityVersion == null) return true;
-		if (pkg.unity == null) return true;
 
-		// vrcsdk exceptions for unity version
-		if (VRCSDK_PACKAGES.includes(pkg.name)) {
-			if (pkg.version.major === 3 && pkg.version.minor <= 4) {
-				return unityVersion[0] === 2019;
-			}
-		} else if (pkg.name === "com.vrchat.core.vpm-resolver") {
-			if (pkg.version.major === 0 && pkg.version.minor === 1 && pkg.version.patch <= 26) {
-				return unityVersion[0] === 2019;
-			}
-		}
+		// max_unity includes the rules for vrcsdk and the resolver for unity 2019
+		if (pkg.max_unity != null && compareUnityVersion(unityVersion, pkg.max_unity) > 0) return false;
+		if (pkg.unity == null) return true;
 
 		return compareUnityVersion(pkg.unity, unityVersion) <= 0;
 	}
@@ -1000,6 +1022,8 @@ function combinePackagesAndProjectDetails(
 				id: pkg.name,
 				displayName: pkg.display_name ?? pkg.name,
 				aliases: pkg.aliases,
+				keywords: pkg.keywords,
+				author: pkg.author,
 				infoSource: pkg.version,
 				unityCompatible: new Map(),
 				unityIncompatible: new Map(),
@@ -1021,6 +1045,8 @@ function combinePackagesAndProjectDetails(
 			packageRowInfo.infoSource = pkg.version;
 			packageRowInfo.displayName = pkg.display_name ?? pkg.name;
 			packageRowInfo.aliases = pkg.aliases;
+			packageRowInfo.keywords = pkg.keywords;
+			packageRowInfo.author = pkg.author;
 		}
 
 		if (project == null || isUnityCompatible(pkg, project.unity)) {
@@ -38,6 +38,10 @@ export function environmentPackages() {
     return invoke()<TauriPackage[]>("environment_packages")
 }
 
+export function environmentPackageUsages() {
+    return invoke()<TauriPackageUsages[]>("environment_package_usages")
+}
+
 export function environmentRepositoriesInfo() {
     return invoke()<TauriRepositoriesInfo>("environment_repositories_info")
 }
@@ -114,6 +118,10 @@ export function projectUpgradeMultiplePackage(projectPath: string, envVersion: n
     return invoke()<TauriPendingProjectChanges>("project_upgrade_multiple_package", { projectPath,envVersion,packageIndices })
 }
 
+export function projectUpgradeAllPackages(projectPath: string, level: TauriUpgradeLevel) {
+    return invoke()<TauriPendingProjectChanges>("project_upgrade_all_packages", { projectPath,level })
+}
+
 export function projectResolve(projectPath: string) {
     return invoke()<TauriPendingProjectChanges>("project_resolve", { projectPath })
 }
@@ -122,8 +130,8 @@ export function projectRemovePackage(projectPath: string, name: string) {
     return invoke()<TauriPendingProjectChanges>("project_remove_package", { projectPath,name })
 }
 
-export function projectApplyPendingChanges(projectPath: string, changesVersion: number) {
-    return invoke()<null>("project_apply_pending_changes", { projectPath,changesVersion })
+export function projectApplyPendingChanges(projectPath: string, changesVersion: number, overwriteModified: boolean) {
+    return invoke()<null>("project_apply_pending_changes", { projectPath,changesVersion,overwriteModified })
 }
 
 export function projectBeforeMigrateProjectTo2022(allowMismatchedUnity: boolean) {
@@ -160,12 +168,13 @@ export function utilGetVersion() {
 
 export type TauriProjectCreationInformation = { templates: TauriProjectTemplate[]; default_path: string }
 export type TauriCreateProjectResult = "AlreadyExists" | "TemplateNotFound" | "Successful"
-export type TauriBasePackageInfo = { name: string; display_name: string | null; aliases: string[]; version: TauriVersion; unity: [number, number] | null; changelog_url: string | null; vpm_dependencies: string[]; is_yanked: boolean }
-export type TauriPendingProjectChanges = { changes_version: number; package_changes: ([string, TauriPackageChange])[]; remove_legacy_files: string[]; remove_legacy_folders: string[]; conflicts: ([string, TauriConflictInfo])[] }
+export type TauriBasePackageInfo = { name: string; display_name: string | null; aliases: string[]; version: TauriVersion; unity: [number, number] | null; max_unity: [number, number] | null; changelog_url: string | null; vpm_dependencies: string[]; is_yanked: boolean; description: string | null; keywords: string[]; author: string | null; license: string | null; licenses_url: string | null; documentation_url: string | null; unity_release: string | null; package_type: string | null; hide_in_editor: boolean | null; samples: TauriPackageSample[] }
+export type TauriPendingProjectChanges = { changes_version: number; package_changes: ([string, TauriPackageChange])[]; remove_legacy_files: string[]; remove_legacy_folders: string[]; conflicts: ([string, TauriConflictInfo])[]; modified_packages: string[] }
 export type TauriFinalizeMigrationWithUnity2022 = { type: "NoUnity2022Found" } | { type: "MigrationStarted"; event_name: string }
 export type TauriPickUnityHubResult = "NoFolderSelected" | "InvalidSelection" | "Successful"
 export type TauriBeforeMigrateProjectTo2022Result = { type: "NoUnity2022Found" } | { type: "ConfirmNotExactlyRecommendedUnity2022"; found: string; recommended: string } | { type: "ReadyToMigrate" }
 export type TauriAddRepositoryResult = "BadUrl" | "Success"
+export type TauriUpgradeLevel = "Latest" | "Minor" | "Patch" | "Compatible"
 export type TauriProjectType = "Unknown" | "LegacySdk2" | "LegacyWorlds" | "LegacyAvatars" | "UpmWorlds" | "UpmAvatars" | "UpmStarter" | "Worlds" | "Avatars" | "VpmStarter"
 export type TauriOpenUnityResult = "NoUnityVersionForTheProject" | "NoMatchingUnityFound" | "Success"
 export type TauriPickProjectDefaultPathResult = { type: "NoFolderSelected" } | { type: "InvalidSelection" } | { type: "Successful"; new_path: string }
@@ -179,13 +188,16 @@ export type LogEntry = { time: string; level: LogLevel; target: string; message:
 export type TauriProjectDetails = { unity: [number, number] | null; unity_str: string; installed_packages: ([string, TauriBasePackageInfo])[] }
 export type TauriProjectDirCheckResult = "InvalidNameForFolderName" | "MayCompatibilityProblem" | "WideChar" | "AlreadyExists" | "Ok"
 export type LogLevel = "Error" | "Warn" | "Info" | "Debug" | "Trace"
-export type TauriPackage = ({ name: string; display_name: string | null; aliases: string[]; version: TauriVersion; unity: [number, number] | null; changelog_url: string | null; vpm_dependencies: string[]; is_yanked: boolean }) & { env_version: number; index: number; source: TauriPackageSource }
+export type TauriPackage = ({ name: string; display_name: string | null; aliases: string[]; version: TauriVersion; unity: [number, number] | null; max_unity: [number, number] | null; changelog_url: string | null; vpm_dependencies: string[]; is_yanked: boolean; description: string | null; keywords: string[]; author: string | null; license: string | null; licenses_url: string | null; documentation_url: string | null; unity_release: string | null; package_type: string | null; hide_in_editor: boolean | null; samples: TauriPackageSample[] }) & { env_version: number; index: number; source: TauriPackageSource }
 export type TauriPickProjectBackupPathResult = "NoFolderSelected" | "InvalidSelection" | "Successful"
 export type TauriRepositoriesInfo = { user_repositories: TauriUserRepository[]; hidden_user_repositories: string[]; hide_local_user_packages: boolean; show_prerelease_packages: boolean }
 export type TauriPickUnityResult = "NoFolderSelected" | "InvalidSelection" | "AlreadyAdded" | "Successful"
 export type TauriDownloadRepository = { type: "BadUrl" } | { type: "Duplicated" } | { type: "DownloadError"; message: string } | { type: "Success"; value: TauriRemoteRepositoryInfo }
 export type TauriConflictInfo = { packages: string[]; unity_conflict: boolean }
+export type TauriPackageUsages = { name: string; display_name: string | null; projects: TauriPackageUsage[] }
+export type TauriPackageUsage = { project_name: string; project_path: string; version: TauriVersion; latest_version: TauriVersion | null }
 export type TauriVersion = { major: number; minor: number; patch: number; pre: string; build: string }
+export type TauriPackageSample = { display_name: string; description: string | null; path: string }
 export type TauriPackageSource = "LocalUser" | { Remote: { id: string; display_name: string } }
 export type TauriUserRepository = { id: string; url: string | null; display_name: string }
 export type TauriPackageChange = { InstallNew: TauriBasePackageInfo } | { Remove: TauriRemoveReason }
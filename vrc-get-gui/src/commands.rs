@@ -1,10 +1,10 @@
+use std::collections::BTreeMap;
 use std::ffi::OsStr;
 use std::fmt::Display;
 use std::io;
 use std::num::Wrapping;
 use std::path::{Path, PathBuf};
 use std::process::Stdio;
-use std::ptr::NonNull;
 use std::sync::atomic::{AtomicU32, Ordering};
 
 use indexmap::IndexMap;
@@ -28,14 +28,17 @@ use crate::config::GuiConfigHolder;
 use vrc_get_vpm::environment::UserProject;
 use vrc_get_vpm::io::{DefaultEnvironmentIo, DefaultProjectIo, DirEntry, EnvironmentIo, IoTrait};
 use vrc_get_vpm::repository::RemoteRepository;
+use vrc_get_vpm::unity_compatibility::maximum_unity;
 use vrc_get_vpm::unity_project::pending_project_changes::{
     ConflictInfo, PackageChange, RemoveReason,
 };
-use vrc_get_vpm::unity_project::{AddPackageOperation, PendingProjectChanges};
+use vrc_get_vpm::unity_project::{
+    AddPackageOperation, OwnedPendingProjectChanges, PendingProjectChanges, UpgradeLevel,
+};
 use vrc_get_vpm::version::Version;
 use vrc_get_vpm::{
     unity_hub, EnvironmentIoHolder, PackageCollection, PackageInfo, PackageJsonLike, ProjectType,
-    VersionSelector, VRCHAT_RECOMMENDED_2022_UNITY,
+    SharedPackageCollection, VersionSelector, VRCHAT_RECOMMENDED_2022_UNITY,
 };
 
 use crate::logging::LogEntry;
@@ -49,6 +52,7 @@ pub(crate) fn handlers<R: Runtime>() -> impl Fn(Invoke<R>) + Send + Sync + 'stat
         environment_remove_project,
         environment_copy_project_for_migration,
         environment_packages,
+        environment_package_usages,
         environment_repositories_info,
         environment_hide_repository,
         environment_show_repository,
@@ -68,6 +72,7 @@ pub(crate) fn handlers<R: Runtime>() -> impl Fn(Invoke<R>) + Send + Sync + 'stat
         project_details,
         project_install_package,
         project_upgrade_multiple_package,
+        project_upgrade_all_packages,
         project_resolve,
         project_remove_package,
         project_apply_pending_changes,
@@ -93,6 +98,7 @@ pub(crate) fn export_ts() {
             environment_remove_project,
             environment_copy_project_for_migration,
             environment_packages,
+            environment_package_usages,
             environment_repositories_info,
             environment_hide_repository,
             environment_show_repository,
@@ -112,6 +118,7 @@ pub(crate) fn export_ts() {
             project_details,
             project_install_package,
             project_upgrade_multiple_package,
+            project_upgrade_all_packages,
             project_resolve,
             project_remove_package,
             project_apply_pending_changes,
@@ -312,25 +319,21 @@ impl<E: Display> From<E> for RustError {
     }
 }
 
-unsafe impl Send for EnvironmentState {}
-
-unsafe impl Sync for EnvironmentState {}
-
 struct EnvironmentState {
     io: DefaultEnvironmentIo,
     environment: EnvironmentHolder,
     config: GuiConfigHolder,
-    packages: Option<NonNull<[PackageInfo<'static>]>>,
-    // null or reference to
+    packages: Option<SharedPackageCollection>,
     projects: Box<[UserProject]>,
     projects_version: Wrapping<u32>,
-    changes_info: ChangesInfoHolder,
+    changes_info: Option<PendingProjectChangesInfo>,
 }
 
-struct PendingProjectChangesInfo<'env> {
+struct PendingProjectChangesInfo {
     environment_version: u32,
     changes_version: u32,
-    changes: PendingProjectChanges<'env>,
+    changes: OwnedPendingProjectChanges,
+    has_modified_packages: bool,
 }
 
 struct EnvironmentHolder {
@@ -377,56 +380,44 @@ impl EnvironmentHolder {
     }
 }
 
-struct ChangesInfoHolder {
-    changes_info: Option<NonNull<PendingProjectChangesInfo<'static>>>,
-}
-
-impl ChangesInfoHolder {
-    fn new() -> Self {
-        Self { changes_info: None }
+impl EnvironmentState {
+    fn new(io: DefaultEnvironmentIo) -> Self {
+        Self {
+            environment: EnvironmentHolder::new(),
+            config: GuiConfigHolder::new(),
+            packages: None,
+            projects: Box::new([]),
+            projects_version: Wrapping(0),
+            changes_info: None,
+            io,
+        }
     }
 
-    fn update(
+    fn update_changes(
         &mut self,
         environment_version: u32,
-        changes: PendingProjectChanges<'_>,
+        changes: OwnedPendingProjectChanges,
+        modified_packages: Vec<String>,
     ) -> TauriPendingProjectChanges {
         static CHANGES_GLOBAL_INDEXER: AtomicU32 = AtomicU32::new(0);
         let changes_version = CHANGES_GLOBAL_INDEXER.fetch_add(1, Ordering::SeqCst);
 
-        let result = TauriPendingProjectChanges::new(changes_version, &changes);
+        let has_modified_packages = !modified_packages.is_empty();
+        let result = TauriPendingProjectChanges::new(
+            changes_version,
+            &changes.as_pending_changes(),
+            modified_packages,
+        );
 
-        let changes_info = Box::new(PendingProjectChangesInfo {
+        self.changes_info = Some(PendingProjectChangesInfo {
             environment_version,
             changes_version,
             changes,
+            has_modified_packages,
         });
 
-        if let Some(ptr) = self.changes_info.take() {
-            unsafe { drop(Box::from_raw(ptr.as_ptr())) }
-        }
-        self.changes_info = NonNull::new(Box::into_raw(changes_info) as *mut _);
-
         result
     }
-
-    fn take(&mut self) -> Option<PendingProjectChangesInfo> {
-        Some(*unsafe { Box::from_raw(self.changes_info.take()?.as_mut()) })
-    }
-}
-
-impl EnvironmentState {
-    fn new(io: DefaultEnvironmentIo) -> Self {
-        Self {
-            environment: EnvironmentHolder::new(),
-            config: GuiConfigHolder::new(),
-            packages: None,
-            projects: Box::new([]),
-            projects_version: Wrapping(0),
-            changes_info: ChangesInfoHolder::new(),
-            io,
-        }
-    }
 }
 
 #[derive(Debug, Clone, Serialize, specta::Type)]
@@ -740,9 +731,27 @@ struct TauriBasePackageInfo {
     aliases: Vec<String>,
     version: TauriVersion,
     unity: Option<(u16, u8)>,
+    max_unity: Option<(u16, u8)>,
     changelog_url: Option<String>,
     vpm_dependencies: Vec<String>,
     is_yanked: bool,
+    description: Option<String>,
+    keywords: Vec<String>,
+    author: Option<String>,
+    license: Option<String>,
+    licenses_url: Option<String>,
+    documentation_url: Option<String>,
+    unity_release: Option<String>,
+    package_type: Option<String>,
+    hide_in_editor: Option<bool>,
+    samples: Vec<TauriPackageSample>,
+}
+
+#[derive(Serialize, specta::Type)]
+struct TauriPackageSample {
+    display_name: String,
+    description: Option<String>,
+    path: String,
 }
 
 impl TauriBasePackageInfo {
@@ -753,6 +762,7 @@ impl TauriBasePackageInfo {
             aliases: package.aliases().iter().map(|v| v.to_string()).collect(),
             version: package.version().into(),
             unity: package.unity().map(|v| (v.major(), v.minor())),
+            max_unity: maximum_unity(package).map(|v| (v.major(), v.minor())),
             changelog_url: package.changelog_url().map(|v| v.to_string()),
             vpm_dependencies: package
                 .vpm_dependencies()
@@ -760,6 +770,24 @@ impl TauriBasePackageInfo {
                 .map(|x| x.to_string())
                 .collect(),
             is_yanked: package.is_yanked(),
+            description: package.description().map(|v| v.to_string()),
+            keywords: package.keywords().iter().map(|v| v.to_string()).collect(),
+            author: package.author().map(|v| v.name().to_string()),
+            license: package.license().map(|v| v.to_string()),
+            licenses_url: package.licenses_url().map(|v| v.to_string()),
+            documentation_url: package.documentation_url().map(|v| v.to_string()),
+            unity_release: package.unity_release().map(|v| v.to_string()),
+            package_type: package.package_type().map(|v| v.to_string()),
+            hide_in_editor: package.hide_in_editor(),
+            samples: package
+                .samples()
+                .iter()
+                .map(|v| TauriPackageSample {
+                    display_name: v.display_name().to_string(),
+                    description: v.description().map(|v| v.to_string()),
+                    path: v.path().to_string(),
+                })
+                .collect(),
         }
     }
 }
@@ -817,25 +845,89 @@ async fn environment_packages(
     info!("loading package infos");
     environment.load_package_infos(true).await?;
 
-    let packages = environment
-        .get_all_packages()
-        .collect::<Vec<_>>()
-        .into_boxed_slice();
-    if let Some(ptr) = env_state.packages {
-        env_state.packages = None; // avoid a double drop
-        unsafe { drop(Box::from_raw(ptr.as_ptr())) }
-    }
-    env_state.packages = NonNull::new(Box::into_raw(packages) as *mut _);
-    let packages = unsafe { &*env_state.packages.unwrap().as_ptr() };
+    let packages = env_state
+        .packages
+        .insert(SharedPackageCollection::new(environment));
     let version = env_state.environment.environment_version.0;
 
     Ok(packages
+        .packages()
         .iter()
         .enumerate()
-        .map(|(index, value)| TauriPackage::new(version, index, value))
+        .map(|(index, value)| TauriPackage::new(version, index, &value.as_package_info()))
         .collect::<Vec<_>>())
 }
 
+#[derive(Serialize, specta::Type)]
+struct TauriPackageUsage {
+    project_name: String,
+    project_path: String,
+    version: TauriVersion,
+    latest_version: Option<TauriVersion>,
+}
+
+#[derive(Serialize, specta::Type)]
+struct TauriPackageUsages {
+    name: String,
+    display_name: Option<String>,
+    projects: Vec<TauriPackageUsage>,
+}
+
+#[tauri::command]
+#[specta::specta]
+async fn environment_package_usages(
+    state: State<'_, Mutex<EnvironmentState>>,
+) -> Result<Vec<TauriPackageUsages>, RustError> {
+    let mut env_state = state.lock().await;
+    let env_state = &mut *env_state;
+    let environment = env_state
+        .environment
+        .get_environment_mut(false, &env_state.io)
+        .await?;
+
+    info!("loading package infos");
+    environment.load_package_infos(true).await?;
+
+    let projects = environment.get_projects()?;
+    environment.disconnect_litedb();
+
+    let allow_prerelease = environment.show_prerelease_packages();
+    let mut usages = BTreeMap::<String, TauriPackageUsages>::new();
+
+    for project in &projects {
+        let io = DefaultProjectIo::new(Path::new(project.path()).into());
+        let unity = match UnityProject::load(io).await {
+            Ok(unity) => unity,
+            Err(e) => {
+                warn!("failed to load project {}: {e}", project.path());
+                continue;
+            }
+        };
+
+        let selector = VersionSelector::latest_for(unity.unity_version(), allow_prerelease);
+        for locked in unity.locked_packages() {
+            let latest = environment.find_package_by_name(locked.name(), selector);
+            let usage =
+                (usages.entry(locked.name().to_owned())).or_insert_with(|| TauriPackageUsages {
+                    name: locked.name().to_owned(),
+                    display_name: None,
+                    projects: vec![],
+                });
+            if usage.display_name.is_none() {
+                usage.display_name = latest.and_then(|x| x.display_name()).map(Into::into);
+            }
+            usage.projects.push(TauriPackageUsage {
+                project_name: project.name().to_owned(),
+                project_path: project.path().to_owned(),
+                version: locked.version().into(),
+                latest_version: latest.map(|x| x.version().into()),
+            });
+        }
+    }
+
+    Ok(usages.into_values().collect())
+}
+
 #[derive(Serialize, specta::Type)]
 struct TauriUserRepository {
     id: String,
@@ -1647,6 +1739,7 @@ async fn environment_create_project(
         environment.save().await?;
 
         let mut unity_project = load_project(path_str.into()).await?;
+        unity_project.set_dependency_range_style(environment.dependency_range_style().clone());
 
         // finally, resolve the project folder
         let request = unity_project.resolve_request(environment).await?;
@@ -1705,10 +1798,13 @@ struct TauriPendingProjectChanges {
     remove_legacy_folders: Vec<String>,
 
     conflicts: Vec<(String, TauriConflictInfo)>,
+
+    // the packages with local modifications that will be lost
+    modified_packages: Vec<String>,
 }
 
 impl TauriPendingProjectChanges {
-    fn new(version: u32, changes: &PendingProjectChanges) -> Self {
+    fn new(version: u32, changes: &PendingProjectChanges, modified_packages: Vec<String>) -> Self {
         TauriPendingProjectChanges {
             changes_version: version,
             package_changes: changes
@@ -1731,6 +1827,7 @@ impl TauriPendingProjectChanges {
                 .iter()
                 .map(|(name, info)| (name.to_string(), info.into()))
                 .collect(),
+            modified_packages,
         }
     }
 }
@@ -1804,13 +1901,42 @@ macro_rules! changes {
         )?
 
         let $environment = state.environment.get_environment_mut(false, &state.io).await?;
-        let $packages = unsafe { &*state.packages.unwrap().as_mut() };
-        let changes = $body;
+        let collection = state
+            .packages
+            .clone()
+            .ok_or_else(|| RustError::unrecoverable("packages are not loaded"))?;
+        let $packages = &collection;
+        let (unity_project, changes): (UnityProject, PendingProjectChanges) = $body;
+
+        let modified_packages = unity_project
+            .overwritten_modifications(&changes)
+            .await?
+            .iter()
+            .map(|x| x.name().to_string())
+            .collect();
 
-        Ok(state.changes_info.update(current_version, changes))
+        let changes = OwnedPendingProjectChanges::new(&collection, &changes)
+            .ok_or_else(|| RustError::unrecoverable("changes refer to unknown packages"))?;
+
+        Ok(state.update_changes(current_version, changes, modified_packages))
     }};
 }
 
+/// Refuses the bulk upgrades moving held packages since other packages require them.
+fn refuse_held_changes(
+    unity_project: &UnityProject,
+    changes: &PendingProjectChanges,
+) -> Result<(), RustError> {
+    let held = unity_project.held_changes(changes);
+    if held.is_empty() {
+        return Ok(());
+    }
+    Err(RustError::unrecoverable(format!(
+        "held packages would be upgraded since other packages require them: {}",
+        held.iter().map(|x| x.name()).collect::<Vec<_>>().join(", ")
+    )))
+}
+
 #[tauri::command]
 #[specta::specta]
 async fn project_install_package(
@@ -1820,9 +1946,10 @@ async fn project_install_package(
     package_index: usize,
 ) -> Result<TauriPendingProjectChanges, RustError> {
     changes!(state, env_version, |environment, packages| {
-        let installing_package = packages[package_index];
+        let installing_package = packages.packages()[package_index].as_package_info();
 
-        let unity_project = load_project(project_path).await?;
+        let mut unity_project = load_project(project_path).await?;
+        unity_project.set_dependency_range_style(environment.dependency_range_style().clone());
 
         let operation = if let Some(locked) = unity_project.get_locked(installing_package.name()) {
             if installing_package.version() < locked.version() {
@@ -1837,15 +1964,10 @@ async fn project_install_package(
         let allow_prerelease = environment.show_prerelease_packages();
 
         match unity_project
-            .add_package_request(
-                environment,
-                &[installing_package],
-                operation,
-                allow_prerelease,
-            )
+            .add_package_request(packages, &[installing_package], operation, allow_prerelease)
             .await
         {
-            Ok(request) => request,
+            Ok(request) => (unity_project, request),
             Err(e) => return Err(RustError::unrecoverable(e)),
         }
     })
@@ -1860,29 +1982,86 @@ async fn project_upgrade_multiple_package(
     package_indices: Vec<usize>,
 ) -> Result<TauriPendingProjectChanges, RustError> {
     changes!(state, env_version, |environment, packages| {
+        let unity_project = load_project(project_path).await?;
+
+        // held packages are skipped by bulk upgrades
         let installing_packages = package_indices
             .iter()
-            .map(|index| packages[*index])
+            .map(|index| packages.packages()[*index].as_package_info())
+            .filter(|package| !unity_project.is_held(package.name()))
             .collect::<Vec<_>>();
 
-        let unity_project = load_project(project_path).await?;
-
         let operation = AddPackageOperation::UpgradeLocked;
 
         let allow_prerelease = environment.show_prerelease_packages();
 
-        match unity_project
-            .add_package_request(
-                environment,
-                &installing_packages,
-                operation,
-                allow_prerelease,
-            )
+        let request = match unity_project
+            .add_package_request(packages, &installing_packages, operation, allow_prerelease)
             .await
         {
             Ok(request) => request,
             Err(e) => return Err(RustError::unrecoverable(e)),
+        };
+
+        refuse_held_changes(&unity_project, &request)?;
+
+        (unity_project, request)
+    })
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, specta::Type)]
+enum TauriUpgradeLevel {
+    Latest,
+    Minor,
+    Patch,
+    Compatible,
+}
+
+impl From<TauriUpgradeLevel> for UpgradeLevel {
+    fn from(value: TauriUpgradeLevel) -> Self {
+        match value {
+            TauriUpgradeLevel::Latest => Self::Latest,
+            TauriUpgradeLevel::Minor => Self::Minor,
+            TauriUpgradeLevel::Patch => Self::Patch,
+            TauriUpgradeLevel::Compatible => Self::Compatible,
         }
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+async fn project_upgrade_all_packages(
+    state: State<'_, Mutex<EnvironmentState>>,
+    project_path: String,
+    level: TauriUpgradeLevel,
+) -> Result<TauriPendingProjectChanges, RustError> {
+    changes!(state, |environment, packages| {
+        let unity_project = load_project(project_path).await?;
+
+        let allow_prerelease = environment.show_prerelease_packages();
+
+        // held packages are skipped by bulk upgrades
+        let installing_packages = unity_project
+            .locked_packages()
+            .filter(|locked| !unity_project.is_held(locked.name()))
+            .filter_map(|locked| {
+                unity_project.find_upgrade(packages, locked.name(), level.into(), allow_prerelease)
+            })
+            .collect::<Vec<_>>();
+
+        let operation = AddPackageOperation::UpgradeLocked;
+
+        let request = match unity_project
+            .add_package_request(packages, &installing_packages, operation, allow_prerelease)
+            .await
+        {
+            Ok(request) => request,
+            Err(e) => return Err(RustError::unrecoverable(e)),
+        };
+
+        refuse_held_changes(&unity_project, &request)?;
+
+        (unity_project, request)
     })
 }
 
@@ -1892,11 +2071,11 @@ async fn project_resolve(
     state: State<'_, Mutex<EnvironmentState>>,
     project_path: String,
 ) -> Result<TauriPendingProjectChanges, RustError> {
-    changes!(state, |environment, _| {
+    changes!(state, |_, packages| {
         let unity_project = load_project(project_path).await?;
 
-        match unity_project.resolve_request(environment).await {
-            Ok(request) => request,
+        match unity_project.resolve_request(packages).await {
+            Ok(request) => (unity_project, request),
             Err(e) => return Err(RustError::unrecoverable(e)),
         }
     })
@@ -1913,7 +2092,7 @@ async fn project_remove_package(
         let unity_project = load_project(project_path).await?;
 
         match unity_project.remove_request(&[&name]).await {
-            Ok(request) => request,
+            Ok(request) => (unity_project, request),
             Err(e) => return Err(RustError::unrecoverable(e)),
         }
     })
@@ -1925,6 +2104,7 @@ async fn project_apply_pending_changes(
     state: State<'_, Mutex<EnvironmentState>>,
     project_path: String,
     changes_version: u32,
+    overwrite_modified: bool,
 ) -> Result<(), RustError> {
     let mut env_state = state.lock().await;
     let env_state = &mut *env_state;
@@ -1935,6 +2115,11 @@ async fn project_apply_pending_changes(
     if changes.environment_version != env_state.environment.environment_version.0 {
         return Err(RustError::unrecoverable("environment version mismatch"));
     }
+    if changes.has_modified_packages && !overwrite_modified {
+        return Err(RustError::unrecoverable(
+            "the changes overwrite local modifications of packages",
+        ));
+    }
 
     let environment = env_state
         .environment
@@ -1944,7 +2129,7 @@ async fn project_apply_pending_changes(
     let mut unity_project = load_project(project_path).await?;
 
     unity_project
-        .apply_pending_changes(environment, changes.changes)
+        .apply_pending_changes(environment, changes.changes.as_pending_changes())
         .await?;
 
     unity_project.save().await?;
@@ -18,6 +18,7 @@ use crate::repository::RemoteRepository;
 use crate::structs::setting::UserRepoSetting;
 use crate::traits::{EnvironmentIoHolder, HttpClient, PackageCollection, RemotePackageDownloader};
 use crate::utils::{to_vec_pretty_os_eol, Sha256AsyncWrite};
+use crate::version::DependencyRangeStyle;
 use crate::{PackageInfo, PackageManifest, VersionSelector};
 use futures::future::{join_all, try_join};
 use futures::prelude::*;
@@ -502,6 +503,27 @@ impl<T: HttpClient, IO: EnvironmentIo> Environment<T, IO> {
         self.settings.set_unity_hub(value);
     }
 
+    /// The default style of the range written to `dependencies` on install.
+    ///
+    /// This is `dependencyRangeStyle` in `vrc-get/settings.json`.
+    pub fn dependency_range_style(&self) -> &DependencyRangeStyle {
+        self.vrc_get_settings.dependency_range_style()
+    }
+
+    /// Sets the default style of the range written to `dependencies` on install.
+    ///
+    /// [`DependencyRangeStyle::Range`] is rejected since it cannot be loaded from the settings.
+    pub fn set_dependency_range_style(&mut self, value: DependencyRangeStyle) -> io::Result<()> {
+        if let DependencyRangeStyle::Range(_) = value {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "range cannot be used as the default dependency range style",
+            ));
+        }
+        self.vrc_get_settings.set_dependency_range_style(value);
+        Ok(())
+    }
+
     pub fn http(&self) -> Option<&T> {
         self.http.as_ref()
     }
@@ -1,6 +1,7 @@
 use crate::io;
 use crate::io::EnvironmentIo;
 use crate::utils::{read_json_file, SaveController};
+use crate::version::DependencyRangeStyle;
 use serde::{Deserialize, Serialize};
 
 /// since this file is vrc-get specific, additional keys can be removed
@@ -11,6 +12,23 @@ struct AsJson {
     ignore_official_repository: bool,
     #[serde(default)]
     ignore_curated_repository: bool,
+    #[serde(default, deserialize_with = "default_if_invalid_style")]
+    dependency_range_style: DependencyRangeStyle,
+}
+
+/// Invalid styles fall back to the default to keep the other settings loadable.
+fn default_if_invalid_style<'de, D>(de: D) -> Result<DependencyRangeStyle, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = serde_json::Value::deserialize(de)?;
+    match DependencyRangeStyle::deserialize(value) {
+        Ok(v) => Ok(v),
+        Err(err) => {
+            log::warn!("ignoring invalid dependencyRangeStyle in {JSON_PATH}: {err}");
+            Ok(DependencyRangeStyle::default())
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -56,6 +74,14 @@ impl VrcGetSettings {
         self.controller.as_mut().ignore_curated_repository = value;
     }
 
+    pub fn dependency_range_style(&self) -> &DependencyRangeStyle {
+        &self.controller.dependency_range_style
+    }
+
+    pub fn set_dependency_range_style(&mut self, value: DependencyRangeStyle) {
+        self.controller.as_mut().dependency_range_style = value;
+    }
+
     pub async fn save(&mut self, io: &impl EnvironmentIo) -> io::Result<()> {
         self.controller.save(io, JSON_PATH.as_ref()).await
     }
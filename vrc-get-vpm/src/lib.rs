@@ -9,19 +9,23 @@ use std::path::Path;
 
 use indexmap::IndexMap;
 
-use version::{ReleaseType, UnityVersion, Version, VersionRange};
+use version::{UnityVersion, Version, VersionRange};
 
 pub mod environment;
 pub mod io;
 mod package_json;
 pub mod repository;
+mod shared_collection;
 mod structs;
 mod traits;
+pub mod unity_compatibility;
 pub mod unity_project;
 mod utils;
 pub mod version;
 mod version_selector;
 
+#[cfg(feature = "memory-io")]
+pub mod memory_io;
 #[cfg(feature = "unity")]
 pub mod unity;
 #[cfg(feature = "unity-hub")]
@@ -30,17 +34,25 @@ pub mod unity_hub;
 use crate::repository::local::LocalCachedRepository;
 
 pub use environment::Environment;
+pub use package_json::PackageAuthor;
+pub use package_json::PackageDeprecation;
 pub use package_json::PackageJson;
 pub use package_json::PackageJsonLike;
 pub use package_json::PackageManifest;
+pub use package_json::PackageReplacement;
+pub use package_json::PackageSample;
 pub use package_json::PartialUnityVersion;
+pub use shared_collection::OwnedPackageInfo;
+pub use shared_collection::SharedPackageCollection;
 pub use structs::setting::UserRepoSetting;
 pub use traits::EnvironmentIoHolder;
 pub use traits::HttpClient;
 pub use traits::PackageCollection;
 pub use traits::RemotePackageDownloader;
 pub use unity_project::UnityProject;
-pub use version_selector::VersionSelector;
+pub use version_selector::{VersionMismatch, VersionSelector};
+
+use unity_compatibility::unity_compatible;
 
 pub const VRCHAT_RECOMMENDED_2022_UNITY: UnityVersion = UnityVersion::new_f1(2022, 3, 6);
 
@@ -217,43 +229,3 @@ impl From<ProjectType> for vrc_get_litedb::ProjectType {
         }
     }
 }
-
-fn unity_compatible(package: &PackageManifest, unity: UnityVersion) -> bool {
-    fn is_vrcsdk_for_2019(version: &Version) -> bool {
-        version.major == 3 && version.minor <= 4
-    }
-
-    fn is_resolver_for_2019(version: &Version) -> bool {
-        version.major == 0 && version.minor == 1 && version.patch <= 26
-    }
-
-    match package.name() {
-        "com.vrchat.avatars" | "com.vrchat.worlds" | "com.vrchat.base"
-            if is_vrcsdk_for_2019(package.version()) =>
-        {
-            // this version of VRCSDK is only for unity 2019 so for other version(s) of unity, it's not satisfied.
-            unity.major() == 2019
-        }
-        "com.vrchat.core.vpm-resolver" if is_resolver_for_2019(package.version()) => {
-            // this version of Resolver is only for unity 2019 so for other version(s) of unity, it's not satisfied.
-            unity.major() == 2019
-        }
-        _ => {
-            // otherwice, check based on package info
-
-            if let Some(min_unity) = package.unity() {
-                unity
-                    >= UnityVersion::new(
-                        min_unity.major(),
-                        min_unity.minor(),
-                        0,
-                        ReleaseType::Alpha,
-                        0,
-                    )
-            } else {
-                // if there are no info, satisfies for all unity versions
-                true
-            }
-        }
-    }
-}
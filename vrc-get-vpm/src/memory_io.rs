@@ -0,0 +1,22 @@
+//! In-memory implementations for testing crates built on this crate.
+//!
+//! This module is available with the `memory-io` feature.
+//! With [`VirtualFileSystem`] and [`FakeHttpClient`], you can run [`Environment`] and
+//! [`UnityProject`] without touching the real file system and the network.
+//!
+//! [`Environment`]: crate::Environment
+//! [`UnityProject`]: crate::UnityProject
+
+mod file_system;
+mod http_client;
+mod package_collection;
+mod project_builder;
+mod unitypackage_builder;
+
+pub use file_system::{
+    VirtualDirEntry, VirtualFileStream, VirtualFileSystem, VirtualReadDirStream,
+};
+pub use http_client::{create_package_zip, FakeHttpClient, RepositoryBuilder};
+pub use package_collection::{PackageCollectionBuilder, VirtualPackageCollection};
+pub use project_builder::VirtualProjectBuilder;
+pub use unitypackage_builder::UnityPackageBuilder;
@@ -1,34 +1,40 @@
+use crate::io::{DirEntry, EnvironmentIo, FileType, IoTrait, Metadata, ProjectIo};
 use futures::Stream;
 use indexmap::map::Entry;
 use indexmap::IndexMap;
 use std::ffi::{OsStr, OsString};
+use std::io;
 use std::io::ErrorKind;
 use std::path::{Component, Path, PathBuf};
 use std::pin::Pin;
 use std::sync::{Arc, Mutex};
 use std::task::{Context, Poll};
-use std::{error, io};
-use vrc_get_vpm::io::{EnvironmentIo, ExitStatus, FileType, Metadata, ProjectIo};
 
-pub(crate) use file_stream::*;
+pub use file_stream::VirtualFileStream;
 
 const NOTA_DIRECTORY: ErrorKind = ErrorKind::Other; // NotADirectory is unstable
 const IS_DIRECTORY: ErrorKind = ErrorKind::Other; // IsADirectory is unstable
 
-fn err<V, E>(kind: ErrorKind, error: E) -> io::Result<V>
-where
-    E: Into<Box<dyn error::Error + Send + Sync>>,
-{
+fn err<V>(kind: ErrorKind, error: &'static str) -> io::Result<V> {
     Err(io::Error::new(kind, error))
 }
 
-/// The virtual file system is a TraitIo implementation for testing.
+/// The file system on the memory.
 ///
-/// This struct implements All EnvironmentIo and ProjectIo methods.
+/// This implements both [`EnvironmentIo`] and [`ProjectIo`].
+/// Cloning this shares the contents so you can inspect the files after passing this to
+/// [`Environment`](crate::Environment) or [`UnityProject`](crate::UnityProject).
+#[derive(Clone)]
 pub struct VirtualFileSystem {
     root: DirectoryEntry,
 }
 
+impl Default for VirtualFileSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl VirtualFileSystem {
     pub fn new() -> Self {
         Self {
@@ -36,19 +42,25 @@ impl VirtualFileSystem {
         }
     }
 
+    /// Adds the file with the content, creating parent directories if needed.
+    ///
+    /// This fails if the file already exists.
     pub async fn add_file(&self, path: &Path, content: &[u8]) -> io::Result<()> {
-        let Some((dir_path, last)) = self.resolve2(path)? else {
-            return err(IS_DIRECTORY, "is directory");
-        };
+        let (dir_path, last) = self.resolve_file(path)?;
         self.root
-            .create_dir_all(&dir_path)
-            .await?
-            .create_file(last, true)
-            .await?
-            .set_content(content)
-            .await;
+            .create_dir_all(&dir_path)?
+            .create_file(last, true)?
+            .set_content(content);
         Ok(())
     }
+
+    /// Reads whole the content of the file.
+    pub async fn read_file(&self, path: &Path) -> io::Result<Vec<u8>> {
+        let (dir_path, last) = self.resolve_file(path)?;
+        let file = self.root.get_folder(&dir_path)?.get(last)?.into_file()?;
+        let content = file.content.lock().unwrap().clone();
+        Ok(content)
+    }
 }
 
 impl VirtualFileSystem {
@@ -58,12 +70,12 @@ impl VirtualFileSystem {
         for x in path.components() {
             match x {
                 Component::Prefix(_) | Component::RootDir => {
-                    panic!("absolute path")
+                    return err(ErrorKind::InvalidInput, "absolute path");
                 }
                 Component::CurDir => continue,
                 Component::ParentDir => {
                     if result.pop().is_none() {
-                        panic!("accessing parent folder")
+                        return err(ErrorKind::InvalidInput, "accessing parent folder");
                     }
                 }
                 Component::Normal(component) => result.push(component),
@@ -75,44 +87,39 @@ impl VirtualFileSystem {
 
     fn resolve2<'a>(&self, path: &'a Path) -> io::Result<Option<(Vec<&'a OsStr>, &'a OsStr)>> {
         let mut resolved = self.resolve(path)?;
-        if resolved.is_empty() {
-            Ok(None)
-        } else {
-            let last = resolved.remove(resolved.len() - 1);
+        if let Some(last) = resolved.pop() {
             Ok(Some((resolved, last)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn resolve_file<'a>(&self, path: &'a Path) -> io::Result<(Vec<&'a OsStr>, &'a OsStr)> {
+        match self.resolve2(path)? {
+            Some(resolved) => Ok(resolved),
+            None => err(IS_DIRECTORY, "is directory"),
         }
     }
 }
 
-impl vrc_get_vpm::io::IoTrait for VirtualFileSystem {
+impl IoTrait for VirtualFileSystem {
     async fn create_dir_all(&self, path: &Path) -> io::Result<()> {
-        self.root.create_dir_all(&self.resolve(path)?).await?;
+        self.root.create_dir_all(&self.resolve(path)?)?;
         Ok(())
     }
 
     async fn write(&self, path: &Path, content: &[u8]) -> io::Result<()> {
-        let Some((dir_path, last)) = self.resolve2(path)? else {
-            return err(IS_DIRECTORY, "is directory");
-        };
-        let file = self
-            .root
-            .get_folder(&dir_path)
-            .await?
-            .create_file(last, false)
-            .await?;
-        file.set_content(content).await;
+        let (dir_path, last) = self.resolve_file(path)?;
+        self.root
+            .get_folder(&dir_path)?
+            .create_file(last, false)?
+            .set_content(content);
         Ok(())
     }
 
     async fn remove_file(&self, path: &Path) -> io::Result<()> {
-        let Some((dir_path, last)) = self.resolve2(path)? else {
-            return err(IS_DIRECTORY, "is directory");
-        };
-        self.root
-            .get_folder(&dir_path)
-            .await?
-            .remove_file(last)
-            .await?;
+        let (dir_path, last) = self.resolve_file(path)?;
+        self.root.get_folder(&dir_path)?.remove_file(last)?;
         Ok(())
     }
 
@@ -120,11 +127,7 @@ impl vrc_get_vpm::io::IoTrait for VirtualFileSystem {
         let Some((dir_path, last)) = self.resolve2(path)? else {
             return err(ErrorKind::PermissionDenied, "removing root");
         };
-        self.root
-            .get_folder(&dir_path)
-            .await?
-            .remove_dir_all(last)
-            .await?;
+        self.root.get_folder(&dir_path)?.remove_dir_all(last)?;
         Ok(())
     }
 
@@ -132,73 +135,37 @@ impl vrc_get_vpm::io::IoTrait for VirtualFileSystem {
         let Some((dir_path, last)) = self.resolve2(path)? else {
             return Ok(Metadata::dir());
         };
-        Ok(self
-            .root
-            .get_folder(&dir_path)
-            .await?
-            .get(last)
-            .await?
-            .metadata())
+        Ok(self.root.get_folder(&dir_path)?.get(last)?.metadata())
     }
 
-    type DirEntry = DirEntry;
-    type ReadDirStream = ReadDirStream;
+    type DirEntry = VirtualDirEntry;
+    type ReadDirStream = VirtualReadDirStream;
 
     async fn read_dir(&self, path: &Path) -> io::Result<Self::ReadDirStream> {
-        let root = self.root.get_folder(&self.resolve(path)?).await?;
+        let dir = self.root.get_folder(&self.resolve(path)?)?;
 
-        Ok(ReadDirStream::new(root))
+        Ok(VirtualReadDirStream { index: 0, dir })
     }
 
-    type FileStream = FileStream;
+    type FileStream = VirtualFileStream;
 
     async fn create_new(&self, path: &Path) -> io::Result<Self::FileStream> {
-        let Some((dir_path, last)) = self.resolve2(path)? else {
-            return err(IS_DIRECTORY, "is directory");
-        };
-
-        Ok(FileStream::new(
-            self.root
-                .get_folder(&dir_path)
-                .await?
-                .create_file(last, true)
-                .await?
-                .content
-                .clone(),
-        ))
+        let (dir_path, last) = self.resolve_file(path)?;
+        let file = self.root.get_folder(&dir_path)?.create_file(last, true)?;
+        Ok(VirtualFileStream::new(file.content))
     }
 
     async fn create(&self, path: &Path) -> io::Result<Self::FileStream> {
-        let Some((dir_path, last)) = self.resolve2(path)? else {
-            return err(IS_DIRECTORY, "is directory");
-        };
-
-        Ok(FileStream::new(
-            self.root
-                .get_folder(&dir_path)
-                .await?
-                .create_file(last, false)
-                .await?
-                .content
-                .clone(),
-        ))
+        let (dir_path, last) = self.resolve_file(path)?;
+        let file = self.root.get_folder(&dir_path)?.create_file(last, false)?;
+        file.set_content(&[]);
+        Ok(VirtualFileStream::new(file.content))
     }
 
     async fn open(&self, path: &Path) -> io::Result<Self::FileStream> {
-        let Some((dir_path, last)) = self.resolve2(path)? else {
-            return err(IS_DIRECTORY, "is directory");
-        };
-
-        Ok(FileStream::new(
-            self.root
-                .get_folder(&dir_path)
-                .await?
-                .get(last)
-                .await?
-                .into_file()?
-                .content
-                .clone(),
-        ))
+        let (dir_path, last) = self.resolve_file(path)?;
+        let file = self.root.get_folder(&dir_path)?.get(last)?.into_file()?;
+        Ok(VirtualFileStream::new(file.content))
     }
 }
 
@@ -218,9 +185,20 @@ impl EnvironmentIo for VirtualFileSystem {
     #[cfg(feature = "experimental-project-management")]
     type ProjectIo = VirtualFileSystem;
 
+    /// Returns the file system rooted at the path.
+    ///
+    /// Absolute paths are treated as relative to the root of this file system.
     #[cfg(feature = "experimental-project-management")]
-    fn new_project_io(&self, _: &Path) -> Self::ProjectIo {
-        panic!("not implemented") // TODO: implement
+    fn new_project_io(&self, path: &Path) -> Self::ProjectIo {
+        let components = (path.components())
+            .filter_map(|x| match x {
+                Component::Normal(component) => Some(component),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        Self {
+            root: (self.root.create_dir_all(&components)).expect("creating project directory"),
+        }
     }
 }
 
@@ -253,20 +231,6 @@ impl FileSystemEntry {
             FileSystemEntry::Directory(e) => Ok(e),
         }
     }
-
-    fn as_file(&self) -> io::Result<&FileEntry> {
-        match self {
-            FileSystemEntry::File(e) => Ok(e),
-            FileSystemEntry::Directory(_) => err(IS_DIRECTORY, "is a directory"),
-        }
-    }
-
-    fn as_directory(&self) -> io::Result<&DirectoryEntry> {
-        match self {
-            FileSystemEntry::File(_) => err(NOTA_DIRECTORY, "is a file"),
-            FileSystemEntry::Directory(e) => Ok(e),
-        }
-    }
 }
 
 #[derive(Clone)]
@@ -281,80 +245,77 @@ impl DirectoryEntry {
         }
     }
 
-    async fn get(&self, name: &OsStr) -> io::Result<FileSystemEntry> {
+    fn get(&self, name: &OsStr) -> io::Result<FileSystemEntry> {
         self.backed
             .lock()
             .unwrap()
             .get(name)
-            .map(Clone::clone)
+            .cloned()
             .ok_or_else(|| io::Error::new(ErrorKind::NotFound, "file not found"))
     }
 
-    async fn get_folder(&self, path: &[&OsStr]) -> io::Result<DirectoryEntry> {
+    fn get_folder(&self, path: &[&OsStr]) -> io::Result<DirectoryEntry> {
         let mut current = self.clone();
 
         for component in path {
-            current = current.get(component).await?.into_directory()?;
+            current = current.get(component)?.into_directory()?;
         }
 
         Ok(current)
     }
 
-    async fn create_dir_all(&self, path: &[&OsStr]) -> io::Result<DirectoryEntry> {
+    fn create_dir_all(&self, path: &[&OsStr]) -> io::Result<DirectoryEntry> {
         let mut current = self.clone();
 
         for component in path {
-            let mut locked = current.backed.lock().unwrap();
-            let next = match locked.entry(component.to_os_string()) {
-                Entry::Occupied(e) => e.into_mut().as_directory()?.clone(),
-                Entry::Vacant(e) => e
-                    .insert(FileSystemEntry::Directory(DirectoryEntry::new()))
-                    .as_directory()
-                    .unwrap()
-                    .clone(),
+            let next = match current.backed.lock().unwrap().entry(component.into()) {
+                Entry::Occupied(e) => e.get().clone().into_directory()?,
+                Entry::Vacant(e) => {
+                    let directory = DirectoryEntry::new();
+                    e.insert(FileSystemEntry::Directory(directory.clone()));
+                    directory
+                }
             };
-            drop(locked);
             current = next;
         }
 
         Ok(current)
     }
 
-    async fn create_file(&self, name: &OsStr, new: bool) -> io::Result<FileEntry> {
-        let mut backed = self.backed.lock().unwrap();
-        match backed.entry(name.to_os_string()) {
-            Entry::Occupied(e) => match e.into_mut() {
+    fn create_file(&self, name: &OsStr, new: bool) -> io::Result<FileEntry> {
+        match self.backed.lock().unwrap().entry(name.into()) {
+            Entry::Occupied(e) => match e.get() {
                 FileSystemEntry::File(_) if new => err(ErrorKind::AlreadyExists, "file exists"),
                 FileSystemEntry::File(entry) => Ok(entry.clone()),
                 FileSystemEntry::Directory(_) => err(IS_DIRECTORY, "directory exists"),
             },
             Entry::Vacant(e) => {
-                let FileSystemEntry::File(e) = e.insert(FileSystemEntry::File(FileEntry::new()))
-                else {
-                    unreachable!()
-                };
-                Ok(e.clone())
+                let file = FileEntry::new();
+                e.insert(FileSystemEntry::File(file.clone()));
+                Ok(file)
             }
         }
     }
 
-    async fn remove_file(&self, name: &OsStr) -> io::Result<FileEntry> {
-        let mut backed = self.backed.lock().unwrap();
-        match backed.entry(name.to_os_string()) {
-            Entry::Occupied(mut e) => {
-                e.get_mut().as_file()?;
-                Ok(e.shift_remove().into_file().unwrap())
+    fn remove_file(&self, name: &OsStr) -> io::Result<FileEntry> {
+        match self.backed.lock().unwrap().entry(name.into()) {
+            Entry::Occupied(e) => {
+                let FileSystemEntry::File(_) = e.get() else {
+                    return err(IS_DIRECTORY, "is a directory");
+                };
+                e.shift_remove().into_file()
             }
             Entry::Vacant(_) => err(ErrorKind::NotFound, "file not found"),
         }
     }
 
-    async fn remove_dir_all(&self, name: &OsStr) -> io::Result<DirectoryEntry> {
-        let mut backed = self.backed.lock().unwrap();
-        match backed.entry(name.to_os_string()) {
-            Entry::Occupied(mut e) => {
-                e.get_mut().as_directory()?;
-                Ok(e.shift_remove().into_directory().unwrap())
+    fn remove_dir_all(&self, name: &OsStr) -> io::Result<DirectoryEntry> {
+        match self.backed.lock().unwrap().entry(name.into()) {
+            Entry::Occupied(e) => {
+                let FileSystemEntry::Directory(_) = e.get() else {
+                    return err(NOTA_DIRECTORY, "is a file");
+                };
+                e.shift_remove().into_directory()
             }
             Entry::Vacant(_) => err(ErrorKind::NotFound, "file not found"),
         }
@@ -373,52 +334,42 @@ impl FileEntry {
         }
     }
 
-    pub(crate) async fn set_content(&self, content: &[u8]) {
+    fn set_content(&self, content: &[u8]) {
         *self.content.lock().unwrap() = content.to_vec();
     }
 }
 
-pub struct ReadDirStream {
+/// The [`Stream`] of entries in the directory of [`VirtualFileSystem`].
+pub struct VirtualReadDirStream {
     index: usize,
     dir: DirectoryEntry,
 }
 
-impl ReadDirStream {
-    fn new(dir: DirectoryEntry) -> Self {
-        Self { index: 0, dir }
-    }
-}
-
-impl Stream for ReadDirStream {
-    type Item = io::Result<DirEntry>;
+impl Stream for VirtualReadDirStream {
+    type Item = io::Result<VirtualDirEntry>;
 
     fn poll_next(mut self: Pin<&mut Self>, _: &mut Context) -> Poll<Option<Self::Item>> {
         let locked = self.dir.backed.lock().unwrap();
         let Some((name, entry)) = locked.get_index(self.index) else {
             return Poll::Ready(None);
         };
-        let entry = DirEntry::new(name, entry.metadata());
+        let entry = VirtualDirEntry {
+            name: name.clone(),
+            metadata: entry.metadata(),
+        };
         drop(locked);
         self.index += 1;
         Poll::Ready(Some(Ok(entry)))
     }
 }
 
-pub struct DirEntry {
+/// The entry in the directory of [`VirtualFileSystem`].
+pub struct VirtualDirEntry {
     name: OsString,
     metadata: Metadata,
 }
 
-impl DirEntry {
-    fn new(name: &OsStr, metadata: Metadata) -> Self {
-        Self {
-            name: name.to_os_string(),
-            metadata,
-        }
-    }
-}
-
-impl vrc_get_vpm::io::DirEntry for DirEntry {
+impl DirEntry for VirtualDirEntry {
     fn file_name(&self) -> OsString {
         self.name.clone()
     }
@@ -440,13 +391,14 @@ mod file_stream {
     use std::sync::{Arc, Mutex};
     use std::task::{Context, Poll};
 
-    pub struct FileStream {
+    /// The opened file of [`VirtualFileSystem`](super::VirtualFileSystem).
+    pub struct VirtualFileStream {
         content: Arc<Mutex<Vec<u8>>>,
         position: usize,
     }
 
-    impl FileStream {
-        pub(crate) fn new(content: Arc<Mutex<Vec<u8>>>) -> Self {
+    impl VirtualFileStream {
+        pub(super) fn new(content: Arc<Mutex<Vec<u8>>>) -> Self {
             Self {
                 content,
                 position: 0,
@@ -454,45 +406,35 @@ mod file_stream {
         }
     }
 
-    impl AsyncSeek for FileStream {
+    fn invalid_position() -> io::Error {
+        io::Error::new(ErrorKind::InvalidInput, "invalid position")
+    }
+
+    impl AsyncSeek for VirtualFileStream {
         fn poll_seek(
             mut self: Pin<&mut Self>,
             _: &mut Context<'_>,
             pos: SeekFrom,
         ) -> Poll<io::Result<u64>> {
-            match pos {
-                SeekFrom::Start(position) => {
-                    self.position = position
-                        .try_into()
-                        .map_err(|_| io::Error::new(ErrorKind::InvalidInput, "invalid position"))?;
-                    Poll::Ready(Ok(self.position as u64))
-                }
+            let position = match pos {
+                SeekFrom::Start(position) => position.try_into().map_err(|_| invalid_position())?,
                 SeekFrom::Current(offset) => {
-                    let offset = offset
-                        .try_into()
-                        .map_err(|_| io::Error::new(ErrorKind::InvalidInput, "invalid position"))?;
-                    self.position = self.position.checked_add_signed(offset).ok_or_else(|| {
-                        io::Error::new(ErrorKind::InvalidInput, "invalid position")
-                    })?;
-                    Poll::Ready(Ok(self.position as u64))
+                    let offset = offset.try_into().map_err(|_| invalid_position())?;
+                    (self.position.checked_add_signed(offset)).ok_or_else(invalid_position)?
                 }
                 SeekFrom::End(offset) => {
-                    let offset = offset
-                        .try_into()
-                        .map_err(|_| io::Error::new(ErrorKind::InvalidInput, "invalid position"))?;
-
-                    let lock = self.content.clone();
-                    let guard = lock.lock().unwrap();
-                    self.position = guard.len().checked_add_signed(offset).ok_or_else(|| {
-                        io::Error::new(ErrorKind::InvalidInput, "invalid position")
-                    })?;
-                    Poll::Ready(Ok(self.position as u64))
+                    let offset = offset.try_into().map_err(|_| invalid_position())?;
+                    let len = self.content.lock().unwrap().len();
+                    len.checked_add_signed(offset)
+                        .ok_or_else(invalid_position)?
                 }
-            }
+            };
+            self.position = position;
+            Poll::Ready(Ok(position as u64))
         }
     }
 
-    impl AsyncRead for FileStream {
+    impl AsyncRead for VirtualFileStream {
         fn poll_read(
             mut self: Pin<&mut Self>,
             _: &mut Context<'_>,
@@ -500,8 +442,7 @@ mod file_stream {
         ) -> Poll<io::Result<usize>> {
             let lock = self.content.clone();
             let guard = lock.lock().unwrap();
-            let len = guard.len();
-            let remaining = len - self.position;
+            let remaining = guard.len().saturating_sub(self.position);
             let to_copy = buf.len().min(remaining);
             buf[..to_copy].copy_from_slice(&guard[self.position..][..to_copy]);
             self.position += to_copy;
@@ -509,9 +450,9 @@ mod file_stream {
         }
     }
 
-    impl AsyncWrite for FileStream {
+    impl AsyncWrite for VirtualFileStream {
         fn poll_write(
-            self: Pin<&mut Self>,
+            mut self: Pin<&mut Self>,
             _: &mut Context<'_>,
             buf: &[u8],
         ) -> Poll<io::Result<usize>> {
@@ -522,6 +463,7 @@ mod file_stream {
                 guard.resize(new_len, 0);
             }
             guard[self.position..][..buf.len()].copy_from_slice(buf);
+            self.position = new_len;
 
             Poll::Ready(Ok(buf.len()))
         }
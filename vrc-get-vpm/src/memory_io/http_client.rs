@@ -0,0 +1,193 @@
+use crate::io;
+use crate::HttpClient;
+use async_zip::base::write::ZipFileWriter;
+use async_zip::{Compression, ZipEntryBuilder};
+use futures::io::Cursor;
+use indexmap::IndexMap;
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use url::Url;
+
+/// The [`HttpClient`] serves resources added in advance without network access.
+///
+/// Requests to unknown URLs fail with [`NotFound`](io::ErrorKind::NotFound).
+/// The etag of the resource is the sha256 of the content.
+/// Cloning this shares the resources so resources can be added after passing this to
+/// [`Environment`](crate::Environment).
+#[derive(Clone, Default)]
+pub struct FakeHttpClient {
+    resources: Arc<Mutex<HashMap<Url, Arc<[u8]>>>>,
+}
+
+impl FakeHttpClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serves the content at the URL. The resource previously added at the URL is replaced.
+    pub fn add_resource(&self, url: Url, content: impl Into<Arc<[u8]>>) {
+        self.resources.lock().unwrap().insert(url, content.into());
+    }
+
+    /// Stops serving the resource at the URL.
+    pub fn remove_resource(&self, url: &Url) {
+        self.resources.lock().unwrap().remove(url);
+    }
+
+    /// Serves the repository json and zip files of packages in the repository.
+    pub fn add_repository(&self, repository: &RepositoryBuilder) {
+        let json = serde_json::to_vec(&repository.to_json()).expect("serializing repository");
+        self.add_resource(repository.url.clone(), json);
+        for (url, zip) in &repository.zips {
+            self.add_resource(url.clone(), zip.clone());
+        }
+    }
+
+    fn find(&self, url: &Url) -> io::Result<Arc<[u8]>> {
+        (self.resources.lock().unwrap().get(url).cloned())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("{url} is not found")))
+    }
+}
+
+impl HttpClient for FakeHttpClient {
+    async fn get(
+        &self,
+        url: &Url,
+        _: &IndexMap<Box<str>, Box<str>>,
+    ) -> io::Result<impl io::AsyncRead + Send> {
+        Ok(Cursor::new(self.find(url)?))
+    }
+
+    async fn get_with_etag(
+        &self,
+        url: &Url,
+        _: &IndexMap<Box<str>, Box<str>>,
+        current_etag: Option<&str>,
+    ) -> io::Result<Option<(impl io::AsyncRead + Send, Option<Box<str>>)>> {
+        let content = self.find(url)?;
+        let etag = hex::encode(Sha256::digest(&content));
+        if current_etag == Some(etag.as_str()) {
+            return Ok(None);
+        }
+        Ok(Some((Cursor::new(content), Some(etag.into()))))
+    }
+}
+
+/// The builder of the repository json served with [`FakeHttpClient`].
+pub struct RepositoryBuilder {
+    url: Url,
+    id: Option<Box<str>>,
+    name: Option<Box<str>>,
+    packages: IndexMap<Box<str>, Map<String, Value>>,
+    zips: Vec<(Url, Arc<[u8]>)>,
+}
+
+impl RepositoryBuilder {
+    pub fn new(url: Url) -> Self {
+        Self {
+            url,
+            id: None,
+            name: None,
+            packages: IndexMap::new(),
+            zips: vec![],
+        }
+    }
+
+    pub fn with_id(mut self, id: impl Into<Box<str>>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    pub fn with_name(mut self, name: impl Into<Box<str>>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Adds the package version to the repository.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the package json doesn't have string `name` and `version`.
+    pub fn add_package(mut self, package_json: Value) -> Self {
+        let name = package_json["name"]
+            .as_str()
+            .expect("no name in package json");
+        let version = (package_json["version"].as_str()).expect("no version in package json");
+        self.packages
+            .entry(name.into())
+            .or_default()
+            .insert(version.into(), package_json);
+        self
+    }
+
+    /// Adds the package version with the zip file created by [`create_package_zip`].
+    ///
+    /// The zip file is served next to the repository json and `url` of the package json is overwritten.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the package json doesn't have string `name` and `version`.
+    pub fn add_package_with_zip(
+        mut self,
+        mut package_json: Value,
+        zip: impl Into<Arc<[u8]>>,
+    ) -> Self {
+        let name = package_json["name"]
+            .as_str()
+            .expect("no name in package json");
+        let version = (package_json["version"].as_str()).expect("no version in package json");
+        let url = (self.url.join(&format!("{name}-{version}.zip"))).expect("creating zip url");
+        package_json["url"] = Value::String(url.to_string());
+        self.zips.push((url, zip.into()));
+        self.add_package(package_json)
+    }
+
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// Creates the repository json.
+    pub fn to_json(&self) -> Value {
+        let packages = (self.packages.iter())
+            .map(|(name, versions)| (name.to_string(), json!({ "versions": versions })))
+            .collect::<Map<_, _>>();
+
+        let mut json = json!({
+            "url": self.url.as_str(),
+            "packages": packages,
+        });
+        if let Some(id) = &self.id {
+            json["id"] = Value::from(id.as_ref());
+        }
+        if let Some(name) = &self.name {
+            json["name"] = Value::from(name.as_ref());
+        }
+        json
+    }
+}
+
+/// Creates the zip file of the package with `package.json` and the files.
+///
+/// The path of files should be relative to the package root and separated with `/`.
+pub async fn create_package_zip(package_json: &Value, files: &[(&str, &[u8])]) -> Vec<u8> {
+    async fn write_entry(writer: &mut ZipFileWriter<Cursor<Vec<u8>>>, path: &str, content: &[u8]) {
+        let entry = ZipEntryBuilder::new(path.into(), Compression::Stored);
+        (writer.write_entry_whole(entry, content).await).expect("writing to memory");
+    }
+
+    let mut writer = ZipFileWriter::new(Cursor::new(Vec::new()));
+
+    let package_json = serde_json::to_vec_pretty(package_json).expect("serializing package json");
+    write_entry(&mut writer, "package.json", &package_json).await;
+    for (path, content) in files {
+        write_entry(&mut writer, path, content).await;
+    }
+
+    writer
+        .close()
+        .await
+        .expect("writing to memory")
+        .into_inner()
+}
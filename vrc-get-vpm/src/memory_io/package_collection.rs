@@ -1,48 +1,63 @@
+use crate::version::Version;
+use crate::{PackageCollection, PackageInfo, PackageManifest, VersionSelector};
 use std::path::PathBuf;
-use vrc_get_vpm::version::Version;
-use vrc_get_vpm::{PackageCollection as _, PackageInfo, PackageManifest, VersionSelector};
 
+/// The builder of [`VirtualPackageCollection`].
 pub struct PackageCollectionBuilder {
     packages: Vec<(PackageManifest, PathBuf)>,
 }
 
+impl Default for PackageCollectionBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl PackageCollectionBuilder {
     pub fn new() -> Self {
         Self { packages: vec![] }
     }
 
+    /// Adds the package as a local package at `Packages/<name>/<version>`.
+    #[allow(clippy::should_implement_trait)]
     pub fn add(mut self, json: PackageManifest) -> PackageCollectionBuilder {
         let path = format!("Packages/{}/{}", json.name(), json.version());
         self.packages.push((json, path.into()));
         self
     }
 
-    pub fn build(self) -> PackageCollection {
-        PackageCollection {
+    pub fn build(self) -> VirtualPackageCollection {
+        VirtualPackageCollection {
             packages: self.packages,
         }
     }
 }
 
-pub struct PackageCollection {
+/// The [`PackageCollection`] with fixed packages.
+pub struct VirtualPackageCollection {
     packages: Vec<(PackageManifest, PathBuf)>,
 }
 
-impl PackageCollection {
-    pub fn get_package(&self, name: &str, version: Version) -> PackageInfo {
+impl VirtualPackageCollection {
+    /// Gets the package with the name and the version.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the package is not in this collection.
+    pub fn get_package(&self, name: &str, version: Version) -> PackageInfo<'_> {
         self.find_package_by_name(name, VersionSelector::specific_version(&version))
-            .unwrap()
+            .unwrap_or_else(|| panic!("package {name} version {version} not found"))
     }
 }
 
-impl vrc_get_vpm::PackageCollection for PackageCollection {
-    fn get_all_packages(&self) -> impl Iterator<Item = PackageInfo> {
+impl PackageCollection for VirtualPackageCollection {
+    fn get_all_packages(&self) -> impl Iterator<Item = PackageInfo<'_>> {
         self.packages
             .iter()
             .map(|(json, path)| PackageInfo::local(json, path))
     }
 
-    fn find_packages(&self, package: &str) -> impl Iterator<Item = PackageInfo> {
+    fn find_packages(&self, package: &str) -> impl Iterator<Item = PackageInfo<'_>> {
         self.get_all_packages()
             .filter(move |pkg| pkg.name() == package)
     }
@@ -53,6 +68,7 @@ impl vrc_get_vpm::PackageCollection for PackageCollection {
         version: VersionSelector,
     ) -> Option<PackageInfo<'_>> {
         self.find_packages(name)
-            .find(|pkg| version.satisfies(pkg.package_json()))
+            .filter(|pkg| version.satisfies(pkg.package_json()))
+            .max_by_key(|pkg| pkg.version())
     }
 }
@@ -1,11 +1,11 @@
-use crate::common::VirtualFileSystem;
+use crate::io::IoTrait;
+use crate::memory_io::VirtualFileSystem;
+use crate::version::{Version, VersionRange};
+use crate::UnityProject;
 use indexmap::IndexMap;
 use serde_json::json;
-use vrc_get_vpm::io::IoTrait;
-use vrc_get_vpm::unity_project::pending_project_changes::Remove;
-use vrc_get_vpm::version::{Version, VersionRange};
-use vrc_get_vpm::{PackageManifest, UnityProject};
 
+/// The builder of [`UnityProject`] on [`VirtualFileSystem`].
 pub struct VirtualProjectBuilder {
     dependencies: IndexMap<String, String>,
     locked: IndexMap<String, (Version, IndexMap<String, VersionRange>)>,
@@ -13,6 +13,12 @@ pub struct VirtualProjectBuilder {
     directories: Vec<String>,
 }
 
+impl Default for VirtualProjectBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl VirtualProjectBuilder {
     pub fn new() -> Self {
         Self {
@@ -37,6 +43,11 @@ impl VirtualProjectBuilder {
         self
     }
 
+    /// Adds the package to the locked section of `vpm-manifest.json`.
+    ///
+    /// This doesn't add the package to the `Packages` folder. Use [`add_package_json`] for that.
+    ///
+    /// [`add_package_json`]: VirtualProjectBuilder::add_package_json
     pub fn add_locked(
         &mut self,
         name: &str,
@@ -78,7 +89,19 @@ impl VirtualProjectBuilder {
         self
     }
 
+    /// Creates new file system and loads the project from it.
     pub async fn build(&self) -> std::io::Result<UnityProject<VirtualFileSystem>> {
+        self.build_on(VirtualFileSystem::new()).await
+    }
+
+    /// Writes the project to the file system and loads the project from it.
+    ///
+    /// Since [`VirtualFileSystem`] shares the contents with its clones,
+    /// you can pass a clone to inspect the project files later.
+    pub async fn build_on(
+        &self,
+        fs: VirtualFileSystem,
+    ) -> std::io::Result<UnityProject<VirtualFileSystem>> {
         let vpm_manifest = {
             let mut dependencies = serde_json::Map::new();
             for (dependency, version) in &self.dependencies {
@@ -106,7 +129,6 @@ impl VirtualProjectBuilder {
             })
         };
 
-        let fs = VirtualFileSystem::new();
         fs.add_file(
             "Packages/vpm-manifest.json".as_ref(),
             vpm_manifest.to_string().as_bytes(),
@@ -0,0 +1,52 @@
+use crate::unity_project::{UnityPackage, UnityPackageAsset};
+
+/// The builder of `.unitypackage` file for [`UnityPackage::read`].
+///
+/// The `.meta` file of each asset is generated from the GUID.
+pub struct UnityPackageBuilder {
+    assets: Vec<UnityPackageAsset>,
+}
+
+impl Default for UnityPackageBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UnityPackageBuilder {
+    pub fn new() -> Self {
+        Self { assets: vec![] }
+    }
+
+    pub fn add_asset(&mut self, guid: &str, path: &str, content: &[u8]) -> &mut Self {
+        self.assets.push(UnityPackageAsset::new(
+            guid,
+            path,
+            Some(content.to_vec()),
+            Some(meta_for(guid)),
+        ));
+        self
+    }
+
+    pub fn add_folder(&mut self, guid: &str, path: &str) -> &mut Self {
+        (self.assets).push(UnityPackageAsset::new(
+            guid,
+            path,
+            None,
+            Some(meta_for(guid)),
+        ));
+        self
+    }
+
+    /// Creates the gzip-compressed tar archive.
+    pub async fn build(&mut self) -> Vec<u8> {
+        let package = UnityPackage::new(std::mem::take(&mut self.assets));
+        let mut buffer = Vec::new();
+        (package.write(&mut buffer).await).expect("writing to memory");
+        buffer
+    }
+}
+
+fn meta_for(guid: &str) -> Vec<u8> {
+    format!("fileFormatVersion: 2\nguid: {guid}\n").into_bytes()
+}
@@ -0,0 +1,124 @@
+use serde::{Deserialize, Deserializer};
+
+/// The `author` of the package.
+///
+/// This accepts both the object form and the npm-style string form `Name <email> (url)`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PackageAuthor {
+    name: Box<str>,
+    email: Option<Box<str>>,
+    url: Option<Box<str>>,
+}
+
+impl PackageAuthor {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn email(&self) -> Option<&str> {
+        self.email.as_deref()
+    }
+
+    pub fn url(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
+
+    fn parse(value: &str) -> Self {
+        fn enclosed(value: &str, open: char, close: char) -> Option<&str> {
+            let (_, rest) = value.split_once(open)?;
+            let (enclosed, _) = rest.split_once(close)?;
+            Some(enclosed.trim()).filter(|x| !x.is_empty())
+        }
+
+        let name_end = value.find(['<', '(']).unwrap_or(value.len());
+
+        Self {
+            name: value[..name_end].trim().into(),
+            email: enclosed(value, '<', '>').map(Into::into),
+            url: enclosed(value, '(', ')').map(Into::into),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for PackageAuthor {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum AuthorJson {
+            String(Box<str>),
+            Object {
+                name: Box<str>,
+                #[serde(default)]
+                email: Option<Box<str>>,
+                #[serde(default)]
+                url: Option<Box<str>>,
+            },
+        }
+
+        Ok(match AuthorJson::deserialize(deserializer)? {
+            AuthorJson::String(value) => Self::parse(&value),
+            AuthorJson::Object { name, email, url } => Self { name, email, url },
+        })
+    }
+}
+
+/// The `license` of the package.
+///
+/// In addition to the SPDX expression, this accepts the deprecated npm-style object `{ "type": "MIT" }`.
+#[derive(Debug, Clone)]
+pub(crate) struct License(Box<str>);
+
+impl License {
+    pub(crate) fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl<'de> Deserialize<'de> for License {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum LicenseJson {
+            String(Box<str>),
+            Object {
+                #[serde(rename = "type")]
+                type_: Box<str>,
+            },
+        }
+
+        Ok(match LicenseJson::deserialize(deserializer)? {
+            LicenseJson::String(value) => Self(value),
+            LicenseJson::Object { type_ } => Self(type_),
+        })
+    }
+}
+
+#[test]
+fn parse_author() {
+    let author: PackageAuthor =
+        serde_json::from_str(r#""anatawa12 <anatawa12@example.com> (https://anatawa12.com)""#)
+            .unwrap();
+    assert_eq!(author.name(), "anatawa12");
+    assert_eq!(author.email(), Some("anatawa12@example.com"));
+    assert_eq!(author.url(), Some("https://anatawa12.com"));
+
+    let author: PackageAuthor =
+        serde_json::from_str(r#"{"name": "VRChat", "url": "https://vrchat.com"}"#).unwrap();
+    assert_eq!(author.name(), "VRChat");
+    assert_eq!(author.email(), None);
+    assert_eq!(author.url(), Some("https://vrchat.com"));
+}
+
+#[test]
+fn parse_license() {
+    let license: License = serde_json::from_str(r#""MIT""#).unwrap();
+    assert_eq!(license.as_str(), "MIT");
+    let license: License = serde_json::from_str(r#"{"type": "Apache-2.0"}"#).unwrap();
+    assert_eq!(license.as_str(), "Apache-2.0");
+}
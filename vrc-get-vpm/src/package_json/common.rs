@@ -1,3 +1,4 @@
+use crate::package_json::{PackageAuthor, PackageDeprecation, PackageSample};
 use crate::version::{Version, VersionRange};
 use crate::PartialUnityVersion;
 use indexmap::IndexMap;
@@ -15,8 +16,19 @@ pub trait PackageJsonLike {
     fn url(&self) -> Option<&Url>;
     fn zip_sha_256(&self) -> Option<&str>;
     fn changelog_url(&self) -> Option<&Url>;
+    fn documentation_url(&self) -> Option<&Url>;
+    fn license(&self) -> Option<&str>;
+    fn author(&self) -> Option<&PackageAuthor>;
+    fn licenses_url(&self) -> Option<&Url>;
+    fn keywords(&self) -> &[Box<str>];
+    fn samples(&self) -> &[PackageSample];
+    fn hide_in_editor(&self) -> Option<bool>;
+    fn unity_release(&self) -> Option<&str>;
+    fn package_type(&self) -> Option<&str>;
     fn unity(&self) -> Option<&PartialUnityVersion>;
+    fn max_unity(&self) -> Option<&PartialUnityVersion>;
     fn is_yanked(&self) -> bool;
+    fn deprecation(&self) -> Option<&PackageDeprecation>;
     fn aliases(&self) -> &[Box<str>];
 }
 
@@ -62,19 +74,50 @@ macro_rules! impl_package_json {
             pub fn url(&self) -> Option<&Url> = |$v| $expr.url.as_ref()
             pub fn zip_sha_256(&self) -> Option<&str> = |$v| $expr.zip_sha_256.as_deref()
             pub fn changelog_url(&self) -> Option<&Url> = |$v| $expr.changelog_url.as_ref()
+            pub fn documentation_url(&self) -> Option<&Url> = |$v| $expr.documentation_url.as_ref()
+            pub fn license(&self) -> Option<&str> = |$v| $expr.license.as_ref().map(|x| x.as_str())
+            pub fn author(&self) -> Option<&$crate::package_json::PackageAuthor> = |$v| $expr.author.as_ref()
+            pub fn licenses_url(&self) -> Option<&Url> = |$v| $expr.licenses_url.as_ref()
+            pub fn keywords(&self) -> &[Box<str>] = |$v| $expr.keywords.as_slice()
+            pub fn samples(&self) -> &[$crate::package_json::PackageSample] = |$v| $expr.samples.as_slice()
+            pub fn hide_in_editor(&self) -> Option<bool> = |$v| $expr.hide_in_editor
+            pub fn unity_release(&self) -> Option<&str> = |$v| $expr.unity_release.as_deref()
+            pub fn package_type(&self) -> Option<&str> = |$v| $expr.package_type.as_deref()
             pub fn unity(&self) -> Option<&PartialUnityVersion> = |$v| $expr.unity.as_ref()
+            pub fn max_unity(&self) -> Option<&PartialUnityVersion> = |$v| $expr.vrc_get.max_unity.as_ref()
             pub fn is_yanked(&self) -> bool = |$v| $expr.vrc_get.yanked.is_yanked()
+            pub fn deprecation(&self) -> Option<&$crate::package_json::PackageDeprecation> = |$v| $expr.vrc_get.deprecated.get()
             pub fn aliases(&self) -> &[Box<str>] = |$v| $expr.vrc_get.aliases.as_slice()
         }
     };
 }
 
+/// Deserializes the metadata or falls back to the default with a warning.
+///
+/// The metadata is informative, so one package with broken metadata
+/// must not make the whole repository fail to load.
+pub(crate) fn default_if_invalid<'de, D, T>(de: D) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: serde::Deserialize<'de> + Default,
+{
+    let value = <serde_json::Value as serde::Deserialize>::deserialize(de)?;
+    match T::deserialize(value) {
+        Ok(v) => Ok(v),
+        Err(err) => {
+            log::warn!("ignoring invalid metadata in package.json: {err}");
+            Ok(T::default())
+        }
+    }
+}
+
 macro_rules! package_json_struct {
     {
         $(#[$meta:meta])*
         $vis:vis struct $name: ident {
             $optional_vis:vis optional$(: #[$optional: meta])?;
             $required_vis:vis required$(: #[$required: meta])?;
+            metadata$(: #[$metadata: meta])?;
         }
     } => {
         #[derive(Deserialize, Debug, Clone)]
@@ -111,6 +154,25 @@ macro_rules! package_json_struct {
 
             $(#[$optional])?
             $optional_vis changelog_url: Option<Url>,
+            $(#[$metadata])?
+            $optional_vis documentation_url: Option<Url>,
+            $(#[$metadata])?
+            $optional_vis license: Option<$crate::package_json::License>,
+            $(#[$metadata])?
+            $optional_vis author: Option<$crate::package_json::PackageAuthor>,
+            $(#[$metadata])?
+            $optional_vis licenses_url: Option<Url>,
+            $(#[$metadata])?
+            $optional_vis keywords: Vec<Box<str>>,
+            $(#[$metadata])?
+            $optional_vis samples: Vec<$crate::package_json::PackageSample>,
+            $(#[$metadata])?
+            $optional_vis hide_in_editor: Option<bool>,
+            $(#[$metadata])?
+            $optional_vis unity_release: Option<Box<str>>,
+            $(#[$metadata])?
+            #[serde(rename = "type")]
+            $optional_vis package_type: Option<Box<str>>,
 
             #[serde(rename = "vrc-get")]
             $(#[$optional])?
@@ -126,6 +188,12 @@ macro_rules! package_json_struct {
             /// aliases for `vrc-get i --name <name> <version>` command.
             $(#[$optional])?
             pub(super) aliases: Vec<Box<str>>,
+            /// the latest `major.minor` of unity the package is compatible with.
+            $(#[$metadata])?
+            pub(super) max_unity: Option<crate::PartialUnityVersion>,
+            /// the deprecation message and the package replacing this package.
+            $(#[$metadata])?
+            pub(super) deprecated: $crate::package_json::DeprecatedState,
         }
     };
 }
@@ -0,0 +1,153 @@
+use crate::version::VersionRange;
+use serde::{Deserialize, Deserializer};
+
+/// The `vrc-get.deprecated` of the package.
+///
+/// This accepts `true`, the message, or the object `{ "message": "...", "replacedBy": ... }`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PackageDeprecation {
+    message: Option<Box<str>>,
+    replaced_by: Option<PackageReplacement>,
+}
+
+impl PackageDeprecation {
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    /// The package to migrate to with `vrc-get migrate replaced`.
+    pub fn replaced_by(&self) -> Option<&PackageReplacement> {
+        self.replaced_by.as_ref()
+    }
+}
+
+/// The package which replaces the deprecated package.
+///
+/// This accepts the package id or the object `{ "name": "...", "range": "..." }`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PackageReplacement {
+    name: Box<str>,
+    range: Option<VersionRange>,
+}
+
+impl PackageReplacement {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The versions of the replacement to install. `None` means the latest version.
+    pub fn range(&self) -> Option<&VersionRange> {
+        self.range.as_ref()
+    }
+}
+
+impl<'de> Deserialize<'de> for PackageReplacement {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum ReplacementJson {
+            String(Box<str>),
+            Object {
+                name: Box<str>,
+                #[serde(default)]
+                range: Option<VersionRange>,
+            },
+        }
+
+        Ok(match ReplacementJson::deserialize(deserializer)? {
+            ReplacementJson::String(name) => Self { name, range: None },
+            ReplacementJson::Object { name, range } => Self { name, range },
+        })
+    }
+}
+
+/// The state of `vrc-get.deprecated`. `false` is the same as not deprecated.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DeprecatedState(Option<PackageDeprecation>);
+
+impl DeprecatedState {
+    pub(crate) fn get(&self) -> Option<&PackageDeprecation> {
+        self.0.as_ref()
+    }
+}
+
+impl<'de> Deserialize<'de> for DeprecatedState {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum DeprecatedJson {
+            Bool(bool),
+            Message(Box<str>),
+            #[serde(rename_all = "camelCase")]
+            Object {
+                #[serde(default)]
+                message: Option<Box<str>>,
+                #[serde(default)]
+                replaced_by: Option<PackageReplacement>,
+            },
+        }
+
+        Ok(Self(match DeprecatedJson::deserialize(deserializer)? {
+            DeprecatedJson::Bool(false) => None,
+            DeprecatedJson::Bool(true) => Some(PackageDeprecation {
+                message: None,
+                replaced_by: None,
+            }),
+            DeprecatedJson::Message(message) => Some(PackageDeprecation {
+                message: Some(message),
+                replaced_by: None,
+            }),
+            DeprecatedJson::Object {
+                message,
+                replaced_by,
+            } => Some(PackageDeprecation {
+                message,
+                replaced_by,
+            }),
+        }))
+    }
+}
+
+#[test]
+fn parse_deprecated() {
+    let state: DeprecatedState = serde_json::from_str("false").unwrap();
+    assert_eq!(state.get(), None);
+
+    let state: DeprecatedState = serde_json::from_str("true").unwrap();
+    let deprecation = state.get().unwrap();
+    assert_eq!(deprecation.message(), None);
+    assert_eq!(deprecation.replaced_by(), None);
+
+    let state: DeprecatedState = serde_json::from_str(r#""no longer maintained""#).unwrap();
+    let deprecation = state.get().unwrap();
+    assert_eq!(deprecation.message(), Some("no longer maintained"));
+    assert_eq!(deprecation.replaced_by(), None);
+
+    let state: DeprecatedState =
+        serde_json::from_str(r#"{"message": "renamed", "replacedBy": "com.anatawa12.new-tool"}"#)
+            .unwrap();
+    let deprecation = state.get().unwrap();
+    assert_eq!(deprecation.message(), Some("renamed"));
+    let replaced_by = deprecation.replaced_by().unwrap();
+    assert_eq!(replaced_by.name(), "com.anatawa12.new-tool");
+    assert_eq!(replaced_by.range(), None);
+
+    let state: DeprecatedState = serde_json::from_str(
+        r#"{"replacedBy": {"name": "com.anatawa12.new-tool", "range": "^2.0.0"}}"#,
+    )
+    .unwrap();
+    let deprecation = state.get().unwrap();
+    assert_eq!(deprecation.message(), None);
+    let replaced_by = deprecation.replaced_by().unwrap();
+    assert_eq!(replaced_by.name(), "com.anatawa12.new-tool");
+    assert_eq!(
+        replaced_by.range(),
+        Some(&"^2.0.0".parse::<VersionRange>().unwrap())
+    );
+}
@@ -1,15 +1,23 @@
 #[macro_use]
 pub mod common;
 
+mod author;
+mod deprecation;
 #[allow(clippy::module_inception)]
 pub mod package_json;
 pub mod package_manifest;
 mod partial_unity_version;
+mod sample;
 mod yank_state;
 
+use author::License;
+use deprecation::DeprecatedState;
 use yank_state::YankState;
 
+pub use author::PackageAuthor;
 pub use common::PackageJsonLike;
+pub use deprecation::{PackageDeprecation, PackageReplacement};
 pub use package_json::PackageJson;
 pub use package_manifest::PackageManifest;
 pub use partial_unity_version::PartialUnityVersion;
+pub use sample::PackageSample;
@@ -51,6 +51,7 @@ mod inner {
         pub(super) struct PackageJson {
             pub(super) optional: #[serde(default, deserialize_with = "default_if_err")];
             pub(super) required;
+            metadata: #[serde(default, deserialize_with = "default_if_err")];
         }
     }
 }
@@ -89,3 +90,34 @@ fn deserialize_partially_bad() {
     assert_eq!(package_json.aliases(), &["vpm".into()]);
     assert_eq!(package_json.changelog_url(), None);
 }
+
+#[test]
+fn deserialize_metadata() {
+    let json = r#"{
+        "name": "com.anatawa12.tool",
+        "version": "1.0.0",
+        "type": "tool",
+        "keywords": ["avatar", "optimization"],
+        "licensesUrl": "https://example.com/LICENSE",
+        "hideInEditor": false,
+        "unity": "2022.3",
+        "unityRelease": "22f1",
+        "samples": [
+            {"displayName": "Basic", "description": "The basic usage", "path": "Samples~/Basic"}
+        ]
+    }"#;
+    let package_json: PackageJson = serde_json::from_str(json).unwrap();
+    assert_eq!(package_json.package_type(), Some("tool"));
+    assert_eq!(
+        package_json.keywords(),
+        &["avatar".into(), "optimization".into()]
+    );
+    assert_eq!(
+        package_json.licenses_url().map(|x| x.as_str()),
+        Some("https://example.com/LICENSE")
+    );
+    assert_eq!(package_json.hide_in_editor(), Some(false));
+    assert_eq!(package_json.unity_release(), Some("22f1"));
+    assert_eq!(package_json.samples().len(), 1);
+    assert_eq!(package_json.samples()[0].path(), "Samples~/Basic");
+}
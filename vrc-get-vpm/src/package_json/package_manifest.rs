@@ -10,11 +10,23 @@ package_json_struct! {
     pub struct PackageManifest {
         optional: #[serde(default)];
         required;
+        metadata: #[serde(default, deserialize_with = "crate::package_json::common::default_if_invalid")];
     }
 }
 
 impl_package_json!(impl PackageManifest = |value| value);
 
+impl PackageManifest {
+    /// Limits the unity versions like `vrc-get.maxUnity` for the rules shipped by the repository.
+    pub(crate) fn limit_max_unity(&mut self, max_unity: PartialUnityVersion) {
+        let limited = match self.vrc_get.max_unity {
+            Some(current) => current.min(max_unity),
+            None => max_unity,
+        };
+        self.vrc_get.max_unity = Some(limited);
+    }
+}
+
 /// Constructing PackageJson. Especially for testing.
 impl PackageManifest {
     pub fn new(name: impl Into<Box<str>>, version: Version) -> Self {
@@ -32,6 +44,15 @@ impl PackageManifest {
             vrc_get: VrcGetMeta::default(),
             zip_sha_256: None,
             changelog_url: None,
+            documentation_url: None,
+            license: None,
+            author: None,
+            licenses_url: None,
+            keywords: Vec::new(),
+            samples: Vec::new(),
+            hide_in_editor: None,
+            unity_release: None,
+            package_type: None,
         }
     }
 
@@ -1,10 +1,16 @@
 use serde::de::Error;
 use serde::{Deserialize, Deserializer};
+use std::fmt;
 
-#[derive(Debug, Clone)]
+/// The `major.minor` of unity version like `unity` in `package.json`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
 pub struct PartialUnityVersion(u16, u8);
 
 impl PartialUnityVersion {
+    pub fn new(major: u16, minor: u8) -> Self {
+        Self(major, minor)
+    }
+
     pub fn major(&self) -> u16 {
         self.0
     }
@@ -14,6 +20,12 @@ impl PartialUnityVersion {
     }
 }
 
+impl fmt::Display for PartialUnityVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.0, self.1)
+    }
+}
+
 impl<'de> Deserialize<'de> for PartialUnityVersion {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -0,0 +1,46 @@
+use serde::Deserialize;
+
+/// The sample listed in `samples` of the package.
+///
+/// The sample is a folder in the package that can be imported to the `Assets` folder.
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageSample {
+    #[serde(default)]
+    display_name: Box<str>,
+    #[serde(default)]
+    description: Option<Box<str>>,
+    #[serde(default)]
+    path: Box<str>,
+}
+
+impl PackageSample {
+    pub fn display_name(&self) -> &str {
+        &self.display_name
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// The path to the folder of the sample, relative to the package folder.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+}
+
+#[test]
+fn parse_sample() {
+    let samples: Vec<PackageSample> = serde_json::from_str(
+        r#"[
+            {"displayName": "Basic", "description": "The basic usage", "path": "Samples~/Basic"},
+            {"displayName": "Advanced", "path": "Samples~/Advanced"}
+        ]"#,
+    )
+    .unwrap();
+
+    assert_eq!(samples[0].display_name(), "Basic");
+    assert_eq!(samples[0].description(), Some("The basic usage"));
+    assert_eq!(samples[0].path(), "Samples~/Basic");
+    assert_eq!(samples[1].description(), None);
+}
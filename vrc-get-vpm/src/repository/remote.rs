@@ -1,4 +1,5 @@
 use crate::traits::HttpClient;
+use crate::unity_compatibility::UnityCompatibilityRule;
 use crate::utils::{deserialize_json, deserialize_json_slice};
 use crate::version::Version;
 use crate::PackageManifest;
@@ -29,12 +30,46 @@ struct ParsedRepository {
     id: Option<Box<str>>,
     #[serde(default)]
     packages: HashMap<Box<str>, RemotePackages>,
+    #[serde(
+        default,
+        rename = "vrc-get",
+        deserialize_with = "crate::package_json::common::default_if_invalid"
+    )]
+    vrc_get: RepositoryVrcGetMeta,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+struct RepositoryVrcGetMeta {
+    /// the rules to limit unity versions of the packages in this repository.
+    #[serde(default, deserialize_with = "skip_invalid_rules")]
+    unity_compatibility: Vec<UnityCompatibilityRule>,
+}
+
+/// Deserializes the rules, ignoring the invalid ones with a warning.
+fn skip_invalid_rules<'de, D>(de: D) -> Result<Vec<UnityCompatibilityRule>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let rules: Vec<Value> = crate::package_json::common::default_if_invalid(de)?;
+    Ok(rules
+        .into_iter()
+        .filter_map(|rule| match serde_json::from_value(rule) {
+            Ok(rule) => Some(rule),
+            Err(err) => {
+                log::warn!("ignoring invalid unityCompatibility rule: {err}");
+                None
+            }
+        })
+        .collect())
 }
 
 impl RemoteRepository {
     pub fn parse(cache: JsonMap) -> io::Result<Self> {
+        let mut parsed: ParsedRepository = deserialize_json(Value::Object(cache.clone()))?;
+        parsed.apply_unity_compatibility();
         Ok(Self {
-            parsed: deserialize_json(Value::Object(cache.clone()))?,
+            parsed,
             actual: cache,
         })
     }
@@ -125,6 +160,29 @@ impl RemoteRepository {
     pub fn get_package_version(&self, name: &str, version: &Version) -> Option<&PackageManifest> {
         self.parsed.packages.get(name)?.versions.get(version)
     }
+
+    /// The rules in `vrc-get.unityCompatibility` of the repository.
+    ///
+    /// The rules are already applied to the packages in this repository.
+    pub fn unity_compatibility_rules(&self) -> &[UnityCompatibilityRule] {
+        &self.parsed.vrc_get.unity_compatibility
+    }
+}
+
+impl ParsedRepository {
+    /// The rules only apply to the packages in the repository.
+    fn apply_unity_compatibility(&mut self) {
+        for rule in &self.vrc_get.unity_compatibility {
+            let Some(packages) = self.packages.get_mut(rule.package()) else {
+                continue;
+            };
+            for package in packages.versions.values_mut() {
+                if rule.matches(package.name(), package.version()) {
+                    package.limit_max_unity(*rule.max_unity());
+                }
+            }
+        }
+    }
 }
 
 impl Serialize for RemoteRepository {
@@ -0,0 +1,202 @@
+use crate::repository::local::LocalCachedRepository;
+use crate::version::Version;
+use crate::{PackageCollection, PackageInfo, PackageInfoInner, PackageManifest, VersionSelector};
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+/// The owned version of [`PackageInfo`].
+///
+/// The manifest and the repository are shared with [`Arc`] so this is cheap to clone,
+/// `'static`, and `Send`.
+#[derive(Clone, Debug)]
+pub struct OwnedPackageInfo {
+    inner: OwnedPackageInfoInner,
+}
+
+#[derive(Clone, Debug)]
+enum OwnedPackageInfoInner {
+    Remote(Arc<PackageManifest>, Arc<LocalCachedRepository>),
+    Local(Arc<PackageManifest>, Arc<Path>),
+}
+
+impl OwnedPackageInfo {
+    pub fn remote(json: Arc<PackageManifest>, repo: Arc<LocalCachedRepository>) -> Self {
+        Self {
+            inner: OwnedPackageInfoInner::Remote(json, repo),
+        }
+    }
+
+    pub fn local(json: Arc<PackageManifest>, path: Arc<Path>) -> Self {
+        Self {
+            inner: OwnedPackageInfoInner::Local(json, path),
+        }
+    }
+
+    /// Borrows this as [`PackageInfo`].
+    pub fn as_package_info(&self) -> PackageInfo<'_> {
+        match &self.inner {
+            OwnedPackageInfoInner::Remote(json, repo) => PackageInfo::remote(json, repo),
+            OwnedPackageInfoInner::Local(json, path) => PackageInfo::local(json, path),
+        }
+    }
+
+    pub fn package_json(&self) -> &PackageManifest {
+        match &self.inner {
+            OwnedPackageInfoInner::Remote(json, _) => json,
+            OwnedPackageInfoInner::Local(json, _) => json,
+        }
+    }
+
+    pub fn repo(&self) -> Option<&LocalCachedRepository> {
+        match &self.inner {
+            OwnedPackageInfoInner::Remote(_, repo) => Some(repo),
+            OwnedPackageInfoInner::Local(_, _) => None,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        self.package_json().name()
+    }
+
+    pub fn version(&self) -> &Version {
+        self.package_json().version()
+    }
+
+    pub fn is_yanked(&self) -> bool {
+        self.package_json().is_yanked()
+    }
+
+    pub fn display_name(&self) -> Option<&str> {
+        self.package_json().display_name()
+    }
+}
+
+impl Serialize for OwnedPackageInfo {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        #[serde(tag = "type", rename_all = "camelCase")]
+        enum Source<'a> {
+            Remote {
+                id: Option<&'a str>,
+                url: Option<&'a str>,
+            },
+            Local {
+                path: &'a Path,
+            },
+        }
+
+        let json = self.package_json();
+        let source = match &self.inner {
+            OwnedPackageInfoInner::Remote(_, repo) => Source::Remote {
+                id: repo.id(),
+                url: repo.url().map(url::Url::as_str),
+            },
+            OwnedPackageInfoInner::Local(_, path) => Source::Local { path },
+        };
+
+        let mut s = serializer.serialize_struct("OwnedPackageInfo", 7)?;
+        s.serialize_field("name", json.name())?;
+        s.serialize_field("version", json.version())?;
+        s.serialize_field("displayName", &json.display_name())?;
+        s.serialize_field("description", &json.description())?;
+        s.serialize_field("yanked", &json.is_yanked())?;
+        s.serialize_field("vpmDependencies", json.vpm_dependencies())?;
+        s.serialize_field("source", &source)?;
+        s.end()
+    }
+}
+
+/// The snapshot of a [`PackageCollection`] with [`Arc`]-shared manifests and repositories.
+///
+/// Unlike [`Environment`](crate::Environment), this is cheap to clone and does not need to be borrowed,
+/// so this can be held across awaits or sent between tasks.
+/// The changes resolved with this collection can be converted to
+/// [`OwnedPendingProjectChanges`](crate::unity_project::OwnedPendingProjectChanges).
+#[derive(Clone, Debug)]
+pub struct SharedPackageCollection {
+    packages: Arc<[OwnedPackageInfo]>,
+    // address of manifest to index of packages
+    indices: Arc<HashMap<usize, usize>>,
+}
+
+impl SharedPackageCollection {
+    /// Creates the snapshot of all packages in the collection.
+    pub fn new(collection: &impl PackageCollection) -> Self {
+        let mut repositories = HashMap::<usize, Arc<LocalCachedRepository>>::new();
+        let mut local_paths = HashMap::<&Path, Arc<Path>>::new();
+
+        let packages = collection
+            .get_all_packages()
+            .map(|package| {
+                let json = Arc::new(package.package_json().clone());
+                match package.inner {
+                    PackageInfoInner::Remote(_, repo) => {
+                        let repo = repositories
+                            .entry(repo as *const _ as usize)
+                            .or_insert_with(|| Arc::new(repo.clone()));
+                        OwnedPackageInfo::remote(json, repo.clone())
+                    }
+                    PackageInfoInner::Local(_, path) => {
+                        let path = local_paths.entry(path).or_insert_with(|| path.into());
+                        OwnedPackageInfo::local(json, path.clone())
+                    }
+                }
+            })
+            .collect::<Arc<[_]>>();
+
+        Self::from_packages(packages)
+    }
+
+    fn from_packages(packages: Arc<[OwnedPackageInfo]>) -> Self {
+        let indices = (packages.iter().enumerate())
+            .map(|(index, package)| (manifest_address(package.package_json()), index))
+            .collect::<HashMap<_, _>>();
+
+        Self {
+            packages,
+            indices: Arc::new(indices),
+        }
+    }
+
+    /// Returns all packages in this snapshot.
+    pub fn packages(&self) -> &[OwnedPackageInfo] {
+        &self.packages
+    }
+
+    /// Returns the owned version of the package borrowed from this collection.
+    ///
+    /// Returns `None` if the package is not from this collection.
+    pub fn to_owned_package(&self, package: PackageInfo) -> Option<OwnedPackageInfo> {
+        let index = self
+            .indices
+            .get(&manifest_address(package.package_json()))?;
+        Some(self.packages[*index].clone())
+    }
+}
+
+fn manifest_address(manifest: &PackageManifest) -> usize {
+    manifest as *const _ as usize
+}
+
+impl PackageCollection for SharedPackageCollection {
+    fn get_all_packages(&self) -> impl Iterator<Item = PackageInfo<'_>> {
+        self.packages.iter().map(OwnedPackageInfo::as_package_info)
+    }
+
+    fn find_packages(&self, package: &str) -> impl Iterator<Item = PackageInfo<'_>> {
+        self.get_all_packages().filter(move |x| x.name() == package)
+    }
+
+    fn find_package_by_name(
+        &self,
+        package: &str,
+        package_selector: VersionSelector,
+    ) -> Option<PackageInfo<'_>> {
+        self.find_packages(package)
+            .filter(|x| package_selector.satisfies(x.package_json()))
+            .max_by_key(|x| x.version())
+    }
+}
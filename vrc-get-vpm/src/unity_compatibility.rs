@@ -0,0 +1,148 @@
+use crate::version::{ReleaseType, UnityVersion, Version, VersionRange};
+use crate::{PackageJsonLike, PartialUnityVersion};
+use lazy_static::lazy_static;
+use serde::Deserialize;
+use std::fmt;
+
+/// The rule to limit the unity versions the package is compatible with.
+///
+/// Repositories can ship rules for their packages in `vrc-get.unityCompatibility` of the repository.
+/// Prerelease versions of the package are matched with `versions` as well.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UnityCompatibilityRule {
+    package: Box<str>,
+    versions: VersionRange,
+    max_unity: PartialUnityVersion,
+}
+
+impl UnityCompatibilityRule {
+    pub fn new(
+        package: impl Into<Box<str>>,
+        versions: VersionRange,
+        max_unity: PartialUnityVersion,
+    ) -> Self {
+        Self {
+            package: package.into(),
+            versions,
+            max_unity,
+        }
+    }
+
+    pub fn package(&self) -> &str {
+        &self.package
+    }
+
+    pub fn versions(&self) -> &VersionRange {
+        &self.versions
+    }
+
+    /// The latest `major.minor` of unity the package is compatible with.
+    pub fn max_unity(&self) -> &PartialUnityVersion {
+        &self.max_unity
+    }
+
+    pub fn matches(&self, name: &str, version: &Version) -> bool {
+        self.package.as_ref() == name && self.versions.match_pre(version, true)
+    }
+}
+
+lazy_static! {
+    /// The rules for well-known packages which don't tell the maximum unity version.
+    static ref BUILTIN_RULES: Vec<UnityCompatibilityRule> = {
+        // VRCSDK 3.4 or older and the resolver 0.1.26 or older are only for unity 2019
+        let vrcsdk_for_2019 = ">=3.0.0-0 <3.5.0-0".parse::<VersionRange>().unwrap();
+        let resolver_for_2019 = ">=0.1.0-0 <0.1.27-0".parse::<VersionRange>().unwrap();
+        let unity_2019 = PartialUnityVersion::new(2019, 4);
+
+        vec![
+            UnityCompatibilityRule::new("com.vrchat.avatars", vrcsdk_for_2019.clone(), unity_2019),
+            UnityCompatibilityRule::new("com.vrchat.worlds", vrcsdk_for_2019.clone(), unity_2019),
+            UnityCompatibilityRule::new("com.vrchat.base", vrcsdk_for_2019, unity_2019),
+            UnityCompatibilityRule::new("com.vrchat.core.vpm-resolver", resolver_for_2019, unity_2019),
+        ]
+    };
+}
+
+/// Why the package is not compatible with the unity.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum UnityIncompatibility {
+    /// The unity is older than `unity` and `unityRelease` of the package.
+    TooOld { minimum: UnityVersion },
+    /// The unity is newer than `vrc-get.maxUnity` of the package or the rules.
+    TooNew { maximum: PartialUnityVersion },
+}
+
+impl fmt::Display for UnityIncompatibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnityIncompatibility::TooOld { minimum } => {
+                write!(f, "requires unity {minimum} or later")
+            }
+            UnityIncompatibility::TooNew { maximum } => {
+                write!(f, "requires unity {maximum} or older")
+            }
+        }
+    }
+}
+
+/// The minimum unity version from `unity` and `unityRelease` of the package.
+pub fn minimum_unity(package: &impl PackageJsonLike) -> Option<UnityVersion> {
+    let min_unity = package.unity()?;
+    let release = (package.unity_release())
+        .and_then(|release| {
+            UnityVersion::parse(&format!(
+                "{}.{}.{}",
+                min_unity.major(),
+                min_unity.minor(),
+                release.trim()
+            ))
+        })
+        // unityRelease is only meaningful with the same major and minor version
+        .filter(|x| x.major() == min_unity.major() && x.minor() == min_unity.minor());
+
+    Some(release.unwrap_or_else(|| {
+        UnityVersion::new(
+            min_unity.major(),
+            min_unity.minor(),
+            0,
+            ReleaseType::Alpha,
+            0,
+        )
+    }))
+}
+
+/// The maximum unity version from `vrc-get.maxUnity` of the package and the builtin rules.
+pub fn maximum_unity(package: &impl PackageJsonLike) -> Option<PartialUnityVersion> {
+    (BUILTIN_RULES.iter())
+        .filter(|rule| rule.matches(package.name(), package.version()))
+        .map(|rule| rule.max_unity)
+        .chain(package.max_unity().copied())
+        .min()
+}
+
+/// Checks if the package is compatible with the unity and returns the reason if not.
+pub fn unity_incompatibility(
+    package: &impl PackageJsonLike,
+    unity: UnityVersion,
+) -> Option<UnityIncompatibility> {
+    if let Some(minimum) = minimum_unity(package) {
+        if unity < minimum {
+            return Some(UnityIncompatibility::TooOld { minimum });
+        }
+    }
+
+    if let Some(maximum) = maximum_unity(package) {
+        if (unity.major(), unity.minor()) > (maximum.major(), maximum.minor()) {
+            return Some(UnityIncompatibility::TooNew { maximum });
+        }
+    }
+
+    // if there are no info, satisfies for all unity versions
+    None
+}
+
+pub(crate) fn unity_compatible(package: &impl PackageJsonLike, unity: UnityVersion) -> bool {
+    unity_incompatibility(package, unity).is_none()
+}
@@ -1,21 +1,34 @@
 mod add_package;
+mod dependency_graph;
+mod doctor;
+mod export_unitypackage;
 mod find_legacy_assets;
+mod history;
+mod import_sample;
+mod import_unitypackage;
+mod local_modifications;
+mod migrate_replaced;
 mod migrate_unity_2022;
 mod migrate_vpm;
+mod package_patch;
 mod package_resolution;
 pub mod pending_project_changes;
+mod project_settings;
 mod project_type;
 mod remove_package;
 mod resolve;
+mod saved_changes;
+mod upgrade;
 mod upm_manifest;
 mod vpm_manifest;
 
 use crate::io;
+use crate::unity_project::project_settings::ProjectSettings;
 use crate::unity_project::upm_manifest::UpmManifest;
 use crate::unity_project::vpm_manifest::VpmManifest;
 use crate::utils::{try_load_json, PathBufExt};
-use crate::version::{UnityVersion, Version, VersionRange};
-use futures::future::try_join;
+use crate::version::{DependencyRangeStyle, UnityVersion, Version, VersionRange};
+use futures::future::try_join3;
 use futures::prelude::*;
 use indexmap::IndexMap;
 use lazy_static::lazy_static;
@@ -29,10 +42,28 @@ use crate::io::{DirEntry, FileSystemProjectIo, ProjectIo};
 use crate::PackageJson;
 pub use add_package::AddPackageErr;
 pub use add_package::AddPackageOperation;
+pub use dependency_graph::{DependencyGraph, GraphEdge, GraphNode, GraphNodeKind};
+pub use doctor::ProjectDiagnostic;
+pub use export_unitypackage::ExportUnityPackageErr;
+pub use find_legacy_assets::{scan_legacy_assets, top_level_legacy_assets, LegacyAssetInfo};
+pub use history::{HistoryEntry, LockSnapshot};
+pub use import_sample::{ImportSampleErr, SampleInfo};
+pub use import_unitypackage::{
+    ImportAsset, ImportAssetStatus, ImportFilter, PendingImport, UnityPackage, UnityPackageAsset,
+};
+pub use local_modifications::PackageModifications;
+pub use migrate_replaced::DeprecatedPackage;
 pub use migrate_unity_2022::MigrateUnity2022Error;
 pub use migrate_vpm::MigrateVpmError;
+pub use package_patch::CreatePatchErr;
+pub use pending_project_changes::OwnedPendingProjectChanges;
 pub use pending_project_changes::PendingProjectChanges;
+pub use remove_package::RemovePackageErr;
 pub use resolve::ResolvePackageErr;
+pub use saved_changes::RestoreChangesErr;
+pub use saved_changes::SavedPendingChanges;
+pub use upgrade::UpgradeLevel;
+pub use upm_manifest::UpmDependency;
 
 #[derive(Debug)]
 pub struct UnityProject<IO: ProjectIo> {
@@ -41,11 +72,19 @@ pub struct UnityProject<IO: ProjectIo> {
     manifest: VpmManifest,
     // manifest.json
     upm_manifest: UpmManifest,
+    /// vrc-get-project.json
+    settings: ProjectSettings,
     /// unity version parsed
     unity_version: Option<UnityVersion>,
     /// packages installed in the directory but not locked in vpm-manifest.json
     unlocked_packages: Vec<(Box<str>, Option<PackageJson>)>,
     installed_packages: HashMap<Box<str>, PackageJson>,
+    /// the style of the range written to dependencies on install
+    dependency_range_style: DependencyRangeStyle,
+    /// the reason recorded to the history entries
+    history_reason: Option<Box<str>>,
+    /// history entries to be saved
+    unsaved_history: Vec<HistoryEntry>,
 }
 
 // basic lifecycle
@@ -53,6 +92,7 @@ impl<IO: ProjectIo> UnityProject<IO> {
     pub async fn load(io: IO) -> io::Result<Self> {
         let manifest = VpmManifest::load(&io).await?;
         let upm_manifest = UpmManifest::load(&io).await?;
+        let settings = ProjectSettings::load(&io).await?;
 
         let mut installed_packages = HashMap::new();
         let mut unlocked_packages = vec![];
@@ -62,6 +102,11 @@ impl<IO: ProjectIo> UnityProject<IO> {
             if !dir_entry.file_type().await?.is_dir() {
                 continue;
             }
+            if dir_entry.file_name() == package_patch::PATCHES_FOLDER
+                || dir_entry.file_name() == history::HISTORY_FOLDER
+            {
+                continue;
+            }
             let read = Self::try_read_unlocked_package(&io, dir_entry).await;
             let mut is_installed = false;
             if let Some(parsed) = &read.1 {
@@ -83,9 +128,13 @@ impl<IO: ProjectIo> UnityProject<IO> {
             io,
             manifest,
             upm_manifest,
+            settings,
             unity_version,
             unlocked_packages,
             installed_packages,
+            dependency_range_style: DependencyRangeStyle::default(),
+            history_reason: None,
+            unsaved_history: vec![],
         })
     }
 }
@@ -127,19 +176,8 @@ impl<IO: ProjectIo> UnityProject<IO> {
             return None;
         };
 
-        let Some((_, version_info)) = buffer.split_once("m_EditorVersion:") else {
-            log::error!("m_EditorVersion not found in ProjectVersion.txt");
-            return None;
-        };
-
-        let version_info_end = version_info
-            .find(|x: char| x == '\r' || x == '\n')
-            .unwrap_or(version_info.len());
-        let version_info = &version_info[..version_info_end];
-        let version_info = version_info.trim();
-
-        let Some(unity_version) = UnityVersion::parse(version_info) else {
-            log::error!("failed to unity version in ProjectVersion.txt ({version_info})");
+        let Some(unity_version) = UnityVersion::parse_project_version(&buffer) else {
+            log::error!("failed to parse m_EditorVersion in ProjectVersion.txt");
             return None;
         };
 
@@ -151,11 +189,13 @@ impl<IO: ProjectIo> UnityProject<IO> {
     }
 
     pub async fn save(&mut self) -> io::Result<()> {
-        try_join(
+        try_join3(
             self.manifest.save(&self.io),
             self.upm_manifest.save(&self.io),
+            self.settings.save(&self.io),
         )
         .await?;
+        self.save_history().await?;
         Ok(())
     }
 }
@@ -216,9 +256,101 @@ impl<IO: ProjectIo> UnityProject<IO> {
         self.unity_version
     }
 
+    /// The dependencies in `Packages/manifest.json` of UPM.
+    pub fn upm_dependencies(&self) -> impl Iterator<Item = (&str, &UpmDependency)> {
+        self.upm_manifest.dependencies()
+    }
+
     pub fn has_upm_package(&self, name: &str) -> bool {
         self.upm_manifest.get_dependency(name).is_some()
     }
+
+    /// The resolutions in `vrc-get-project.json`.
+    ///
+    /// The range of the resolution is used instead of the ranges required by the dependents.
+    pub fn resolutions(&self) -> impl Iterator<Item = (&str, &VersionRange)> {
+        (self.settings.resolutions().iter()).map(|(name, range)| (name.as_ref(), range))
+    }
+
+    pub fn get_resolution(&self, name: &str) -> Option<&VersionRange> {
+        self.settings.resolutions().get(name)
+    }
+
+    /// The held packages with the reasons in `vrc-get-project.json`.
+    ///
+    /// Held packages should be skipped by bulk upgrades.
+    pub fn held_packages(&self) -> impl Iterator<Item = (&str, Option<&str>)> {
+        self.settings.holds()
+    }
+
+    pub fn is_held(&self, name: &str) -> bool {
+        self.settings.get_hold(name).is_some()
+    }
+
+    pub fn hold_reason(&self, name: &str) -> Option<&str> {
+        self.settings.get_hold(name).flatten()
+    }
+
+    /// The held packages installed at another version by the changes.
+    ///
+    /// Held packages may be changed when other packages require newer versions of them,
+    /// so the changes should be refused unless the user explicitly allows it.
+    pub fn held_changes<'a>(
+        &self,
+        changes: &'a PendingProjectChanges,
+    ) -> Vec<crate::PackageInfo<'a>> {
+        (changes.package_changes().values())
+            .filter_map(|change| change.as_install()?.install_package())
+            .filter(|package| self.is_held(package.name()))
+            .filter(|package| {
+                (self.get_locked(package.name())).map(|x| x.version()) != Some(package.version())
+            })
+            .collect()
+    }
+
+    pub fn dependency_range_style(&self) -> &DependencyRangeStyle {
+        &self.dependency_range_style
+    }
+}
+
+// options
+impl<IO: ProjectIo> UnityProject<IO> {
+    /// Sets the style of the range written to `dependencies` when packages are installed.
+    ///
+    /// This is not saved to the project. The default is [`DependencyRangeStyle::Version`].
+    pub fn set_dependency_range_style(&mut self, style: DependencyRangeStyle) {
+        self.dependency_range_style = style;
+    }
+}
+
+// resolutions
+impl<IO: ProjectIo> UnityProject<IO> {
+    /// Forces the range for the package regardless of the ranges required by the dependents.
+    ///
+    /// The resolution is saved to `vrc-get-project.json` by [`save`](Self::save).
+    pub fn set_resolution(&mut self, name: &str, range: VersionRange) {
+        self.settings.set_resolution(name, range);
+    }
+
+    /// Removes the resolution for the package. Returns `false` if there is no resolution.
+    pub fn remove_resolution(&mut self, name: &str) -> bool {
+        self.settings.remove_resolution(name)
+    }
+}
+
+// holds
+impl<IO: ProjectIo> UnityProject<IO> {
+    /// Holds the package at the current version against bulk upgrades.
+    ///
+    /// The hold is saved to `vrc-get-project.json` by [`save`](Self::save).
+    pub fn hold_package(&mut self, name: &str, reason: Option<Box<str>>) {
+        self.settings.hold(name, reason);
+    }
+
+    /// Removes the hold of the package. Returns `false` if the package is not held.
+    pub fn unhold_package(&mut self, name: &str) -> bool {
+        self.settings.unhold(name)
+    }
 }
 
 impl<IO: FileSystemProjectIo + ProjectIo> UnityProject<IO> {
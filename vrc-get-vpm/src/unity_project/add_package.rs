@@ -70,19 +70,20 @@ impl<IO: ProjectIo> UnityProject<IO> {
         for &request in packages {
             match operation {
                 AddPackageOperation::InstallToDependencies => {
-                    let add_to_dependencies = self
-                        .manifest
-                        .get_dependency(request.name())
-                        .and_then(|range| range.as_single_version())
-                        .map(|full| &full < request.version())
-                        .unwrap_or(true);
+                    let new_range = self.dependency_range_style.range_for(request.version());
+                    let add_to_dependencies = match self.manifest.get_dependency(request.name()) {
+                        None => true,
+                        Some(range) if range == &new_range => false,
+                        // the same version is rewritten only if the style is changed
+                        Some(range) => range
+                            .as_single_version()
+                            .map(|full| &full <= request.version())
+                            .unwrap_or(true),
+                    };
 
                     if add_to_dependencies {
                         debug!("Adding package {} to dependencies", request.name());
-                        changes.add_to_dependencies(
-                            request.name().into(),
-                            DependencyRange::version(request.version().clone()),
-                        );
+                        changes.add_to_dependencies(request.name().into(), new_range);
                     }
 
                     check_and_add_adding_package(request, &mut adding_packages, &self.manifest);
@@ -184,6 +185,7 @@ impl<IO: ProjectIo> UnityProject<IO> {
             }),
             self.manifest.all_locked(),
             |pkg| self.manifest.get_locked(pkg),
+            self.settings.resolutions(),
             self.unity_version(),
             env,
             adding_packages,
@@ -0,0 +1,225 @@
+use crate::io::ProjectIo;
+use crate::unity_project::upm_manifest::UpmDependency;
+use crate::version::{Version, VersionRange};
+use crate::UnityProject;
+use std::collections::{BTreeMap, HashMap};
+
+/// The dependency graph of the packages created by [`UnityProject::dependency_graph`].
+#[derive(Debug, Clone)]
+pub struct DependencyGraph {
+    nodes: Vec<GraphNode>,
+    edges: Vec<GraphEdge>,
+}
+
+impl DependencyGraph {
+    /// The packages in the graph, sorted by the name.
+    pub fn nodes(&self) -> &[GraphNode] {
+        &self.nodes
+    }
+
+    /// The dependencies in the graph.
+    ///
+    /// The dependencies of the project come first, followed by the dependencies of each package.
+    pub fn edges(&self) -> &[GraphEdge] {
+        &self.edges
+    }
+
+    pub fn get_node(&self, name: &str) -> Option<&GraphNode> {
+        self.nodes.iter().find(|node| node.name.as_ref() == name)
+    }
+}
+
+/// The kind of the package in [`DependencyGraph`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum GraphNodeKind {
+    /// The package locked in `vpm-manifest.json`.
+    Locked,
+    /// The package installed in `Packages` but not locked.
+    Unlocked,
+    /// The package depended on but neither locked nor installed.
+    Missing,
+    /// The package in `Packages/manifest.json` of UPM.
+    Upm,
+}
+
+/// The package in [`DependencyGraph`].
+#[derive(Debug, Clone)]
+pub struct GraphNode {
+    name: Box<str>,
+    version: Option<Version>,
+    kind: GraphNodeKind,
+    root: bool,
+    replaced_by: Option<Box<str>>,
+}
+
+impl GraphNode {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The locked or installed version.
+    ///
+    /// This is `None` for missing packages and UPM packages not specified with a version.
+    pub fn version(&self) -> Option<&Version> {
+        self.version.as_ref()
+    }
+
+    pub fn kind(&self) -> GraphNodeKind {
+        self.kind
+    }
+
+    /// Returns true if the package is in `dependencies` of the project.
+    pub fn is_root(&self) -> bool {
+        self.root
+    }
+
+    /// The installed package listing this package in `legacyPackages`, if any.
+    pub fn replaced_by(&self) -> Option<&str> {
+        self.replaced_by.as_deref()
+    }
+}
+
+/// The dependency in [`DependencyGraph`].
+#[derive(Debug, Clone)]
+pub struct GraphEdge {
+    from: Option<Box<str>>,
+    to: Box<str>,
+    range: VersionRange,
+    conflict: bool,
+}
+
+impl GraphEdge {
+    /// The name of the package depending on the package. `None` for dependencies of the project.
+    pub fn from(&self) -> Option<&str> {
+        self.from.as_deref()
+    }
+
+    pub fn to(&self) -> &str {
+        &self.to
+    }
+
+    /// The range required by the dependent.
+    pub fn range(&self) -> &VersionRange {
+        &self.range
+    }
+
+    /// Returns true if the version of the dependency doesn't satisfy the range.
+    ///
+    /// Like resolving, the resolution for the dependency is used instead of the range if exists.
+    pub fn is_conflict(&self) -> bool {
+        self.conflict
+    }
+}
+
+impl<IO: ProjectIo> UnityProject<IO> {
+    /// Creates the dependency graph from `dependencies` and `locked` in `vpm-manifest.json`
+    /// and the installed packages not locked.
+    ///
+    /// With `include_upm`, the packages in `Packages/manifest.json` are also included.
+    pub fn dependency_graph(&self, include_upm: bool) -> DependencyGraph {
+        let mut nodes = BTreeMap::<&str, GraphNode>::new();
+
+        for locked in self.locked_packages() {
+            nodes.insert(
+                locked.name(),
+                GraphNode::new(locked.name(), Some(locked.version()), GraphNodeKind::Locked),
+            );
+        }
+
+        for package in (self.unlocked_packages().iter()).filter_map(|(_, json)| json.as_ref()) {
+            nodes.entry(package.name()).or_insert_with(|| {
+                GraphNode::new(
+                    package.name(),
+                    Some(package.version()),
+                    GraphNodeKind::Unlocked,
+                )
+            });
+        }
+
+        if include_upm {
+            for (name, dependency) in self.upm_manifest.dependencies() {
+                let version = match dependency {
+                    UpmDependency::Version(version) => Some(version),
+                    UpmDependency::OtherNotation(_) => None,
+                };
+                nodes
+                    .entry(name)
+                    .or_insert_with(|| GraphNode::new(name, version, GraphNodeKind::Upm));
+            }
+        }
+
+        let mut replaced_by = HashMap::<&str, &str>::new();
+        for package in self.all_installed_packages() {
+            for legacy in package.legacy_packages() {
+                replaced_by.insert(legacy.as_ref(), package.name());
+            }
+        }
+
+        let mut edges = Vec::new();
+
+        for (name, range) in self.manifest.dependencies() {
+            edges.push(GraphEdge {
+                from: None,
+                to: name.into(),
+                range: range.as_range(),
+                conflict: false,
+            });
+        }
+
+        for package in self.all_packages() {
+            for (dependency, range) in package.dependencies() {
+                edges.push(GraphEdge {
+                    from: Some(package.name().into()),
+                    to: dependency.clone(),
+                    range: range.clone(),
+                    conflict: false,
+                });
+            }
+        }
+
+        for edge in &mut edges {
+            let node = nodes.get(edge.to.as_ref());
+            let version = node.and_then(|node| node.version.as_ref());
+            edge.conflict = match version {
+                // resolutions take precedence over the ranges required by dependents
+                Some(version) => {
+                    let range = self.get_resolution(&edge.to).unwrap_or(&edge.range);
+                    !range.match_pre(version, true)
+                }
+                // UPM packages not specified with a version can't be checked
+                None => !matches!(node, Some(node) if node.kind == GraphNodeKind::Upm),
+            };
+        }
+
+        let mut missing = Vec::new();
+        for edge in &edges {
+            if !nodes.contains_key(edge.to.as_ref()) {
+                missing.push(GraphNode::new(&edge.to, None, GraphNodeKind::Missing));
+            }
+        }
+
+        let mut nodes = (nodes.into_values()).chain(missing).collect::<Vec<_>>();
+        nodes.sort_by(|a, b| a.name.cmp(&b.name));
+        nodes.dedup_by(|a, b| a.name == b.name);
+
+        for node in &mut nodes {
+            node.root = self.manifest.get_dependency(&node.name).is_some();
+            node.replaced_by = replaced_by.get(node.name.as_ref()).map(|&x| x.into());
+        }
+
+        DependencyGraph { nodes, edges }
+    }
+}
+
+impl GraphNode {
+    fn new(name: &str, version: Option<&Version>, kind: GraphNodeKind) -> Self {
+        Self {
+            name: name.into(),
+            version: version.cloned(),
+            kind,
+            root: false,
+            replaced_by: None,
+        }
+    }
+}
@@ -0,0 +1,348 @@
+use crate::io::ProjectIo;
+use crate::unity_project::find_legacy_assets::{collect_legacy_assets, try_parse_meta, Guid};
+use crate::unity_project::{pending_project_changes, PendingProjectChanges};
+use crate::utils::walk_dir_relative;
+use crate::version::{UnityVersion, Version, VersionRange};
+use crate::{unity_compatible, PackageCollection, PackageInfo, UnityProject, VersionSelector};
+use futures::prelude::*;
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::pin::pin;
+
+/// The problem found by [`UnityProject::diagnose`].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum ProjectDiagnostic {
+    /// The locked package is not installed in `Packages` folder.
+    MissingPackage { name: Box<str>, locked: Version },
+    /// The version in `package.json` of the installed package is different from the locked version.
+    VersionMismatch {
+        name: Box<str>,
+        locked: Version,
+        installed: Version,
+    },
+    /// The folder in `Packages` is neither locked in `vpm-manifest.json` nor a UPM package.
+    UnlockedPackage {
+        folder: Box<str>,
+        package_name: Option<Box<str>>,
+    },
+    /// The legacy asset of the installed package still exists.
+    LegacyAsset {
+        path: Box<Path>,
+        package: Box<str>,
+        is_folder: bool,
+    },
+    /// Multiple `.meta` files have the same GUID.
+    DuplicateGuid {
+        guid: Box<str>,
+        paths: Vec<Box<Path>>,
+    },
+    /// The dependency is not locked or the locked version doesn't satisfy the range.
+    UnsatisfiedDependency {
+        /// The name of the package depending on the dependency. `None` for dependencies of the project.
+        package: Option<Box<str>>,
+        dependency: Box<str>,
+        range: VersionRange,
+        locked: Option<Version>,
+    },
+    /// The installed package doesn't support the unity version of the project.
+    IncompatibleUnity {
+        name: Box<str>,
+        version: Version,
+        unity: UnityVersion,
+    },
+}
+
+impl ProjectDiagnostic {
+    /// Returns true if [`UnityProject::doctor_fix_request`] can fix this problem.
+    ///
+    /// The problem may not be fixed if the package is not found in the repositories.
+    pub fn is_fixable(&self) -> bool {
+        match self {
+            ProjectDiagnostic::MissingPackage { .. }
+            | ProjectDiagnostic::VersionMismatch { .. }
+            | ProjectDiagnostic::LegacyAsset { .. } => true,
+            ProjectDiagnostic::UnlockedPackage { .. }
+            | ProjectDiagnostic::DuplicateGuid { .. }
+            | ProjectDiagnostic::UnsatisfiedDependency { .. }
+            | ProjectDiagnostic::IncompatibleUnity { .. } => false,
+        }
+    }
+}
+
+impl fmt::Display for ProjectDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProjectDiagnostic::MissingPackage { name, locked } => {
+                write!(f, "{name} version {locked} is locked but not installed")
+            }
+            ProjectDiagnostic::VersionMismatch {
+                name,
+                locked,
+                installed,
+            } => write!(
+                f,
+                "{name} version {locked} is locked but version {installed} is installed"
+            ),
+            ProjectDiagnostic::UnlockedPackage {
+                folder,
+                package_name: Some(name),
+            } => write!(f, "Packages/{folder} ({name}) is not locked"),
+            ProjectDiagnostic::UnlockedPackage {
+                folder,
+                package_name: None,
+            } => write!(f, "Packages/{folder} is not a package"),
+            ProjectDiagnostic::LegacyAsset {
+                path,
+                package,
+                is_folder,
+            } => write!(
+                f,
+                "legacy {kind} {path} of {package} still exists",
+                kind = if *is_folder { "folder" } else { "file" },
+                path = path.display(),
+            ),
+            ProjectDiagnostic::DuplicateGuid { guid, paths } => {
+                write!(f, "GUID {guid} is used by multiple assets: ")?;
+                let mut iter = paths.iter();
+                write!(f, "{}", iter.next().unwrap().display())?;
+                for path in iter {
+                    write!(f, ", {}", path.display())?;
+                }
+                Ok(())
+            }
+            ProjectDiagnostic::UnsatisfiedDependency {
+                package,
+                dependency,
+                range,
+                locked,
+            } => {
+                match package {
+                    Some(package) => write!(f, "{package} depends on {dependency}@{range}")?,
+                    None => write!(f, "the project depends on {dependency}@{range}")?,
+                }
+                match locked {
+                    Some(locked) => write!(f, " but version {locked} is locked"),
+                    None => write!(f, " but it's not locked"),
+                }
+            }
+            ProjectDiagnostic::IncompatibleUnity {
+                name,
+                version,
+                unity,
+            } => write!(
+                f,
+                "{name} version {version} is not compatible with unity {unity}"
+            ),
+        }
+    }
+}
+
+// diagnosing project
+impl<IO: ProjectIo> UnityProject<IO> {
+    /// Checks the project for problems.
+    ///
+    /// Legacy assets are checked with the package information in the collection.
+    pub async fn diagnose(&self, env: &impl PackageCollection) -> Vec<ProjectDiagnostic> {
+        let mut diagnostics = Vec::new();
+
+        self.diagnose_installation(&mut diagnostics);
+        self.diagnose_dependencies(&mut diagnostics);
+        self.diagnose_unity_version(&mut diagnostics);
+
+        let legacy_assets = collect_legacy_assets(&self.io, &self.find_installed(env)).await;
+        for (path, package) in legacy_assets.folders {
+            diagnostics.push(ProjectDiagnostic::LegacyAsset {
+                path,
+                package: package.into(),
+                is_folder: true,
+            });
+        }
+        for (path, package) in legacy_assets.files {
+            diagnostics.push(ProjectDiagnostic::LegacyAsset {
+                path,
+                package: package.into(),
+                is_folder: false,
+            });
+        }
+
+        self.diagnose_guids(&mut diagnostics).await;
+
+        diagnostics
+    }
+
+    /// Creates the changes to fix the problems can be fixed safely.
+    ///
+    /// This reinstalls missing packages and packages with mismatched versions,
+    /// and removes legacy assets of the installed packages.
+    /// Packages not found in the collection are not reinstalled.
+    pub async fn doctor_fix_request<'env>(
+        &self,
+        env: &'env impl PackageCollection,
+    ) -> PendingProjectChanges<'env> {
+        let mut changes = pending_project_changes::Builder::new();
+
+        for locked in self.locked_packages() {
+            let needs_reinstall = match self.get_installed_package(locked.name()) {
+                None => true,
+                Some(installed) => installed.version() != locked.version(),
+            };
+            if !needs_reinstall {
+                continue;
+            }
+
+            let selector = VersionSelector::specific_version(locked.version());
+            match env.find_package_by_name(locked.name(), selector) {
+                Some(package) => {
+                    changes.install_already_locked(package);
+                }
+                None => log::warn!(
+                    "{} version {} not found in repositories",
+                    locked.name(),
+                    locked.version()
+                ),
+            }
+        }
+
+        let legacy_assets = collect_legacy_assets(&self.io, &self.find_installed(env)).await;
+
+        changes.build_with_legacy_assets(legacy_assets.files, legacy_assets.folders)
+    }
+
+    fn find_installed<'env>(&self, env: &'env impl PackageCollection) -> Vec<PackageInfo<'env>> {
+        (self.installed_packages())
+            .filter_map(|(name, json)| {
+                let selector = VersionSelector::specific_version(json.version());
+                env.find_package_by_name(name, selector)
+            })
+            .collect()
+    }
+
+    fn diagnose_installation(&self, diagnostics: &mut Vec<ProjectDiagnostic>) {
+        for locked in self.locked_packages() {
+            match self.get_installed_package(locked.name()) {
+                None => diagnostics.push(ProjectDiagnostic::MissingPackage {
+                    name: locked.name().into(),
+                    locked: locked.version().clone(),
+                }),
+                Some(installed) if installed.version() != locked.version() => {
+                    diagnostics.push(ProjectDiagnostic::VersionMismatch {
+                        name: locked.name().into(),
+                        locked: locked.version().clone(),
+                        installed: installed.version().clone(),
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+
+        for (folder, json) in self.unlocked_packages() {
+            let package_name = json.as_ref().map(|x| x.name());
+            // embedded UPM packages are listed in manifest.json
+            if self.has_upm_package(package_name.unwrap_or(folder)) {
+                continue;
+            }
+            // the locked package whose folder has another package is reported as missing
+            if self.is_locked(folder) {
+                continue;
+            }
+            diagnostics.push(ProjectDiagnostic::UnlockedPackage {
+                folder: folder.clone(),
+                package_name: package_name.map(Into::into),
+            });
+        }
+    }
+
+    fn diagnose_dependencies(&self, diagnostics: &mut Vec<ProjectDiagnostic>) {
+        let locked_version = |name: &str| -> Option<&Version> {
+            self.get_locked(name).map(|x| x.version()).or_else(|| {
+                (self.unlocked_packages().iter())
+                    .filter_map(|(_, json)| json.as_ref())
+                    .find(|json| json.name() == name)
+                    .map(|json| json.version())
+            })
+        };
+
+        for (name, range) in self.manifest.dependencies() {
+            let locked = locked_version(name);
+            let satisfied = match self.get_resolution(name) {
+                Some(resolution) => locked.map(|x| resolution.match_pre(x, true)),
+                None => locked.map(|x| range.matches(x)),
+            };
+            if !satisfied.unwrap_or(false) {
+                diagnostics.push(ProjectDiagnostic::UnsatisfiedDependency {
+                    package: None,
+                    dependency: name.into(),
+                    range: (self.get_resolution(name).cloned()).unwrap_or_else(|| range.as_range()),
+                    locked: locked.cloned(),
+                });
+            }
+        }
+
+        for package in self.all_packages() {
+            for (dependency, range) in package.dependencies() {
+                // resolutions take precedence over the ranges required by dependents
+                let range = self.get_resolution(dependency).unwrap_or(range);
+                let locked = locked_version(dependency);
+                if !locked.map(|x| range.match_pre(x, true)).unwrap_or(false) {
+                    diagnostics.push(ProjectDiagnostic::UnsatisfiedDependency {
+                        package: Some(package.name().into()),
+                        dependency: dependency.clone(),
+                        range: range.clone(),
+                        locked: locked.cloned(),
+                    });
+                }
+            }
+        }
+    }
+
+    fn diagnose_unity_version(&self, diagnostics: &mut Vec<ProjectDiagnostic>) {
+        let Some(unity) = self.unity_version() else {
+            return;
+        };
+
+        for package in self.all_installed_packages() {
+            if !unity_compatible(package, unity) {
+                diagnostics.push(ProjectDiagnostic::IncompatibleUnity {
+                    name: package.name().into(),
+                    version: package.version().clone(),
+                    unity,
+                });
+            }
+        }
+    }
+
+    async fn diagnose_guids(&self, diagnostics: &mut Vec<ProjectDiagnostic>) {
+        async fn get_guid<IO: ProjectIo>(io: &IO, relative: PathBuf) -> Option<(Guid, PathBuf)> {
+            if relative.extension() != Some(OsStr::new("meta")) {
+                return None;
+            }
+            let guid = try_parse_meta(io, &relative).await?;
+            Some((guid, relative.with_extension("")))
+        }
+
+        let paths = [PathBuf::from("Assets"), PathBuf::from("Packages")];
+        let mut stream =
+            pin!(walk_dir_relative(&self.io, paths).filter_map(|x| get_guid(&self.io, x)));
+
+        let mut guids = HashMap::<Guid, Vec<Box<Path>>>::new();
+        while let Some((guid, path)) = stream.next().await {
+            guids.entry(guid).or_default().push(path.into_boxed_path());
+        }
+
+        let mut duplicates = (guids.into_iter())
+            .filter(|(_, paths)| paths.len() > 1)
+            .map(|(guid, mut paths)| {
+                paths.sort();
+                (guid.to_string().into_boxed_str(), paths)
+            })
+            .collect::<Vec<_>>();
+        duplicates.sort();
+
+        for (guid, paths) in duplicates {
+            diagnostics.push(ProjectDiagnostic::DuplicateGuid { guid, paths });
+        }
+    }
+}
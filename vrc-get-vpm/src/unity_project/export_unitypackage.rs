@@ -0,0 +1,232 @@
+use crate::io;
+use crate::io::ProjectIo;
+use crate::unity_project::find_legacy_assets::try_parse_meta;
+use crate::unity_project::{UnityPackage, UnityPackageAsset};
+use crate::utils::{is_complete_relative, walk_dir_relative};
+use crate::UnityProject;
+use futures::prelude::*;
+use std::ffi::OsStr;
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
+
+// exporting unitypackage
+impl<IO: ProjectIo> UnityProject<IO> {
+    /// Collects the assets in the folders or files to be exported as `.unitypackage`.
+    ///
+    /// Each path is relative to the project root like `Assets/MyTool`.
+    /// All assets must have `.meta` files to preserve GUIDs.
+    /// Like unity, hidden files and folders ending with `~` are not exported.
+    pub async fn export_unitypackage(
+        &self,
+        paths: &[&Path],
+    ) -> Result<UnityPackage, ExportUnityPackageErr> {
+        Ok(UnityPackage::new(self.collect_export_assets(paths).await?))
+    }
+
+    async fn collect_export_assets(
+        &self,
+        paths: &[&Path],
+    ) -> Result<Vec<UnityPackageAsset>, ExportUnityPackageErr> {
+        let mut assets = Vec::new();
+        let mut missing_meta = Vec::new();
+
+        for &root in paths {
+            let metadata = match self.io.metadata(root).await {
+                Ok(metadata) => metadata,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                    return Err(ExportUnityPackageErr::NotFound(root.into()))
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            // the root folder like Assets or Packages/com.anatawa12.gists doesn't have .meta file
+            if let Some(asset) = self.export_asset(root, metadata.is_dir()).await? {
+                assets.push(asset);
+            } else if metadata.is_file() {
+                missing_meta.push(root.into());
+            }
+
+            if !metadata.is_dir() {
+                continue;
+            }
+
+            let mut stream = std::pin::pin!(walk_dir_relative(&self.io, [root.to_owned()])
+                .filter(|x| future::ready(!is_ignored(x))));
+
+            while let Some(relative) = stream.next().await {
+                if relative.extension() == Some(OsStr::new("meta")) {
+                    continue;
+                }
+                let is_dir = self.io.is_dir(&relative).await;
+                match self.export_asset(&relative, is_dir).await? {
+                    Some(asset) => assets.push(asset),
+                    None => missing_meta.push(relative.into_boxed_path()),
+                }
+            }
+        }
+
+        if !missing_meta.is_empty() {
+            missing_meta.sort();
+            return Err(ExportUnityPackageErr::MissingMeta(missing_meta));
+        }
+
+        // the same asset may be collected multiple times if paths overlap
+        assets.sort_by(|a, b| a.path().cmp(b.path()));
+        assets.dedup_by(|a, b| a.path() == b.path());
+
+        Ok(assets)
+    }
+
+    /// Collects the assets of the package installed in `Packages` folder.
+    ///
+    /// The assets are exported to be imported into `destination` like `Assets/MyTool`.
+    /// Use `Packages/<name>` to import the package as an embedded package.
+    pub async fn export_package_unitypackage(
+        &self,
+        name: &str,
+        destination: &Path,
+    ) -> Result<UnityPackage, ExportUnityPackageErr> {
+        // unity imports assets only in Assets and Packages
+        if !(destination.starts_with("Assets") || destination.starts_with("Packages"))
+            || !is_complete_relative(destination)
+        {
+            return Err(ExportUnityPackageErr::BadDestination(destination.into()));
+        }
+        let destination = to_pathname(destination)?;
+
+        let installed = self.get_installed_package(name).is_some()
+            || (self.unlocked_packages())
+                .iter()
+                .any(|(dir, json)| dir.as_ref() == name && json.is_some());
+        if !installed {
+            return Err(ExportUnityPackageErr::NotInstalled(name.into()));
+        }
+
+        let path = PathBuf::from("Packages").join(name);
+        let prefix = to_pathname(&path)?;
+        let assets = (self.collect_export_assets(&[&path]).await?)
+            .into_iter()
+            .map(|asset| {
+                let relative = &asset.path()[prefix.len()..];
+                let path = format!("{destination}{relative}");
+                asset.with_path(path)
+            })
+            .collect();
+
+        Ok(UnityPackage::new(assets))
+    }
+
+    async fn export_asset(
+        &self,
+        path: &Path,
+        is_dir: bool,
+    ) -> io::Result<Option<UnityPackageAsset>> {
+        let mut meta_path = path.as_os_str().to_owned();
+        meta_path.push(".meta");
+        let meta_path = PathBuf::from(meta_path);
+
+        let Some(guid) = try_parse_meta(&self.io, &meta_path).await else {
+            return Ok(None);
+        };
+
+        let meta = self.read_file(&meta_path).await?;
+        let asset = if is_dir {
+            None
+        } else {
+            Some(self.read_file(path).await?)
+        };
+
+        Ok(Some(UnityPackageAsset::new(
+            guid.to_string(),
+            to_pathname(path)?,
+            asset,
+            Some(meta),
+        )))
+    }
+
+    async fn read_file(&self, path: &Path) -> io::Result<Vec<u8>> {
+        let mut file = self.io.open(path).await?;
+        let mut buffer = Vec::new();
+        file.read_to_end(&mut buffer).await?;
+        Ok(buffer)
+    }
+}
+
+/// Unity ignores hidden files and folders and folders ending with `~`
+fn is_ignored(path: &Path) -> bool {
+    (path.components()).any(|x| match x {
+        Component::Normal(name) => {
+            let name = name.to_string_lossy();
+            name.starts_with('.') || name.ends_with('~')
+        }
+        Component::Prefix(_) | Component::RootDir | Component::CurDir | Component::ParentDir => {
+            false
+        }
+    })
+}
+
+fn to_pathname(path: &Path) -> io::Result<String> {
+    let mut pathname = String::new();
+    for component in path.components() {
+        let Component::Normal(name) = component else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("bad asset path: {}", path.display()),
+            ));
+        };
+        let name = name.to_str().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("asset path is not utf8: {}", path.display()),
+            )
+        })?;
+        if !pathname.is_empty() {
+            pathname.push('/');
+        }
+        pathname.push_str(name);
+    }
+    Ok(pathname)
+}
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ExportUnityPackageErr {
+    Io(io::Error),
+    NotFound(Box<Path>),
+    NotInstalled(Box<str>),
+    MissingMeta(Vec<Box<Path>>),
+    BadDestination(Box<Path>),
+}
+
+impl fmt::Display for ExportUnityPackageErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use ExportUnityPackageErr::*;
+        match self {
+            Io(ioerr) => fmt::Display::fmt(ioerr, f),
+            NotFound(path) => write!(f, "{} not found", path.display()),
+            NotInstalled(name) => write!(f, "package {name} is not installed"),
+            MissingMeta(paths) => {
+                f.write_str("the following assets don't have .meta files: ")?;
+                let mut iter = paths.iter();
+                write!(f, "{}", iter.next().unwrap().display())?;
+                for path in iter {
+                    write!(f, ", {}", path.display())?;
+                }
+                Ok(())
+            }
+            BadDestination(path) => write!(
+                f,
+                "{} is not a folder in Assets or Packages",
+                path.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ExportUnityPackageErr {}
+
+impl From<io::Error> for ExportUnityPackageErr {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
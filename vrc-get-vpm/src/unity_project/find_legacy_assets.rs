@@ -55,6 +55,143 @@ pub(crate) async fn collect_legacy_assets<'a>(
     }
 }
 
+/// The asset with GUID found in legacy projects or `.unitypackage` files.
+///
+/// This is used to generate `legacyFolders` and `legacyFiles` of `package.json`.
+#[derive(Debug, Clone)]
+pub struct LegacyAssetInfo {
+    path: Box<str>,
+    guid: Box<str>,
+    is_folder: bool,
+}
+
+impl LegacyAssetInfo {
+    pub(crate) fn new(
+        path: impl Into<Box<str>>,
+        guid: impl Into<Box<str>>,
+        is_folder: bool,
+    ) -> Self {
+        Self {
+            path: path.into(),
+            guid: guid.into(),
+            is_folder,
+        }
+    }
+
+    /// The path relative to the project root separated with `/`.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn guid(&self) -> &str {
+        &self.guid
+    }
+
+    pub fn is_folder(&self) -> bool {
+        self.is_folder
+    }
+
+    /// Returns true if the asset can be found by the path when migrating projects.
+    ///
+    /// Assets outside `Assets` and `Packages` can only be found by GUID.
+    pub fn is_valid_path(&self) -> bool {
+        valid_path(Path::new(self.path.as_ref()))
+    }
+}
+
+/// Collects the assets with `.meta` files in the folder of the project including the folder itself.
+///
+/// Assets without `.meta` files are skipped.
+pub async fn scan_legacy_assets(io: &impl ProjectIo, folder: &Path) -> Vec<LegacyAssetInfo> {
+    async fn get_info(io: &impl ProjectIo, relative: PathBuf) -> Option<LegacyAssetInfo> {
+        if relative.extension() == Some(OsStr::new("meta")) {
+            return None;
+        }
+        let mut meta = relative.as_os_str().to_owned();
+        meta.push(".meta");
+        let guid = try_parse_meta(io, Path::new(&meta)).await?;
+        let is_folder = io.is_dir(&relative).await;
+        let path = (relative.components())
+            .map(|x| x.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        Some(LegacyAssetInfo::new(path, guid.to_string(), is_folder))
+    }
+
+    let paths = stream::once(future::ready(folder.to_owned()))
+        .chain(walk_dir_relative(io, [folder.to_owned()]));
+    let mut assets = paths
+        .filter_map(|x| get_info(io, x))
+        .collect::<Vec<_>>()
+        .await;
+    assets.sort_by(|a, b| a.path.cmp(&b.path));
+    assets
+}
+
+/// The folders unity gives special meanings to. Those folders are commonly shared by packages.
+const SHARED_FOLDERS: &[&str] = &[
+    "Assets",
+    "Assets/Editor",
+    "Assets/Editor Default Resources",
+    "Assets/Gizmos",
+    "Assets/Plugins",
+    "Assets/Resources",
+    "Assets/StreamingAssets",
+    "Packages",
+];
+
+/// Returns the assets not in the other folders in the list.
+///
+/// Removing those folders and files removes all the assets in the list.
+/// The folders shared by packages like `Assets/Plugins` are never returned;
+/// the assets in them are returned instead.
+pub fn top_level_legacy_assets(assets: &[LegacyAssetInfo]) -> Vec<&LegacyAssetInfo> {
+    let folders = (assets.iter())
+        .filter(|x| x.is_folder && !SHARED_FOLDERS.contains(&x.path()))
+        .map(|x| x.path())
+        .collect::<std::collections::HashSet<_>>();
+
+    (assets.iter())
+        .filter(|asset| !(asset.is_folder && SHARED_FOLDERS.contains(&asset.path())))
+        .filter(|asset| {
+            let mut path = asset.path();
+            while let Some((parent, _)) = path.rsplit_once('/') {
+                if folders.contains(parent) {
+                    return false;
+                }
+                path = parent;
+            }
+            true
+        })
+        .collect()
+}
+
+/// Returns true if the path can be found as a legacy asset of the package.
+///
+/// The path must be declared by the package, or be in `Assets` if the package
+/// declares legacy assets with GUID since those can be found anywhere in `Assets`.
+pub(super) fn is_legacy_asset_of(package: PackageInfo, path: &Path, is_file: bool) -> bool {
+    if !valid_path(path) {
+        return false;
+    }
+
+    let json = package.package_json();
+    let declared = if is_file {
+        json.legacy_files()
+    } else {
+        json.legacy_folders()
+    };
+
+    let declared_path = declared
+        .keys()
+        .any(|declared| Path::new(&declared.replace('\\', "/")) == path);
+    let found_by_guid = path.starts_with("Assets")
+        && path != Path::new("Assets")
+        && (declared.values()).any(|guid| guid.as_deref().and_then(Guid::parse).is_some());
+
+    declared_path || found_by_guid
+}
+
 fn valid_path(path: &Path) -> bool {
     // removing folders other than Assets and Packages are not allowed.
     if !path.starts_with("Assets") && !path.starts_with("Packages") {
@@ -132,7 +269,7 @@ async fn find_legacy_assets_by_path<'a>(
     (found_files, found_folders, find_guids)
 }
 
-async fn try_parse_meta(io: &impl ProjectIo, path: &Path) -> Option<Guid> {
+pub(super) async fn try_parse_meta(io: &impl ProjectIo, path: &Path) -> Option<Guid> {
     let mut file = BufReader::new(io.open(path).await.ok()?);
     let mut buffer = String::new();
     while file.read_line(&mut buffer).await.ok()? != 0 {
@@ -218,10 +355,16 @@ enum LegacySearchResult<'a> {
 }
 
 #[derive(Copy, Clone, Hash, Eq, PartialEq)]
-struct Guid([u8; 16]);
+pub(super) struct Guid([u8; 16]);
 
 impl Guid {
-    fn parse(guid: &str) -> Option<Guid> {
+    pub(super) fn parse(guid: &str) -> Option<Guid> {
         FromHex::from_hex(guid).ok().map(Guid)
     }
 }
+
+impl std::fmt::Display for Guid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&hex::encode(self.0))
+    }
+}
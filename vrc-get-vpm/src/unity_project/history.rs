@@ -0,0 +1,245 @@
+use crate::io;
+use crate::io::{DirEntry, ProjectIo};
+use crate::unity_project::pending_project_changes::{Builder, RemoveReason};
+use crate::unity_project::vpm_manifest::VpmManifest;
+use crate::unity_project::{AddPackageErr, PendingProjectChanges};
+use crate::utils::{to_vec_pretty_os_eol, try_load_json, PathBufExt};
+use crate::version::{DependencyRange, Version};
+use crate::{PackageCollection, UnityProject, VersionSelector};
+use futures::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The folder in `Packages` to store the history journal.
+///
+/// This folder doesn't have `package.json` so unity and vrc-get don't treat this as a package.
+pub(crate) const HISTORY_FOLDER: &str = "vrc-get-history";
+
+fn history_folder() -> PathBuf {
+    PathBuf::from("Packages").joined(HISTORY_FOLDER)
+}
+
+/// The dependencies and locked packages in `vpm-manifest.json` at some point.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LockSnapshot {
+    dependencies: BTreeMap<Box<str>, DependencyRange>,
+    locked: BTreeMap<Box<str>, Version>,
+}
+
+impl LockSnapshot {
+    pub fn dependencies(&self) -> impl Iterator<Item = (&str, &DependencyRange)> {
+        self.dependencies
+            .iter()
+            .map(|(name, range)| (name.as_ref(), range))
+    }
+
+    pub fn locked(&self) -> impl Iterator<Item = (&str, &Version)> {
+        self.locked
+            .iter()
+            .map(|(name, version)| (name.as_ref(), version))
+    }
+
+    pub fn get_locked(&self, name: &str) -> Option<&Version> {
+        self.locked.get(name)
+    }
+
+    pub fn get_dependency(&self, name: &str) -> Option<&DependencyRange> {
+        self.dependencies.get(name)
+    }
+
+    /// Reads the snapshot from the contents of `vpm-manifest.json`.
+    pub fn from_vpm_manifest(json: &[u8]) -> serde_json::Result<Self> {
+        Ok(Self::from_manifest(&VpmManifest::from_slice(json)?))
+    }
+
+    fn from_manifest(manifest: &VpmManifest) -> Self {
+        Self {
+            dependencies: (manifest.dependencies())
+                .map(|(name, range)| (name.into(), range.clone()))
+                .collect(),
+            locked: (manifest.all_locked())
+                .map(|locked| (locked.name().into(), locked.version().clone()))
+                .collect(),
+        }
+    }
+}
+
+/// The entry of the history journal recorded for each applied [`PendingProjectChanges`].
+///
+/// The entries are saved in `Packages/vrc-get-history` by [`UnityProject::save`].
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEntry {
+    /// milliseconds since the unix epoch
+    time: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    reason: Option<Box<str>>,
+    before: LockSnapshot,
+    after: LockSnapshot,
+}
+
+impl HistoryEntry {
+    /// The time the changes are applied in milliseconds since the unix epoch.
+    pub fn time_millis(&self) -> u64 {
+        self.time
+    }
+
+    /// The reason of the changes, which is the command line for vrc-get.
+    pub fn reason(&self) -> Option<&str> {
+        self.reason.as_deref()
+    }
+
+    pub fn before(&self) -> &LockSnapshot {
+        &self.before
+    }
+
+    pub fn after(&self) -> &LockSnapshot {
+        &self.after
+    }
+}
+
+// recording history
+impl<IO: ProjectIo> UnityProject<IO> {
+    /// Sets the reason recorded to the history entries for the changes applied after this call.
+    pub fn set_history_reason(&mut self, reason: Option<Box<str>>) {
+        self.history_reason = reason;
+    }
+
+    /// The current dependencies and locked packages in `vpm-manifest.json`.
+    pub fn lock_snapshot(&self) -> LockSnapshot {
+        LockSnapshot::from_manifest(&self.manifest)
+    }
+
+    /// Records the history entry if the lock is changed from `before`.
+    pub(crate) fn record_history(&mut self, before: LockSnapshot) {
+        let after = self.lock_snapshot();
+        if before == after {
+            return;
+        }
+
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|x| x.as_millis() as u64)
+            .unwrap_or(0);
+
+        self.unsaved_history.push(HistoryEntry {
+            time,
+            reason: self.history_reason.clone(),
+            before,
+            after,
+        });
+    }
+
+    pub(crate) async fn save_history(&mut self) -> io::Result<()> {
+        if self.unsaved_history.is_empty() {
+            return Ok(());
+        }
+
+        let folder = history_folder();
+        self.io.create_dir_all(&folder).await?;
+
+        let mut next = (history_numbers(&self.io).await?.into_iter().max()).map_or(1, |x| x + 1);
+        for entry in &self.unsaved_history {
+            let path = folder.join(format!("{next:05}.json"));
+            self.io.write(&path, &to_vec_pretty_os_eol(entry)?).await?;
+            next += 1;
+        }
+
+        self.unsaved_history.clear();
+        Ok(())
+    }
+}
+
+/// Lists the numbers of the history entries in the history folder.
+async fn history_numbers(io: &impl ProjectIo) -> io::Result<Vec<u32>> {
+    let read_dir = match io.read_dir(&history_folder()).await {
+        Ok(read_dir) => read_dir,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e),
+    };
+
+    read_dir
+        .try_filter_map(|entry| async move {
+            let file_name = entry.file_name();
+            Ok((file_name.to_str())
+                .and_then(|x| x.strip_suffix(".json"))
+                .and_then(|x| x.parse::<u32>().ok()))
+        })
+        .try_collect()
+        .await
+}
+
+// reading and restoring history
+impl<IO: ProjectIo> UnityProject<IO> {
+    /// Reads the history journal of the project, from the oldest to the newest.
+    ///
+    /// The entries not saved yet are not included.
+    pub async fn history(&self) -> io::Result<Vec<HistoryEntry>> {
+        let mut numbers = history_numbers(&self.io).await?;
+        numbers.sort();
+
+        let folder = history_folder();
+        let mut entries = Vec::with_capacity(numbers.len());
+        for number in numbers {
+            let path = folder.join(format!("{number:05}.json"));
+            if let Some(entry) = try_load_json(&self.io, &path).await? {
+                entries.push(entry);
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Creates the changes to restore the dependencies and locked packages to the snapshot.
+    ///
+    /// The packages not in the snapshot are removed, and the packages with different versions are
+    /// installed at the version in the snapshot.
+    pub async fn restore_lock_request<'env>(
+        &self,
+        env: &'env impl PackageCollection,
+        snapshot: &LockSnapshot,
+    ) -> Result<PendingProjectChanges<'env>, AddPackageErr> {
+        let mut changes = Builder::new();
+
+        for (name, version) in snapshot.locked() {
+            let current = self.manifest.get_locked(name);
+            if current.map(|x| x.version()) == Some(version) {
+                continue;
+            }
+
+            let package = env
+                .find_package_by_name(name, VersionSelector::specific_version(version))
+                .ok_or_else(|| AddPackageErr::DependencyNotFound {
+                    dependency_name: name.into(),
+                })?;
+            changes.install_to_locked(package);
+        }
+
+        for (name, range) in snapshot.dependencies() {
+            if self.manifest.get_dependency(name) != Some(range) {
+                changes.add_to_dependencies(name.into(), range.clone());
+            }
+        }
+
+        for locked in self.manifest.all_locked() {
+            let in_snapshot = snapshot.locked.contains_key(locked.name())
+                || snapshot.dependencies.contains_key(locked.name());
+            if !in_snapshot {
+                changes.remove(locked.name().into(), RemoveReason::Requested);
+            }
+        }
+
+        // the packages not removed above may still be in the dependencies
+        for (name, _) in self.manifest.dependencies() {
+            let removed =
+                self.manifest.get_locked(name).is_some() && !snapshot.locked.contains_key(name);
+            if !snapshot.dependencies.contains_key(name) && !removed {
+                changes.remove_from_dependencies(name.into());
+            }
+        }
+
+        Ok(changes.build_resolve(self).await)
+    }
+}
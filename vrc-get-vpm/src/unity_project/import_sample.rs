@@ -0,0 +1,208 @@
+use crate::io;
+use crate::io::ProjectIo;
+use crate::utils::{is_complete_relative, walk_dir_relative};
+use crate::{PackageJson, PackageSample, UnityProject};
+use futures::prelude::*;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// The sample of the installed package with the folder it will be imported to.
+#[derive(Debug)]
+pub struct SampleInfo<'a> {
+    sample: &'a PackageSample,
+    import_path: PathBuf,
+    imported: bool,
+}
+
+impl<'a> SampleInfo<'a> {
+    pub fn sample(&self) -> &'a PackageSample {
+        self.sample
+    }
+
+    /// The folder the sample is imported to, relative to the project root.
+    pub fn import_path(&self) -> &Path {
+        &self.import_path
+    }
+
+    /// Returns true if the folder the sample is imported to exists.
+    pub fn is_imported(&self) -> bool {
+        self.imported
+    }
+}
+
+// importing samples
+impl<IO: ProjectIo> UnityProject<IO> {
+    /// Lists the samples in `samples` of the installed package.
+    pub async fn list_samples(
+        &self,
+        package: &str,
+    ) -> Result<Vec<SampleInfo<'_>>, ImportSampleErr> {
+        let (_, json) = self.find_package_with_dir(package)?;
+
+        let mut samples = Vec::with_capacity(json.samples().len());
+        for sample in json.samples() {
+            let import_path = sample_import_path(json, sample);
+            let imported = self.io.is_dir(&import_path).await;
+            samples.push(SampleInfo {
+                sample,
+                import_path,
+                imported,
+            });
+        }
+        Ok(samples)
+    }
+
+    /// Copies the sample of the installed package to the `Assets` folder.
+    ///
+    /// Like the Package Manager window of unity, the sample is copied to
+    /// `Assets/Samples/<package displayName>/<version>/<sample displayName>`.
+    /// The sample is looked up by the `displayName` or the name of the folder.
+    ///
+    /// If the sample is already imported, the folder is replaced with `overwrite`,
+    /// or an error is returned otherwise.
+    /// Returns the folder the sample is imported to.
+    pub async fn import_sample(
+        &self,
+        package: &str,
+        sample: &str,
+        overwrite: bool,
+    ) -> Result<PathBuf, ImportSampleErr> {
+        let (dir, json) = self.find_package_with_dir(package)?;
+
+        let found = (json.samples().iter())
+            .find(|x| x.display_name() == sample)
+            .or_else(|| {
+                (json.samples().iter()).find(|x| {
+                    let path = Path::new(x.path());
+                    path.file_name().and_then(|x| x.to_str()) == Some(sample)
+                })
+            })
+            .ok_or_else(|| ImportSampleErr::SampleNotFound {
+                package: package.into(),
+                sample: sample.into(),
+            })?;
+
+        let sample_path = Path::new(found.path());
+        if found.path().is_empty() || !is_complete_relative(sample_path) {
+            return Err(ImportSampleErr::BadSamplePath(found.path().into()));
+        }
+
+        let source = PathBuf::from("Packages").join(dir).join(sample_path);
+        if !self.io.is_dir(&source).await {
+            return Err(ImportSampleErr::SampleFolderNotFound(source.into()));
+        }
+
+        let destination = sample_import_path(json, found);
+        if self.io.is_dir(&destination).await {
+            if !overwrite {
+                return Err(ImportSampleErr::AlreadyImported(destination.into()));
+            }
+            self.io.remove_dir_all(&destination).await?;
+        }
+
+        self.io.create_dir_all(&destination).await?;
+
+        let mut stream = std::pin::pin!(walk_dir_relative(&self.io, [source.clone()]));
+        while let Some(path) = stream.next().await {
+            let relative = path
+                .strip_prefix(&source)
+                .expect("walking in the sample folder");
+            let target = destination.join(relative);
+            if self.io.is_dir(&path).await {
+                self.io.create_dir_all(&target).await?;
+            } else {
+                let mut file = self.io.open(&path).await?;
+                let mut buffer = Vec::new();
+                file.read_to_end(&mut buffer).await?;
+                // the file may be found before the parent folder is created
+                if let Some(parent) = target.parent() {
+                    self.io.create_dir_all(parent).await?;
+                }
+                self.io.write(&target, &buffer).await?;
+            }
+        }
+
+        Ok(destination)
+    }
+
+    /// Finds the installed package with the name of the folder in `Packages`.
+    fn find_package_with_dir(&self, name: &str) -> Result<(&str, &PackageJson), ImportSampleErr> {
+        if let Some((dir, json)) = self.installed_packages.get_key_value(name) {
+            return Ok((dir.as_ref(), json));
+        }
+
+        (self.unlocked_packages().iter())
+            .find_map(|(dir, json)| {
+                json.as_ref()
+                    .filter(|json| json.name() == name)
+                    .map(|json| (dir.as_ref(), json))
+            })
+            .ok_or_else(|| ImportSampleErr::NotInstalled(name.into()))
+    }
+}
+
+/// The folder the sample is imported to, relative to the project root.
+fn sample_import_path(package: &PackageJson, sample: &PackageSample) -> PathBuf {
+    let package_name = package.display_name().unwrap_or(package.name());
+    PathBuf::from("Assets")
+        .join("Samples")
+        .join(sanitize_file_name(package_name))
+        .join(package.version().to_string())
+        .join(sanitize_file_name(sample.display_name()))
+}
+
+/// Removes the characters not allowed in file names like unity does.
+///
+/// The empty name and the names only with dots like `..` are replaced with `_`
+/// so that the folder never points outside the samples folder.
+fn sanitize_file_name(name: &str) -> String {
+    let name = name
+        .chars()
+        .filter(|&c| !c.is_control() && !r#"<>:"/\|?*"#.contains(c))
+        .collect::<String>();
+    let name = name.trim();
+    if name.chars().all(|c| c == '.') {
+        "_".to_owned()
+    } else {
+        name.to_owned()
+    }
+}
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ImportSampleErr {
+    Io(io::Error),
+    NotInstalled(Box<str>),
+    SampleNotFound { package: Box<str>, sample: Box<str> },
+    BadSamplePath(Box<str>),
+    SampleFolderNotFound(Box<Path>),
+    AlreadyImported(Box<Path>),
+}
+
+impl fmt::Display for ImportSampleErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use ImportSampleErr::*;
+        match self {
+            Io(ioerr) => fmt::Display::fmt(ioerr, f),
+            NotInstalled(name) => write!(f, "package {name} is not installed"),
+            SampleNotFound { package, sample } => {
+                write!(f, "sample {sample} not found in package {package}")
+            }
+            BadSamplePath(path) => write!(f, "bad path of the sample: {path}"),
+            SampleFolderNotFound(path) => {
+                write!(f, "the folder of the sample not found: {}", path.display())
+            }
+            AlreadyImported(path) => {
+                write!(f, "the sample is already imported to {}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ImportSampleErr {}
+
+impl From<io::Error> for ImportSampleErr {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
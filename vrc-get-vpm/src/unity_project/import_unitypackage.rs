@@ -0,0 +1,404 @@
+use crate::io;
+use crate::io::{BufReader, ProjectIo};
+use crate::unity_project::find_legacy_assets::{try_parse_meta, Guid, LegacyAssetInfo};
+use crate::utils::{is_complete_relative, walk_dir_relative, TarReader, TarWriter};
+use crate::UnityProject;
+use async_compression::futures::bufread::GzipDecoder;
+use async_compression::futures::write::GzipEncoder;
+use futures::prelude::*;
+use indexmap::IndexMap;
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::pin::pin;
+
+/// The contents of `.unitypackage` file.
+///
+/// `.unitypackage` is a gzip-compressed tar archive with `<guid>/asset`, `<guid>/asset.meta`,
+/// and `<guid>/pathname` for each asset. Folders don't have `<guid>/asset`.
+#[derive(Debug)]
+pub struct UnityPackage {
+    assets: Vec<UnityPackageAsset>,
+}
+
+#[derive(Debug)]
+pub struct UnityPackageAsset {
+    guid: Box<str>,
+    path: Box<str>,
+    asset: Option<Vec<u8>>,
+    meta: Option<Vec<u8>>,
+}
+
+impl UnityPackage {
+    /// Reads `.unitypackage` file.
+    ///
+    /// The whole contents of the archive are loaded into memory.
+    pub async fn read(reader: impl io::AsyncRead + Unpin) -> io::Result<Self> {
+        #[derive(Default)]
+        struct Partial {
+            path: Option<Box<str>>,
+            asset: Option<Vec<u8>>,
+            meta: Option<Vec<u8>>,
+        }
+
+        let mut reader = TarReader::new(GzipDecoder::new(BufReader::new(reader)));
+        let mut partials = IndexMap::<Box<str>, Partial>::new();
+
+        while let Some(entry) = reader.next_entry().await? {
+            if entry.is_dir {
+                continue;
+            }
+            let path = entry.path.strip_prefix("./").unwrap_or(&entry.path);
+            let Some((guid, file)) = path.split_once('/') else {
+                continue;
+            };
+
+            let partial = partials.entry(guid.into()).or_default();
+            match file {
+                "asset" => partial.asset = Some(entry.content),
+                "asset.meta" => partial.meta = Some(entry.content),
+                "pathname" => {
+                    let pathname = std::str::from_utf8(&entry.content).map_err(|_| {
+                        io::Error::new(io::ErrorKind::InvalidData, "pathname is not utf8")
+                    })?;
+                    // pathname may have additional lines after the path
+                    let pathname = pathname.lines().next().unwrap_or_default().trim();
+                    partial.path = Some(pathname.trim_end_matches('/').into());
+                }
+                // preview.png and other files are not needed
+                _ => {}
+            }
+        }
+
+        let mut assets = Vec::with_capacity(partials.len());
+
+        for (guid, partial) in partials {
+            let Some(path) = partial.path else {
+                log::warn!("asset {guid} in unitypackage has no pathname");
+                continue;
+            };
+
+            if Guid::parse(&guid).is_none() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("bad guid in unitypackage: {guid}"),
+                ));
+            }
+
+            if path.is_empty() || !is_complete_relative(Path::new(path.as_ref())) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("directory traversal detected: {path}"),
+                ));
+            }
+
+            assets.push(UnityPackageAsset {
+                guid,
+                path,
+                asset: partial.asset,
+                meta: partial.meta,
+            });
+        }
+
+        Ok(Self::new(assets))
+    }
+
+    pub(crate) fn new(mut assets: Vec<UnityPackageAsset>) -> Self {
+        assets.sort_by(|a, b| a.path.cmp(&b.path));
+        Self { assets }
+    }
+
+    pub fn assets(&self) -> &[UnityPackageAsset] {
+        &self.assets
+    }
+
+    /// Returns the assets as legacy assets to generate `legacyFolders` and `legacyFiles`.
+    pub fn legacy_assets(&self) -> Vec<LegacyAssetInfo> {
+        (self.assets.iter())
+            .map(|x| LegacyAssetInfo::new(x.path(), x.guid(), x.is_folder()))
+            .collect()
+    }
+
+    /// Writes `.unitypackage` file.
+    pub async fn write(&self, writer: impl io::AsyncWrite + Unpin) -> io::Result<()> {
+        let mut writer = TarWriter::new(GzipEncoder::new(writer));
+
+        for asset in &self.assets {
+            let guid = asset.guid();
+            writer.write_dir(guid).await?;
+            if let Some(content) = asset.asset() {
+                (writer.write_file(&format!("{guid}/asset"), content)).await?;
+            }
+            if let Some(meta) = asset.meta() {
+                (writer.write_file(&format!("{guid}/asset.meta"), meta)).await?;
+            }
+            (writer.write_file(&format!("{guid}/pathname"), asset.path().as_bytes())).await?;
+        }
+
+        let mut encoder = writer.finish().await?;
+        encoder.close().await
+    }
+}
+
+impl UnityPackageAsset {
+    /// Creates the asset. `asset` is `None` for folders.
+    pub(crate) fn new(
+        guid: impl Into<Box<str>>,
+        path: impl Into<Box<str>>,
+        asset: Option<Vec<u8>>,
+        meta: Option<Vec<u8>>,
+    ) -> Self {
+        Self {
+            guid: guid.into(),
+            path: path.into(),
+            asset,
+            meta,
+        }
+    }
+
+    /// Moves the asset to the path.
+    pub(crate) fn with_path(self, path: impl Into<Box<str>>) -> Self {
+        Self {
+            path: path.into(),
+            ..self
+        }
+    }
+
+    pub fn guid(&self) -> &str {
+        &self.guid
+    }
+
+    /// The path of the asset relative to the project root like `Assets/Folder/Asset.prefab`.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn is_folder(&self) -> bool {
+        self.asset.is_none()
+    }
+
+    pub fn asset(&self) -> Option<&[u8]> {
+        self.asset.as_deref()
+    }
+
+    pub fn meta(&self) -> Option<&[u8]> {
+        self.meta.as_deref()
+    }
+}
+
+/// The filter of assets to be imported.
+///
+/// Each filter is a path of the asset like `Assets/Folder`, which matches the asset and
+/// assets in the folder. Without include filters, all assets are included.
+/// Exclude filters have priority over include filters.
+#[derive(Debug, Default, Clone)]
+pub struct ImportFilter {
+    include: Vec<Box<str>>,
+    exclude: Vec<Box<str>>,
+}
+
+impl ImportFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn include(mut self, path: impl AsRef<str>) -> Self {
+        self.include.push(normalize_filter(path.as_ref()));
+        self
+    }
+
+    pub fn exclude(mut self, path: impl AsRef<str>) -> Self {
+        self.exclude.push(normalize_filter(path.as_ref()));
+        self
+    }
+
+    fn matches(&self, path: &str) -> bool {
+        fn matches_filter(filter: &str, path: &str) -> bool {
+            match path.strip_prefix(filter) {
+                Some(rest) => rest.is_empty() || rest.starts_with('/'),
+                None => false,
+            }
+        }
+
+        if self.exclude.iter().any(|x| matches_filter(x, path)) {
+            return false;
+        }
+
+        self.include.is_empty() || self.include.iter().any(|x| matches_filter(x, path))
+    }
+}
+
+fn normalize_filter(path: &str) -> Box<str> {
+    path.replace('\\', "/").trim_end_matches('/').into()
+}
+
+/// The assets to be imported from [`UnityPackage`].
+#[derive(Debug)]
+pub struct PendingImport<'a> {
+    assets: Vec<ImportAsset<'a>>,
+}
+
+#[derive(Debug)]
+pub struct ImportAsset<'a> {
+    asset: &'a UnityPackageAsset,
+    status: ImportAssetStatus,
+}
+
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum ImportAssetStatus {
+    /// The asset does not exist in the project.
+    New,
+    /// The asset exists in the project with the same GUID and will be overwritten.
+    Update,
+    /// Another asset in the project has the same GUID. The asset will not be imported.
+    GuidConflict(Box<Path>),
+    /// Another asset with different GUID exists at the path. The asset will not be imported.
+    PathConflict,
+    /// The asset is not in the `Assets` folder. The asset will not be imported.
+    OutsideAssets,
+}
+
+impl ImportAssetStatus {
+    pub fn will_import(&self) -> bool {
+        match self {
+            ImportAssetStatus::New | ImportAssetStatus::Update => true,
+            ImportAssetStatus::GuidConflict(_)
+            | ImportAssetStatus::PathConflict
+            | ImportAssetStatus::OutsideAssets => false,
+        }
+    }
+}
+
+impl<'a> PendingImport<'a> {
+    pub fn assets(&self) -> &[ImportAsset<'a>] {
+        &self.assets
+    }
+
+    pub fn has_conflicts(&self) -> bool {
+        self.assets.iter().any(|x| !x.status.will_import())
+    }
+}
+
+impl<'a> ImportAsset<'a> {
+    pub fn asset(&self) -> &'a UnityPackageAsset {
+        self.asset
+    }
+
+    pub fn status(&self) -> &ImportAssetStatus {
+        &self.status
+    }
+}
+
+// importing unitypackage
+impl<IO: ProjectIo> UnityProject<IO> {
+    /// Collects the assets to be imported from the unitypackage and checks for the conflicts with
+    /// existing assets in the project.
+    ///
+    /// Folders containing the filtered assets are always included so that the GUIDs of the
+    /// folders are preserved.
+    pub async fn import_unitypackage_request<'a>(
+        &self,
+        package: &'a UnityPackage,
+        filter: &ImportFilter,
+    ) -> io::Result<PendingImport<'a>> {
+        let mut included = HashSet::new();
+        for asset in package.assets() {
+            if filter.matches(asset.path()) {
+                included.insert(asset.path());
+                let mut path = asset.path();
+                while let Some((parent, _)) = path.rsplit_once('/') {
+                    included.insert(parent);
+                    path = parent;
+                }
+            }
+        }
+
+        let existing = self.collect_asset_guids().await;
+        let guid_to_path = (existing.iter())
+            .map(|(path, guid)| (*guid, path.as_path()))
+            .collect::<HashMap<_, _>>();
+
+        let mut assets = Vec::new();
+
+        for asset in package.assets() {
+            if !included.contains(asset.path()) {
+                continue;
+            }
+
+            let path = Path::new(asset.path());
+            let guid = Guid::parse(asset.guid()).expect("verified on read");
+
+            let status = if !path.starts_with("Assets") || path == Path::new("Assets") {
+                ImportAssetStatus::OutsideAssets
+            } else if let Some(existing_guid) = existing.get(path) {
+                if *existing_guid == guid {
+                    ImportAssetStatus::Update
+                } else {
+                    ImportAssetStatus::PathConflict
+                }
+            } else if let Some(existing_path) = guid_to_path.get(&guid) {
+                ImportAssetStatus::GuidConflict((*existing_path).into())
+            } else if self.io.metadata(path).await.is_ok() {
+                // the asset exists without .meta file so unity will use our meta file
+                ImportAssetStatus::Update
+            } else {
+                ImportAssetStatus::New
+            };
+
+            assets.push(ImportAsset { asset, status });
+        }
+
+        Ok(PendingImport { assets })
+    }
+
+    /// Writes the assets to the project. Assets with conflicts are skipped.
+    pub async fn import_unitypackage(&self, import: &PendingImport<'_>) -> io::Result<()> {
+        for import in &import.assets {
+            if !import.status.will_import() {
+                continue;
+            }
+
+            let asset = import.asset;
+            let path = Path::new(asset.path());
+
+            if let Some(content) = asset.asset() {
+                if let Some(parent) = path.parent() {
+                    self.io.create_dir_all(parent).await?;
+                }
+                self.io.write(path, content).await?;
+            } else {
+                self.io.create_dir_all(path).await?;
+            }
+
+            if let Some(meta) = asset.meta() {
+                let mut meta_path = path.as_os_str().to_owned();
+                meta_path.push(".meta");
+                self.io.write(Path::new(&meta_path), meta).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn collect_asset_guids(&self) -> HashMap<PathBuf, Guid> {
+        async fn get_guid<IO: ProjectIo>(io: &IO, relative: PathBuf) -> Option<(PathBuf, Guid)> {
+            if relative.extension() != Some(OsStr::new("meta")) {
+                return None;
+            }
+            let guid = try_parse_meta(io, &relative).await?;
+            let mut path = relative;
+            path.set_extension("");
+            Some((path, guid))
+        }
+
+        let mut stream = pin!(walk_dir_relative(&self.io, [PathBuf::from("Assets")])
+            .filter_map(|x| get_guid(&self.io, x)));
+
+        let mut guids = HashMap::new();
+        while let Some((path, guid)) = stream.next().await {
+            guids.insert(path, guid);
+        }
+        guids
+    }
+}
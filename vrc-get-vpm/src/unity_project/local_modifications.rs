@@ -0,0 +1,269 @@
+use crate::io;
+use crate::io::{IoTrait, ProjectIo};
+use crate::traits::EnvironmentIoHolder;
+use crate::unity_project::package_patch::write_file_diff;
+use crate::unity_project::pending_project_changes::PackageChange;
+use crate::unity_project::PendingProjectChanges;
+use crate::utils::{
+    read_zip_files, to_vec_pretty_os_eol, try_load_json, walk_dir_relative, PathBufExt,
+    Sha256AsyncWrite,
+};
+use crate::version::Version;
+use crate::{
+    PackageCollection, PackageInfo, PackageInfoInner, RemotePackageDownloader, UnityProject,
+    VersionSelector,
+};
+use futures::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Component, Path, PathBuf};
+use std::pin::pin;
+
+/// The name of the file in the package folder to record the hashes of the extracted files.
+///
+/// Unity ignores hidden files so this file is not imported as an asset.
+pub(crate) const FILE_HASHES_NAME: &str = ".vrc-get-hashes.json";
+
+#[derive(Serialize, Deserialize)]
+struct FileHashes {
+    version: Version,
+    /// The sha256 of the files keyed by the path relative to the package folder with `/` separator.
+    files: BTreeMap<Box<str>, Box<str>>,
+}
+
+/// The local modifications to the files of the installed package.
+///
+/// Paths are relative to the package folder with `/` separator.
+#[derive(Debug, Clone)]
+pub struct PackageModifications {
+    name: Box<str>,
+    version: Version,
+    modified: Vec<Box<str>>,
+    added: Vec<Box<str>>,
+    deleted: Vec<Box<str>>,
+}
+
+impl PackageModifications {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The version of the package when the files were extracted.
+    pub fn version(&self) -> &Version {
+        &self.version
+    }
+
+    pub fn modified(&self) -> &[Box<str>] {
+        &self.modified
+    }
+
+    pub fn added(&self) -> &[Box<str>] {
+        &self.added
+    }
+
+    pub fn deleted(&self) -> &[Box<str>] {
+        &self.deleted
+    }
+
+    pub fn is_modified(&self) -> bool {
+        !self.modified.is_empty() || !self.added.is_empty() || !self.deleted.is_empty()
+    }
+}
+
+/// Records the hashes of the files in the package folder just after the package is extracted.
+pub(crate) async fn record_file_hashes(
+    io: &impl ProjectIo,
+    package_folder: &Path,
+    version: &Version,
+) -> io::Result<()> {
+    let hashes = FileHashes {
+        version: version.clone(),
+        files: hash_files(io, package_folder).await?,
+    };
+    io.write(
+        &package_folder.join(FILE_HASHES_NAME),
+        &to_vec_pretty_os_eol(&hashes)?,
+    )
+    .await
+}
+
+async fn hash_files(io: &impl IoTrait, folder: &Path) -> io::Result<BTreeMap<Box<str>, Box<str>>> {
+    let mut files = BTreeMap::new();
+    let mut stream = pin!(walk_dir_relative(io, [folder.to_owned()]));
+    while let Some(path) = stream.next().await {
+        if !io.is_file(&path).await {
+            continue;
+        }
+        let relative = to_relative_name(folder, &path);
+        if relative.as_ref() == FILE_HASHES_NAME {
+            continue;
+        }
+
+        let mut file = io.open(&path).await?;
+        let mut hasher = Sha256AsyncWrite::new(io::sink());
+        io::copy(&mut file, &mut hasher).await?;
+        let (_, hash) = hasher.finalize();
+
+        files.insert(relative, hex::encode(hash).into_boxed_str());
+    }
+    Ok(files)
+}
+
+fn to_relative_name(folder: &Path, path: &Path) -> Box<str> {
+    let relative = path.strip_prefix(folder).unwrap_or(path);
+    let mut name = String::new();
+    for component in relative.components() {
+        if let Component::Normal(component) = component {
+            if !name.is_empty() {
+                name.push('/');
+            }
+            name.push_str(&component.to_string_lossy());
+        }
+    }
+    name.into_boxed_str()
+}
+
+/// Reads the files of the package as extracted by vrc-get.
+async fn read_pristine_files<Env: RemotePackageDownloader + EnvironmentIoHolder>(
+    env: &Env,
+    package: PackageInfo<'_>,
+) -> io::Result<HashMap<Box<str>, Vec<u8>>> {
+    match package.inner {
+        PackageInfoInner::Remote(package, user_repo) => {
+            let zip_file = env.get_package(user_repo, package).await?;
+            Ok(read_zip_files(zip_file)
+                .await?
+                .into_iter()
+                .map(|(path, content)| {
+                    (to_relative_name(Path::new(""), Path::new(&*path)), content)
+                })
+                .collect())
+        }
+        PackageInfoInner::Local(_, path) => read_files(env.io(), path).await,
+    }
+}
+
+/// Reads all files in the folder keyed by the path relative to the folder.
+async fn read_files(io: &impl IoTrait, folder: &Path) -> io::Result<HashMap<Box<str>, Vec<u8>>> {
+    let mut files = HashMap::new();
+    let mut stream = pin!(walk_dir_relative(io, [folder.to_owned()]));
+    while let Some(file) = stream.next().await {
+        if !io.is_file(&file).await {
+            continue;
+        }
+        files.insert(to_relative_name(folder, &file), read_file(io, &file).await?);
+    }
+    Ok(files)
+}
+
+pub(super) async fn read_file(io: &impl IoTrait, path: &Path) -> io::Result<Vec<u8>> {
+    let mut file = io.open(path).await?;
+    let mut buffer = Vec::new();
+    file.read_to_end(&mut buffer).await?;
+    Ok(buffer)
+}
+
+// detecting local modifications
+impl<IO: ProjectIo> UnityProject<IO> {
+    /// Compares the files in the package folder with the hashes recorded on install.
+    ///
+    /// Returns `None` if the package is not installed or installed without recording hashes.
+    pub async fn package_modifications(
+        &self,
+        name: &str,
+    ) -> io::Result<Option<PackageModifications>> {
+        let folder = PathBuf::from("Packages").joined(name);
+        let Some(recorded) =
+            try_load_json::<FileHashes>(&self.io, &folder.join(FILE_HASHES_NAME)).await?
+        else {
+            return Ok(None);
+        };
+        let mut current = hash_files(&self.io, &folder).await?;
+
+        let mut modified = Vec::new();
+        let mut deleted = Vec::new();
+        for (path, hash) in recorded.files {
+            match current.remove(&path) {
+                None => deleted.push(path),
+                Some(current) if current != hash => modified.push(path),
+                Some(_) => {}
+            }
+        }
+        let added = current.into_keys().collect();
+
+        Ok(Some(PackageModifications {
+            name: name.into(),
+            version: recorded.version,
+            modified,
+            added,
+            deleted,
+        }))
+    }
+
+    /// Returns the locally modified packages which will be overwritten or removed by the changes.
+    pub async fn overwritten_modifications(
+        &self,
+        changes: &PendingProjectChanges<'_>,
+    ) -> io::Result<Vec<PackageModifications>> {
+        let mut names = (changes.package_changes().iter())
+            .filter(|(_, change)| match change {
+                PackageChange::Install(install) => install.install_package().is_some(),
+                PackageChange::Remove(_) => true,
+            })
+            .map(|(name, _)| name.as_ref())
+            .collect::<Vec<_>>();
+        names.sort();
+
+        let mut modifications = Vec::new();
+        for name in names {
+            if let Some(found) = self.package_modifications(name).await? {
+                if found.is_modified() {
+                    modifications.push(found);
+                }
+            }
+        }
+        Ok(modifications)
+    }
+
+    /// Creates the diff of the files in the package folder against the package in the
+    /// repositories in the format of `git diff`.
+    ///
+    /// The paths in the diff are relative to the package folder.
+    pub async fn package_diff<Env>(
+        &self,
+        env: &Env,
+        name: &str,
+        version: &Version,
+    ) -> io::Result<String>
+    where
+        Env: PackageCollection + RemotePackageDownloader + EnvironmentIoHolder,
+    {
+        let selector = VersionSelector::specific_version(version);
+        let Some(package) = env.find_package_by_name(name, selector) else {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{name} version {version} not found in repositories"),
+            ));
+        };
+        let pristine = read_pristine_files(env, package).await?;
+
+        let folder = PathBuf::from("Packages").joined(name);
+        let mut current = read_files(&self.io, &folder).await?;
+        current.remove(FILE_HASHES_NAME);
+
+        let mut paths = pristine.keys().chain(current.keys()).collect::<Vec<_>>();
+        paths.sort();
+        paths.dedup();
+
+        let mut diff = String::new();
+        for path in paths {
+            let original = pristine.get(path).map(Vec::as_slice);
+            let modified = current.get(path).map(Vec::as_slice);
+            if original != modified {
+                write_file_diff(&mut diff, path, original, modified);
+            }
+        }
+
+        Ok(diff)
+    }
+}
@@ -0,0 +1,196 @@
+use crate::io::ProjectIo;
+use crate::unity_project::pending_project_changes::RemoveReason;
+use crate::unity_project::{package_resolution, AddPackageErr, PendingProjectChanges};
+use crate::version::Version;
+use crate::{PackageCollection, PackageDeprecation, PackageInfo, UnityProject, VersionSelector};
+use log::debug;
+use std::collections::HashSet;
+
+/// The locked package marked as deprecated with `vrc-get.deprecated`.
+#[derive(Debug, Clone, Copy)]
+pub struct DeprecatedPackage<'a> {
+    name: &'a str,
+    version: &'a Version,
+    deprecation: &'a PackageDeprecation,
+}
+
+impl<'a> DeprecatedPackage<'a> {
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    /// The locked version of the package.
+    pub fn version(&self) -> &'a Version {
+        self.version
+    }
+
+    pub fn deprecation(&self) -> &'a PackageDeprecation {
+        self.deprecation
+    }
+}
+
+// migrating deprecated packages
+impl<IO: ProjectIo> UnityProject<IO> {
+    /// Lists the locked packages deprecated with `vrc-get.deprecated`.
+    ///
+    /// The package is deprecated if the latest version in the repositories is deprecated.
+    /// If the package is not found in the repositories, the installed `package.json` is used.
+    pub fn deprecated_packages<'a>(
+        &'a self,
+        env: &'a impl PackageCollection,
+    ) -> Vec<DeprecatedPackage<'a>> {
+        let selector = VersionSelector::latest_for(None, true);
+
+        let mut deprecated = vec![];
+        for locked in self.locked_packages() {
+            let deprecation = match env.find_package_by_name(locked.name(), selector) {
+                Some(latest) => latest.package_json().deprecation(),
+                None => (self.get_installed_package(locked.name())).and_then(|x| x.deprecation()),
+            };
+
+            if let Some(deprecation) = deprecation {
+                deprecated.push(DeprecatedPackage {
+                    name: locked.name(),
+                    version: locked.version(),
+                    deprecation,
+                });
+            }
+        }
+        deprecated
+    }
+
+    /// Creates a new `PendingProjectChanges` to replace the deprecated packages with
+    /// the packages in `replacedBy` of them.
+    ///
+    /// The deprecated packages are removed and the replacements are installed.
+    /// The replacement is added to `dependencies` if the deprecated package is in `dependencies`.
+    /// The deprecated packages without `replacedBy` are kept as is.
+    pub async fn migrate_replaced_request<'env>(
+        &self,
+        env: &'env impl PackageCollection,
+        allow_prerelease: bool,
+    ) -> Result<PendingProjectChanges<'env>, AddPackageErr> {
+        let mut replacing = (self.deprecated_packages(env).into_iter())
+            .filter_map(|x| Some((x.name(), x.deprecation().replaced_by()?)))
+            .collect::<Vec<_>>();
+
+        // if the replacement is also replaced, it will be migrated with the next run
+        let deprecated = replacing
+            .iter()
+            .map(|&(name, _)| name)
+            .collect::<HashSet<_>>();
+        replacing.retain(|(name, replaced_by)| {
+            let chained = deprecated.contains(replaced_by.name());
+            if chained {
+                debug!(
+                    "Skipping {name} since the replacement {} is also replaced",
+                    replaced_by.name()
+                );
+            }
+            !chained
+        });
+        let removing = replacing
+            .iter()
+            .map(|&(name, _)| name)
+            .collect::<HashSet<_>>();
+
+        let mut changes = super::pending_project_changes::Builder::new();
+        let mut adding_packages = Vec::<PackageInfo<'env>>::with_capacity(replacing.len());
+
+        for (name, replaced_by) in replacing {
+            let selector = match replaced_by.range() {
+                Some(range) => VersionSelector::range_for(self.unity_version(), range),
+                None => VersionSelector::latest_for(self.unity_version(), allow_prerelease),
+            };
+            let replacement = (adding_packages.iter().copied())
+                .find(|x| x.name() == replaced_by.name())
+                .or_else(|| env.find_package_by_name(replaced_by.name(), selector))
+                .ok_or_else(|| AddPackageErr::DependencyNotFound {
+                    dependency_name: replaced_by.name().into(),
+                })?;
+
+            debug!(
+                "Replacing package {name} with {} version {}",
+                replacement.name(),
+                replacement.version()
+            );
+            changes.remove(name.into(), RemoveReason::Legacy);
+
+            if self.manifest.get_dependency(name).is_some()
+                && self.manifest.get_dependency(replacement.name()).is_none()
+                && changes.get_dependencies(replacement.name()).is_none()
+            {
+                changes.add_to_dependencies(
+                    replacement.name().into(),
+                    self.dependency_range_style.range_for(replacement.version()),
+                );
+            }
+
+            // the packages requiring the deprecated package conflicts
+            // unless the replacement takes over the deprecated package
+            if !replacement
+                .legacy_packages()
+                .iter()
+                .any(|x| x.as_ref() == name)
+            {
+                for dependent in (self.all_packages())
+                    .filter(|dep| !removing.contains(dep.name()))
+                    .filter(|dep| dep.dependencies().contains_key(name))
+                {
+                    changes.conflicts(name.into(), dependent.name().into());
+                }
+            }
+
+            let already_locked = self
+                .manifest
+                .get_locked(replacement.name())
+                .map(|locked| locked.version() >= replacement.version())
+                .unwrap_or(false);
+            let already_adding = adding_packages
+                .iter()
+                .any(|x| x.name() == replacement.name());
+            if !already_locked && !already_adding {
+                adding_packages.push(replacement);
+            }
+        }
+
+        if adding_packages.is_empty() {
+            return Ok(changes.build_resolve(self).await);
+        }
+
+        let result = package_resolution::collect_adding_packages(
+            self.manifest.dependencies().map(|(name, original_range)| {
+                if let Some(new_range) = changes.get_dependencies(name) {
+                    (name, new_range)
+                } else {
+                    (name, original_range)
+                }
+            }),
+            self.manifest.all_locked(),
+            |pkg| self.manifest.get_locked(pkg),
+            self.settings.resolutions(),
+            self.unity_version(),
+            env,
+            adding_packages,
+            allow_prerelease,
+        )?;
+
+        for x in result.new_packages {
+            changes.install_to_locked(x);
+        }
+
+        for (package, conflicts_with) in result.conflicts {
+            changes.conflict_multiple(package, conflicts_with);
+        }
+
+        for name in result
+            .found_legacy_packages
+            .into_iter()
+            .filter(|name| self.is_locked(name))
+        {
+            changes.remove(name, RemoveReason::Legacy);
+        }
+
+        Ok(changes.build_resolve(self).await)
+    }
+}
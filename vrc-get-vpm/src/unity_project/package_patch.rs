@@ -0,0 +1,357 @@
+use crate::io;
+use crate::io::{DirEntry, ProjectIo};
+use crate::traits::EnvironmentIoHolder;
+use crate::unity_project::local_modifications::{read_file, record_file_hashes};
+use crate::unity_project::LockSnapshot;
+use crate::utils::{is_complete_relative, PathBufExt};
+use crate::version::Version;
+use crate::{PackageCollection, PackageInfo, RemotePackageDownloader, UnityProject};
+use futures::prelude::*;
+use std::fmt;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// The folder in `Packages` to store the patches for installed packages.
+///
+/// This folder doesn't have `package.json` so unity and vrc-get don't treat this as a package.
+pub(crate) const PATCHES_FOLDER: &str = "vrc-get-patches";
+
+fn patch_path(name: &str, version: &Version) -> PathBuf {
+    PathBuf::from("Packages")
+        .joined(PATCHES_FOLDER)
+        .joined(format!("{name}@{version}.patch"))
+}
+
+/// Appends the diff of one file in the format of `git diff`.
+///
+/// `None` means the file does not exist on the side.
+pub(super) fn write_file_diff(
+    diff: &mut String,
+    path: &str,
+    original: Option<&[u8]>,
+    modified: Option<&[u8]>,
+) {
+    writeln!(diff, "diff --git a/{path} b/{path}").unwrap();
+    match (original, modified) {
+        (None, _) => writeln!(diff, "new file mode 100644").unwrap(),
+        (_, None) => writeln!(diff, "deleted file mode 100644").unwrap(),
+        (Some(_), Some(_)) => {}
+    }
+
+    let original_text = std::str::from_utf8(original.unwrap_or_default());
+    let modified_text = std::str::from_utf8(modified.unwrap_or_default());
+    let (Ok(original_text), Ok(modified_text)) = (original_text, modified_text) else {
+        writeln!(diff, "Binary files a/{path} and b/{path} differ").unwrap();
+        return;
+    };
+
+    let original_name = original.map_or("/dev/null".to_owned(), |_| format!("a/{path}"));
+    let modified_name = modified.map_or("/dev/null".to_owned(), |_| format!("b/{path}"));
+    let patch = diffy::DiffOptions::new()
+        .set_original_filename(original_name)
+        .set_modified_filename(modified_name)
+        .create_patch(original_text, modified_text);
+    write!(diff, "{patch}").unwrap();
+}
+
+/// The diff of one file in the patch file.
+struct FilePatch<'a> {
+    path: &'a str,
+    new_file: bool,
+    deleted_file: bool,
+    /// The unified diff starting with `---` line. `None` for binary files.
+    hunks: Option<&'a str>,
+}
+
+fn invalid_patch(message: impl fmt::Display) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+/// Splits the patch created by [`write_file_diff`] into the diffs of each file.
+fn parse_patch(patch: &str) -> io::Result<Vec<FilePatch<'_>>> {
+    const HEADER: &str = "diff --git a/";
+
+    // the lines in hunks start with ' ', '+', '-', or '\' so header lines can be found by prefix
+    let mut starts = (patch.match_indices('\n'))
+        .map(|(i, _)| i + 1)
+        .filter(|&i| patch[i..].starts_with(HEADER))
+        .collect::<Vec<_>>();
+    if patch.starts_with(HEADER) {
+        starts.insert(0, 0);
+    }
+    if starts.is_empty() && !patch.trim().is_empty() {
+        return Err(invalid_patch("no diff found in the patch"));
+    }
+
+    let mut files = Vec::with_capacity(starts.len());
+    for (index, &start) in starts.iter().enumerate() {
+        let end = starts.get(index + 1).copied().unwrap_or(patch.len());
+        let section = &patch[start..end];
+
+        let (header, rest) = section.split_once('\n').unwrap_or((section, ""));
+        let Some((path, _)) = header[HEADER.len()..].split_once(" b/") else {
+            return Err(invalid_patch(format!("bad diff header: {header}")));
+        };
+        if !is_complete_relative(Path::new(path)) {
+            return Err(invalid_patch(format!(
+                "directory traversal detected: {path}"
+            )));
+        }
+
+        let mut file = FilePatch {
+            path,
+            new_file: false,
+            deleted_file: false,
+            hunks: None,
+        };
+
+        let mut rest = rest;
+        while !rest.is_empty() && !rest.starts_with("--- ") {
+            let (line, next) = rest.split_once('\n').unwrap_or((rest, ""));
+            if line.starts_with("new file mode") {
+                file.new_file = true;
+            } else if line.starts_with("deleted file mode") {
+                file.deleted_file = true;
+            }
+            rest = next;
+        }
+        if !rest.is_empty() {
+            file.hunks = Some(rest);
+        }
+
+        files.push(file);
+    }
+
+    Ok(files)
+}
+
+/// Applies the patch to the package folder.
+///
+/// The patch is applied only if all files can be patched.
+async fn apply_patch(io: &impl ProjectIo, folder: &Path, patch: &str) -> io::Result<()> {
+    let files = parse_patch(patch)?;
+
+    let mut results = Vec::with_capacity(files.len());
+    for file in &files {
+        let path = folder.join(file.path);
+
+        let Some(hunks) = file.hunks else {
+            return Err(invalid_patch(format!(
+                "binary file {} cannot be patched",
+                file.path
+            )));
+        };
+        let hunks = diffy::Patch::from_str(hunks)
+            .map_err(|e| invalid_patch(format!("bad diff for {}: {e}", file.path)))?;
+
+        let original = match read_file(io, &path).await {
+            Ok(_) if file.new_file => {
+                return Err(invalid_patch(format!("{} already exists", file.path)));
+            }
+            Ok(content) => String::from_utf8(content)
+                .map_err(|_| invalid_patch(format!("{} is not a text file", file.path)))?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound && file.new_file => String::new(),
+            Err(e) => return Err(e),
+        };
+
+        let patched = diffy::apply(&original, &hunks)
+            .map_err(|e| invalid_patch(format!("{} for {}", e, file.path)))?;
+
+        results.push((path, (!file.deleted_file).then_some(patched)));
+    }
+
+    for (path, content) in results {
+        match content {
+            Some(content) => {
+                if let Some(parent) = path.parent() {
+                    io.create_dir_all(parent).await?;
+                }
+                io.write(&path, content.as_bytes()).await?;
+            }
+            None => io.remove_file(&path).await?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Re-applies the saved patches to the newly installed packages.
+///
+/// The patch for the installed version is preferred. When the package is upgraded and there is
+/// no patch for the installed version, the patch for the nearest older version is applied if
+/// possible. Failures are reported to the log and the package is left as installed.
+pub(crate) async fn apply_package_patches(
+    io: &impl ProjectIo,
+    installed: &[PackageInfo<'_>],
+    before: &LockSnapshot,
+) {
+    let patches = list_patches(io).await;
+    if patches.is_empty() {
+        return;
+    }
+
+    for package in installed {
+        let mut versions = (patches.iter())
+            .filter(|(name, _)| name.as_ref() == package.name())
+            .map(|(_, version)| version);
+        let upgrading = (before.get_locked(package.name())).is_some_and(|x| x < package.version());
+        // patches for newer versions must not be applied to older versions
+        let patch_version = if upgrading {
+            versions.filter(|&x| x <= package.version()).max()
+        } else {
+            versions.find(|&x| x == package.version())
+        };
+        let Some(patch_version) = patch_version else {
+            continue;
+        };
+
+        let path = patch_path(package.name(), patch_version);
+        let folder = PathBuf::from("Packages").joined(package.name());
+
+        let result = async {
+            let patch = String::from_utf8(read_file(io, &path).await?)
+                .map_err(|_| invalid_patch("patch is not utf8"))?;
+            apply_patch(io, &folder, &patch).await?;
+            record_file_hashes(io, &folder, package.version()).await
+        }
+        .await;
+
+        match result {
+            Ok(()) if patch_version == package.version() => {
+                log::info!("applied patch {}", path.display());
+            }
+            Ok(()) => log::warn!(
+                "applied patch {} to {} version {}. please recreate the patch for the new version",
+                path.display(),
+                package.name(),
+                package.version(),
+            ),
+            Err(e) => log::error!(
+                "patch {} no longer applies to {} version {}: {e}. the package is installed without the patch",
+                path.display(),
+                package.name(),
+                package.version(),
+            ),
+        }
+    }
+}
+
+/// Lists the patches in the patches folder as pairs of the package name and version.
+async fn list_patches(io: &impl ProjectIo) -> Vec<(Box<str>, Version)> {
+    let folder = PathBuf::from("Packages").joined(PATCHES_FOLDER);
+    let Ok(read_dir) = io.read_dir(&folder).await else {
+        return vec![];
+    };
+
+    read_dir
+        .try_filter_map(|entry| async move {
+            let file_name = entry.file_name();
+            let Some(stem) = file_name.to_str().and_then(|x| x.strip_suffix(".patch")) else {
+                return Ok(None);
+            };
+            let Some((name, version)) = stem.rsplit_once('@') else {
+                return Ok(None);
+            };
+            Ok(Version::from_str(version)
+                .ok()
+                .map(|version| (Box::<str>::from(name), version)))
+        })
+        .filter_map(|x| async move { x.ok() })
+        .collect()
+        .await
+}
+
+// creating patches
+impl<IO: ProjectIo> UnityProject<IO> {
+    /// Saves the modifications to the installed package as a patch file in
+    /// `Packages/vrc-get-patches`.
+    ///
+    /// The patch is applied automatically when the same version of the package is installed.
+    /// Modifications to binary files cannot be saved as a patch so they are reported as an error.
+    /// If there are no modifications, the existing patch for the version is removed and
+    /// `None` is returned. Otherwise, the path to the patch file is returned.
+    pub async fn create_package_patch<Env>(
+        &self,
+        env: &Env,
+        name: &str,
+    ) -> Result<Option<PathBuf>, CreatePatchErr>
+    where
+        Env: PackageCollection + RemotePackageDownloader + EnvironmentIoHolder,
+    {
+        let Some(installed) = self.get_installed_package(name) else {
+            return Err(CreatePatchErr::NotInstalled(name.into()));
+        };
+        let version = installed.version();
+
+        let diff = match self.package_diff(env, name, version).await {
+            Ok(diff) => diff,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                return Err(CreatePatchErr::PackageNotFound {
+                    name: name.into(),
+                    version: version.clone(),
+                })
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        // binary files cannot be applied so the patch would never apply
+        if let Some(binary) = (parse_patch(&diff)?.iter()).find(|x| x.hunks.is_none()) {
+            return Err(CreatePatchErr::BinaryFile(binary.path.into()));
+        }
+
+        let path = patch_path(name, version);
+        let folder = PathBuf::from("Packages").joined(name);
+
+        if diff.is_empty() {
+            match self.io.remove_file(&path).await {
+                Ok(()) => {}
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e.into()),
+            }
+            return Ok(None);
+        }
+
+        self.io.create_dir_all(path.parent().unwrap()).await?;
+        self.io.write(&path, diff.as_bytes()).await?;
+
+        // the modifications are saved so overwriting the package is safe now
+        record_file_hashes(&self.io, &folder, version).await?;
+
+        Ok(Some(path))
+    }
+}
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum CreatePatchErr {
+    Io(io::Error),
+    NotInstalled(Box<str>),
+    PackageNotFound { name: Box<str>, version: Version },
+    BinaryFile(Box<str>),
+}
+
+impl fmt::Display for CreatePatchErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use CreatePatchErr::*;
+        match self {
+            Io(ioerr) => fmt::Display::fmt(ioerr, f),
+            NotInstalled(name) => write!(f, "package {name} is not installed"),
+            PackageNotFound { name, version } => {
+                write!(f, "{name} version {version} not found in repositories")
+            }
+            BinaryFile(path) => write!(
+                f,
+                "binary file {path} is modified. binary files cannot be saved as a patch"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CreatePatchErr {}
+
+impl From<io::Error> for CreatePatchErr {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
@@ -1,8 +1,10 @@
 use crate::traits::PackageCollection;
+use crate::unity_compatibility::unity_incompatibility;
 use crate::unity_project::{AddPackageErr, LockedDependencyInfo};
 use crate::version::{DependencyRange, UnityVersion, Version, VersionRange};
 use crate::{PackageInfo, VersionSelector};
-use std::collections::{HashMap, HashSet, VecDeque};
+use indexmap::IndexMap;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 
 struct PackageQueue<'a> {
     pending_queue: VecDeque<PackageInfo<'a>>,
@@ -36,6 +38,9 @@ where
     allow_prerelease: bool,
     pub pending_queue: PackageQueue<'env>,
     dependencies: HashMap<&'a str, DependencyInfo<'env, 'a>>,
+    resolutions: &'a IndexMap<Box<str>, VersionRange>,
+    // (dependent, dependency) -> the range overridden by the resolution. "" dependent for root dependencies
+    overridden: BTreeMap<(&'a str, &'a str), &'a VersionRange>,
 }
 
 struct Legacy<'env>(&'env [Box<str>]);
@@ -115,11 +120,17 @@ where
 }
 
 impl<'env, 'a> ResolutionContext<'env, 'a> {
-    fn new(allow_prerelease: bool, packages: Vec<PackageInfo<'env>>) -> Self {
+    fn new(
+        allow_prerelease: bool,
+        packages: Vec<PackageInfo<'env>>,
+        resolutions: &'a IndexMap<Box<str>, VersionRange>,
+    ) -> Self {
         let mut this = Self {
             dependencies: HashMap::new(),
             pending_queue: PackageQueue::new(packages),
             allow_prerelease,
+            resolutions,
+            overridden: BTreeMap::new(),
         };
 
         for pkg in &this.pending_queue.pending_queue {
@@ -135,12 +146,31 @@ impl<'env, 'a> ResolutionContext<'env, 'a>
 where
     'env: 'a,
 {
+    /// Returns the range of the resolution for the dependency if exists.
+    /// Otherwise, returns the range required by the dependent.
+    fn resolve_range(
+        &mut self,
+        dependent: &'a str,
+        dependency: &str,
+        range: &'a VersionRange,
+    ) -> &'a VersionRange {
+        let Some((dependency, resolution)) = self.resolutions.get_key_value(dependency) else {
+            return range;
+        };
+        if resolution != range {
+            self.overridden
+                .insert((dependent, dependency.as_ref()), range);
+        }
+        resolution
+    }
+
     pub(crate) fn add_root_dependency(
         &mut self,
         name: &'a str,
         range: &'a VersionRange,
         allow_pre: bool,
     ) {
+        let range = self.resolve_range("", name, range);
         self.dependencies
             .get_mut("")
             .unwrap()
@@ -177,6 +207,7 @@ where
         }
 
         for (dependency, range) in locked.dependencies() {
+            let range = self.resolve_range(locked.name(), dependency, range);
             self.dependencies
                 .entry(dependency)
                 .or_default()
@@ -213,6 +244,7 @@ where
             self.dependencies.get_mut(*dep).unwrap().remove_range(name);
         }
         for (dependency, range) in vpm_dependencies.iter() {
+            let range = self.resolve_range(name, dependency, range);
             self.dependencies
                 .entry(dependency)
                 .or_default()
@@ -271,7 +303,24 @@ where
 }
 
 impl<'env, 'a> ResolutionContext<'env, 'a> {
+    fn warn_overridden(&self) {
+        for (&(dependent, dependency), range) in &self.overridden {
+            let resolution = &self.resolutions[dependency];
+            if dependent.is_empty() {
+                log::warn!(
+                    "resolution {dependency}@{resolution} overrides the dependency of the project on {dependency}@{range}"
+                );
+            } else {
+                log::warn!(
+                    "resolution {dependency}@{resolution} overrides the dependency of {dependent} on {dependency}@{range}"
+                );
+            }
+        }
+    }
+
     pub(crate) fn build_result(self) -> PackageResolutionResult<'env> {
+        self.warn_overridden();
+
         let mut conflicts = HashMap::<Box<str>, Vec<Box<str>>>::new();
         for (&name, info) in &self.dependencies {
             if !info.is_legacy() && info.touched {
@@ -330,16 +379,18 @@ pub struct PackageResolutionResult<'env> {
     pub found_legacy_packages: Vec<Box<str>>,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn collect_adding_packages<'a, 'env>(
     dependencies: impl Iterator<Item = (&'a str, &'a DependencyRange)>,
     locked_dependencies: impl Iterator<Item = LockedDependencyInfo<'a>>,
     get_locked: impl Fn(&str) -> Option<LockedDependencyInfo<'a>>,
+    resolutions: &'a IndexMap<Box<str>, VersionRange>,
     unity_version: Option<UnityVersion>,
     env: &'env impl PackageCollection,
     packages: Vec<PackageInfo<'env>>,
     allow_prerelease: bool,
 ) -> Result<PackageResolutionResult<'env>, AddPackageErr> {
-    let mut context = ResolutionContext::<'env, '_>::new(allow_prerelease, packages);
+    let mut context = ResolutionContext::<'env, '_>::new(allow_prerelease, packages, resolutions);
 
     // first, add dependencies
     let root_dependencies = dependencies
@@ -383,22 +434,40 @@ pub(crate) fn collect_adding_packages<'a, 'env>(
             // add new dependencies
             for (dependency, range) in vpm_dependencies.iter() {
                 log::debug!("processing package {name}: dependency {dependency} version {range}");
+                let range = context.resolve_range(name, dependency, range);
 
                 if context.should_add_package(dependency, range) {
-                    let found = env
-                        .find_package_by_name(
-                            dependency,
-                            VersionSelector::range_for(unity_version, range),
-                        )
-                        .or_else(|| {
-                            env.find_package_by_name(
-                                dependency,
-                                VersionSelector::range_for(None, range),
-                            )
-                        })
-                        .ok_or_else(|| AddPackageErr::DependencyNotFound {
-                            dependency_name: dependency.clone(),
-                        })?;
+                    let compatible = env.find_package_by_name(
+                        dependency,
+                        VersionSelector::range_for(unity_version, range),
+                    );
+                    let newest = env
+                        .find_package_by_name(dependency, VersionSelector::range_for(None, range));
+
+                    // tell why the newer version is not used.
+                    // if no compatible version is found, the conflict is reported with the reason.
+                    if let (Some(unity), Some(compatible), Some(newest)) =
+                        (unity_version, compatible, newest)
+                    {
+                        if compatible.version() < newest.version() {
+                            if let Some(reason) =
+                                unity_incompatibility(newest.package_json(), unity)
+                            {
+                                log::info!(
+                                    "using {dependency} version {} since version {} {reason}",
+                                    compatible.version(),
+                                    newest.version(),
+                                );
+                            }
+                        }
+                    }
+
+                    let found =
+                        compatible
+                            .or(newest)
+                            .ok_or_else(|| AddPackageErr::DependencyNotFound {
+                                dependency_name: dependency.clone(),
+                            })?;
 
                     // remove existing if existing
                     context.pending_queue.add_pending_package(found);
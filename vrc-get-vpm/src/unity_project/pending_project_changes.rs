@@ -1,15 +1,20 @@
 use crate::io;
 use crate::io::ProjectIo;
 use crate::traits::EnvironmentIoHolder;
+use crate::unity_compatibility::{unity_incompatibility, UnityIncompatibility};
 use crate::unity_project::find_legacy_assets::collect_legacy_assets;
+use crate::unity_project::local_modifications::record_file_hashes;
+use crate::unity_project::package_patch::apply_package_patches;
 use crate::utils::{copy_recursive, extract_zip};
 use crate::version::DependencyRange;
 use crate::{
-    unity_compatible, PackageInfo, PackageInfoInner, RemotePackageDownloader, UnityProject,
+    OwnedPackageInfo, PackageInfo, PackageInfoInner, RemotePackageDownloader,
+    SharedPackageCollection, UnityProject,
 };
 use either::Either;
 use futures::future::{join3, join_all, try_join_all};
 use log::debug;
+use serde::{Deserialize, Serialize};
 use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::marker::PhantomData;
@@ -25,6 +30,8 @@ use std::path::{Path, PathBuf};
 #[derive(Debug)]
 pub struct PendingProjectChanges<'env> {
     pub(crate) package_changes: HashMap<Box<str>, PackageChange<'env>>,
+    /// The packages removed from `dependencies` but kept in `locked`.
+    pub(crate) remove_from_dependencies: Vec<Box<str>>,
 
     pub(crate) remove_legacy_files: Vec<(Box<Path>, &'env str)>,
     pub(crate) remove_legacy_folders: Vec<(Box<Path>, &'env str)>,
@@ -87,17 +94,21 @@ impl<'env> Remove<'env> {
     }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub enum RemoveReason {
     Requested,
     Legacy,
     Unused,
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ConflictInfo {
     conflicts_packages: Vec<Box<str>>,
     conflicts_with_unity: bool,
+    #[serde(skip)]
+    unity_incompatibility: Option<UnityIncompatibility>,
 }
 
 impl ConflictInfo {
@@ -108,10 +119,18 @@ impl ConflictInfo {
     pub fn conflicts_with_unity(&self) -> bool {
         self.conflicts_with_unity
     }
+
+    /// Why the package is not compatible with the unity of the project.
+    ///
+    /// This is `None` for the changes restored from the saved plan.
+    pub fn unity_incompatibility(&self) -> Option<&UnityIncompatibility> {
+        self.unity_incompatibility.as_ref()
+    }
 }
 
 pub(crate) struct Builder<'env> {
     package_changes: HashMap<Box<str>, PackageChange<'env>>,
+    remove_from_dependencies: Vec<Box<str>>,
     conflicts: HashMap<Box<str>, ConflictInfo>,
 }
 
@@ -119,6 +138,7 @@ impl<'env> Builder<'env> {
     pub fn new() -> Self {
         Self {
             package_changes: HashMap::new(),
+            remove_from_dependencies: Vec::new(),
             conflicts: HashMap::new(),
         }
     }
@@ -250,6 +270,21 @@ impl<'env> Builder<'env> {
         self
     }
 
+    /// Removes the package from `dependencies` without removing the package itself.
+    pub fn remove_from_dependencies(&mut self, name: Box<str>) -> &mut Self {
+        match self.package_changes.get(&name) {
+            Some(PackageChange::Install(e)) if e.to_dependencies.is_some() => {
+                panic!("INTERNAL ERROR: remove_from_dependencies for add_to_dependencies");
+            }
+            Some(PackageChange::Remove(_)) => {
+                panic!("INTERNAL ERROR: remove_from_dependencies for removed");
+            }
+            _ => {}
+        }
+        self.remove_from_dependencies.push(name);
+        self
+    }
+
     fn remove_unused(&mut self, name: Box<str>) -> &mut Self {
         match self.package_changes.entry(name) {
             Entry::Occupied(mut e) => match e.get_mut() {
@@ -309,6 +344,7 @@ impl<'env> Builder<'env> {
 
         PendingProjectChanges {
             package_changes: self.package_changes,
+            remove_from_dependencies: self.remove_from_dependencies,
             conflicts: self.conflicts,
 
             remove_legacy_files: vec![],
@@ -316,6 +352,21 @@ impl<'env> Builder<'env> {
         }
     }
 
+    pub(crate) fn build_with_legacy_assets(
+        self,
+        remove_legacy_files: Vec<(Box<Path>, &'env str)>,
+        remove_legacy_folders: Vec<(Box<Path>, &'env str)>,
+    ) -> PendingProjectChanges<'env> {
+        PendingProjectChanges {
+            package_changes: self.package_changes,
+            remove_from_dependencies: self.remove_from_dependencies,
+            conflicts: self.conflicts,
+
+            remove_legacy_files,
+            remove_legacy_folders,
+        }
+    }
+
     pub async fn build_resolve(
         mut self,
         unity_project: &UnityProject<impl ProjectIo>,
@@ -329,12 +380,12 @@ impl<'env> Builder<'env> {
         );
 
         if let Some(unity) = unity_project.unity_version {
-            for package in installs
-                .iter()
-                .filter(|pkg| !unity_compatible(pkg.package_json(), unity))
-                .map(|pkg| pkg.name().into())
-            {
-                self.conflicts_unity(package);
+            for package in &installs {
+                if let Some(reason) = unity_incompatibility(package.package_json(), unity) {
+                    let conflict = self.conflicts.entry(package.name().into()).or_default();
+                    conflict.conflicts_with_unity = true;
+                    conflict.unity_incompatibility = Some(reason);
+                }
             }
         }
 
@@ -344,6 +395,7 @@ impl<'env> Builder<'env> {
 
         PendingProjectChanges {
             package_changes: self.package_changes,
+            remove_from_dependencies: self.remove_from_dependencies,
             conflicts: self.conflicts,
 
             remove_legacy_files: legacy_assets.files,
@@ -480,6 +532,11 @@ impl PendingProjectChanges<'_> {
         &self.package_changes
     }
 
+    /// The packages removed from `dependencies` but kept installed.
+    pub fn remove_from_dependencies(&self) -> &[Box<str>] {
+        self.remove_from_dependencies.as_slice()
+    }
+
     pub fn remove_legacy_files(&self) -> &[(Box<Path>, &str)] {
         self.remove_legacy_files.as_slice()
     }
@@ -493,6 +550,181 @@ impl PendingProjectChanges<'_> {
     }
 }
 
+/// The owned version of [`PendingProjectChanges`].
+///
+/// This is `'static`, `Send`, and serializable so this can be held across awaits or sent between tasks.
+/// Use [`SharedPackageCollection`] to collect the changes and convert to this.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnedPendingProjectChanges {
+    package_changes: HashMap<Box<str>, OwnedPackageChange>,
+    remove_from_dependencies: Vec<Box<str>>,
+
+    remove_legacy_files: Vec<(Box<Path>, Box<str>)>,
+    remove_legacy_folders: Vec<(Box<Path>, Box<str>)>,
+
+    conflicts: HashMap<Box<str>, ConflictInfo>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum OwnedPackageChange {
+    Install(OwnedInstall),
+    Remove(OwnedRemove),
+}
+
+impl OwnedPackageChange {
+    pub fn as_install(&self) -> Option<&OwnedInstall> {
+        match self {
+            OwnedPackageChange::Install(x) => Some(x),
+            OwnedPackageChange::Remove(_) => None,
+        }
+    }
+
+    pub fn as_remove(&self) -> Option<&OwnedRemove> {
+        match self {
+            OwnedPackageChange::Install(_) => None,
+            OwnedPackageChange::Remove(x) => Some(x),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnedInstall {
+    package: Option<OwnedPackageInfo>,
+    add_to_locked: bool,
+    to_dependencies: Option<DependencyRange>,
+}
+
+impl OwnedInstall {
+    pub fn install_package(&self) -> Option<&OwnedPackageInfo> {
+        self.package.as_ref()
+    }
+
+    pub fn is_adding_to_locked(&self) -> bool {
+        self.add_to_locked
+    }
+
+    pub fn to_dependencies(&self) -> Option<&DependencyRange> {
+        self.to_dependencies.as_ref()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnedRemove {
+    reason: RemoveReason,
+}
+
+impl OwnedRemove {
+    pub fn reason(&self) -> RemoveReason {
+        self.reason
+    }
+}
+
+impl OwnedPendingProjectChanges {
+    /// Converts the changes collected with the [`SharedPackageCollection`] to the owned version.
+    ///
+    /// Returns `None` if some package to be installed is not from the collection.
+    pub fn new(
+        collection: &SharedPackageCollection,
+        changes: &PendingProjectChanges,
+    ) -> Option<Self> {
+        let mut package_changes = HashMap::with_capacity(changes.package_changes.len());
+
+        for (name, change) in &changes.package_changes {
+            let change = match change {
+                PackageChange::Install(install) => OwnedPackageChange::Install(OwnedInstall {
+                    package: match install.package {
+                        Some(package) => Some(collection.to_owned_package(package)?),
+                        None => None,
+                    },
+                    add_to_locked: install.add_to_locked,
+                    to_dependencies: install.to_dependencies.clone(),
+                }),
+                PackageChange::Remove(remove) => OwnedPackageChange::Remove(OwnedRemove {
+                    reason: remove.reason,
+                }),
+            };
+            package_changes.insert(name.clone(), change);
+        }
+
+        fn legacy_assets(assets: &[(Box<Path>, &str)]) -> Vec<(Box<Path>, Box<str>)> {
+            (assets.iter())
+                .map(|(path, package)| (path.clone(), (*package).into()))
+                .collect()
+        }
+
+        Some(Self {
+            package_changes,
+            remove_from_dependencies: changes.remove_from_dependencies.clone(),
+            remove_legacy_files: legacy_assets(&changes.remove_legacy_files),
+            remove_legacy_folders: legacy_assets(&changes.remove_legacy_folders),
+            conflicts: changes.conflicts.clone(),
+        })
+    }
+
+    /// Borrows this as [`PendingProjectChanges`].
+    pub fn as_pending_changes(&self) -> PendingProjectChanges<'_> {
+        let mut builder = Builder::new();
+
+        for (name, change) in &self.package_changes {
+            match change {
+                OwnedPackageChange::Install(install) => {
+                    if let Some(package) = &install.package {
+                        if install.add_to_locked {
+                            builder.install_to_locked(package.as_package_info());
+                        } else {
+                            builder.install_already_locked(package.as_package_info());
+                        }
+                    }
+                    if let Some(range) = &install.to_dependencies {
+                        builder.add_to_dependencies(name.clone(), range.clone());
+                    }
+                }
+                OwnedPackageChange::Remove(remove) => {
+                    builder.remove(name.clone(), remove.reason);
+                }
+            }
+        }
+
+        builder.remove_from_dependencies = self.remove_from_dependencies.clone();
+        builder.conflicts = self.conflicts.clone();
+
+        fn legacy_assets(assets: &[(Box<Path>, Box<str>)]) -> Vec<(Box<Path>, &str)> {
+            (assets.iter())
+                .map(|(path, package)| (path.clone(), package.as_ref()))
+                .collect()
+        }
+
+        builder.build_with_legacy_assets(
+            legacy_assets(&self.remove_legacy_files),
+            legacy_assets(&self.remove_legacy_folders),
+        )
+    }
+
+    pub fn package_changes(&self) -> &HashMap<Box<str>, OwnedPackageChange> {
+        &self.package_changes
+    }
+
+    pub fn remove_from_dependencies(&self) -> &[Box<str>] {
+        self.remove_from_dependencies.as_slice()
+    }
+
+    pub fn remove_legacy_files(&self) -> &[(Box<Path>, Box<str>)] {
+        self.remove_legacy_files.as_slice()
+    }
+
+    pub fn remove_legacy_folders(&self) -> &[(Box<Path>, Box<str>)] {
+        self.remove_legacy_folders.as_slice()
+    }
+
+    pub fn conflicts(&self) -> &HashMap<Box<str>, ConflictInfo> {
+        &self.conflicts
+    }
+}
+
 impl<IO: ProjectIo> UnityProject<IO> {
     /// Applies the changes specified in `AddPackageRequest` to the project.
     pub async fn apply_pending_changes<'env, Env: RemotePackageDownloader + EnvironmentIoHolder>(
@@ -500,6 +732,7 @@ impl<IO: ProjectIo> UnityProject<IO> {
         env: &'env Env,
         request: PendingProjectChanges<'env>,
     ) -> io::Result<()> {
+        let before = self.lock_snapshot();
         let mut installs = Vec::new();
         let mut remove_names = Vec::new();
 
@@ -529,8 +762,13 @@ impl<IO: ProjectIo> UnityProject<IO> {
 
         self.manifest
             .remove_packages(remove_names.iter().map(Box::as_ref));
+        for name in &request.remove_from_dependencies {
+            self.manifest.remove_dependency(name);
+        }
+        self.record_history(before.clone());
 
         install_packages(&self.io, env, &installs).await?;
+        apply_package_patches(&self.io, &installs, &before).await;
 
         remove_assets(
             &self.io,
@@ -633,13 +871,13 @@ pub(crate) async fn add_package<Env: RemotePackageDownloader + EnvironmentIoHold
             // remove dest folder before extract if exists
             io.remove_dir_all(&dest_folder).await.ok();
             extract_zip(zip_file, io, &dest_folder).await?;
-
-            Ok(())
         }
         PackageInfoInner::Local(_, path) => {
             io.remove_dir_all(&dest_folder).await.ok();
-            copy_recursive(env.io(), path.into(), io, dest_folder).await?;
-            Ok(())
+            copy_recursive(env.io(), path.into(), io, dest_folder.clone()).await?;
         }
     }
+
+    // to detect local modifications before overwriting the package
+    record_file_hashes(io, &dest_folder, package.version()).await
 }
@@ -0,0 +1,95 @@
+use crate::io;
+use crate::io::ProjectIo;
+use crate::utils::{load_json_or_default, SaveController};
+use crate::version::VersionRange;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+/// The project settings owned by vrc-get.
+///
+/// This is separated from `vpm-manifest.json` to keep the compatibility with VCC.
+const SETTINGS_PATH: &str = "Packages/vrc-get-project.json";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AsJson {
+    /// The ranges to be used instead of the ranges in dependents.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    resolutions: IndexMap<Box<str>, VersionRange>,
+    /// The packages excluded from bulk upgrades.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    holds: IndexMap<Box<str>, HoldInfo>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HoldInfo {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    reason: Option<Box<str>>,
+}
+
+#[derive(Debug)]
+pub(super) struct ProjectSettings {
+    controller: SaveController<AsJson>,
+}
+
+impl ProjectSettings {
+    pub(super) async fn load(io: &impl ProjectIo) -> io::Result<Self> {
+        Ok(Self {
+            controller: SaveController::new(
+                load_json_or_default(io, SETTINGS_PATH.as_ref()).await?,
+            ),
+        })
+    }
+
+    pub(super) fn resolutions(&self) -> &IndexMap<Box<str>, VersionRange> {
+        &self.controller.resolutions
+    }
+
+    pub(super) fn set_resolution(&mut self, name: &str, range: VersionRange) {
+        self.controller
+            .as_mut()
+            .resolutions
+            .insert(name.into(), range);
+    }
+
+    pub(super) fn remove_resolution(&mut self, name: &str) -> bool {
+        let mut removed = false;
+        self.controller.may_changing(|json| {
+            removed = json.resolutions.shift_remove(name).is_some();
+            removed
+        });
+        removed
+    }
+
+    pub(super) fn holds(&self) -> impl Iterator<Item = (&str, Option<&str>)> {
+        (self.controller.holds.iter()).map(|(name, hold)| (name.as_ref(), hold.reason.as_deref()))
+    }
+
+    pub(super) fn get_hold(&self, name: &str) -> Option<Option<&str>> {
+        self.controller
+            .holds
+            .get(name)
+            .map(|hold| hold.reason.as_deref())
+    }
+
+    pub(super) fn hold(&mut self, name: &str, reason: Option<Box<str>>) {
+        self.controller
+            .as_mut()
+            .holds
+            .insert(name.into(), HoldInfo { reason });
+    }
+
+    pub(super) fn unhold(&mut self, name: &str) -> bool {
+        let mut removed = false;
+        self.controller.may_changing(|json| {
+            removed = json.holds.shift_remove(name).is_some();
+            removed
+        });
+        removed
+    }
+
+    pub(super) async fn save(&mut self, io: &impl ProjectIo) -> io::Result<()> {
+        self.controller.save(io, SETTINGS_PATH.as_ref()).await
+    }
+}
@@ -3,7 +3,6 @@ use crate::unity_project::{
     package_resolution, pending_project_changes, AddPackageErr, LockedDependencyInfo,
     PendingProjectChanges,
 };
-use crate::version::DependencyRange;
 use crate::{PackageCollection, UnityProject, VersionSelector};
 use itertools::Itertools;
 use std::collections::{HashMap, HashSet};
@@ -81,10 +80,12 @@ impl<IO: ProjectIo> UnityProject<IO> {
 
         for (name, range) in self.manifest.dependencies() {
             if self.manifest.get_locked(name).is_none() {
+                let range =
+                    (self.get_resolution(name).cloned()).unwrap_or_else(|| range.as_range());
                 to_install.push(
                     env.find_package_by_name(
                         name,
-                        VersionSelector::range_for(self.unity_version(), &range.as_range()),
+                        VersionSelector::range_for(self.unity_version(), &range),
                     )
                     .ok_or_else(|| AddPackageErr::DependencyNotFound {
                         dependency_name: name.into(),
@@ -104,6 +105,7 @@ impl<IO: ProjectIo> UnityProject<IO> {
             self.manifest.dependencies(),
             self.manifest.all_locked(),
             |pkg| self.manifest.get_locked(pkg),
+            self.settings.resolutions(),
             self.unity_version(),
             env,
             to_install,
@@ -115,7 +117,7 @@ impl<IO: ProjectIo> UnityProject<IO> {
             if install_names.contains(x.name()) {
                 changes.add_to_dependencies(
                     x.name().into(),
-                    DependencyRange::version(x.version().clone()),
+                    self.dependency_range_style.range_for(x.version()),
                 );
             }
         }
@@ -183,7 +185,10 @@ impl<IO: ProjectIo> UnityProject<IO> {
 
         let unlocked_dependencies = unlocked_dependencies_versions
             .into_iter()
-            .map(|(pkg_name, ranges)| {
+            .map(|(pkg_name, mut ranges)| {
+                if let Some(resolution) = self.get_resolution(pkg_name) {
+                    ranges = vec![resolution];
+                }
                 env.find_package_by_name(
                     pkg_name,
                     VersionSelector::ranges_for(self.unity_version, &ranges),
@@ -202,6 +207,7 @@ impl<IO: ProjectIo> UnityProject<IO> {
             self.manifest.dependencies(),
             virtual_locked_dependencies.values().cloned(),
             |pkg| virtual_locked_dependencies.get(pkg).cloned(),
+            self.settings.resolutions(),
             self.unity_version(),
             env,
             unlocked_dependencies,
@@ -0,0 +1,332 @@
+use crate::io::ProjectIo;
+use crate::unity_project::find_legacy_assets::is_legacy_asset_of;
+use crate::unity_project::pending_project_changes::{
+    Builder, PackageChange, PendingProjectChanges, RemoveReason,
+};
+use crate::version::{DependencyRange, Version};
+use crate::{PackageCollection, PackageInfo, PackageInfoInner, UnityProject};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::Path;
+
+const FORMAT_VERSION: u32 = 1;
+
+/// The serializable form of [`PendingProjectChanges`].
+///
+/// This can be saved to a file to review the changes and apply them later.
+/// Restoring the changes with [`UnityProject::restore_pending_changes`] fails if
+/// the lock of the project or the packages in the repositories are changed since the changes are saved.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedPendingChanges {
+    format_version: u32,
+    /// sha256 of the dependencies and locked packages in vpm-manifest.json
+    manifest_hash: Box<str>,
+    package_changes: BTreeMap<Box<str>, SavedPackageChange>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    remove_from_dependencies: Vec<Box<str>>,
+    remove_legacy_files: Vec<SavedLegacyAsset>,
+    remove_legacy_folders: Vec<SavedLegacyAsset>,
+    conflicts: BTreeMap<Box<str>, SavedConflictInfo>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum SavedPackageChange {
+    #[serde(rename_all = "camelCase")]
+    Install {
+        package: Option<SavedPackage>,
+        add_to_locked: bool,
+        to_dependencies: Option<DependencyRange>,
+    },
+    Remove {
+        reason: RemoveReason,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+struct SavedPackage {
+    version: Version,
+    /// id or url of the repository, or path to the user package
+    source: Option<Box<str>>,
+    /// sha256 of the contents of the package manifest
+    manifest_hash: Box<str>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+struct SavedLegacyAsset {
+    path: Box<Path>,
+    package: Box<str>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+struct SavedConflictInfo {
+    conflicts_packages: Vec<Box<str>>,
+    conflicts_with_unity: bool,
+}
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum RestoreChangesErr {
+    UnsupportedFormat(u32),
+    ProjectChanged,
+    PackageNotFound {
+        package_name: Box<str>,
+        version: Version,
+    },
+    PackageChanged {
+        package_name: Box<str>,
+        version: Version,
+    },
+    InvalidChanges,
+}
+
+impl fmt::Display for RestoreChangesErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RestoreChangesErr::UnsupportedFormat(version) => {
+                write!(f, "unsupported format version: {version}")
+            }
+            RestoreChangesErr::ProjectChanged => {
+                f.write_str("the project is changed since the changes are saved")
+            }
+            RestoreChangesErr::PackageNotFound {
+                package_name,
+                version,
+            } => write!(f, "package {package_name} version {version} not found"),
+            RestoreChangesErr::PackageChanged {
+                package_name,
+                version,
+            } => write!(
+                f,
+                "package {package_name} version {version} is changed since the changes are saved"
+            ),
+            RestoreChangesErr::InvalidChanges => f.write_str("the saved changes are broken"),
+        }
+    }
+}
+
+impl std::error::Error for RestoreChangesErr {}
+
+fn package_source(package: PackageInfo) -> Option<Box<str>> {
+    match package.inner {
+        PackageInfoInner::Remote(_, repo) => (repo.id())
+            .or(repo.url().map(url::Url::as_str))
+            .map(Into::into),
+        PackageInfoInner::Local(_, path) => Some(path.to_string_lossy().into()),
+    }
+}
+
+fn package_manifest_hash(package: PackageInfo) -> Box<str> {
+    // hash the fields affect installation. HashMaps are sorted to make hash stable
+    #[derive(Serialize)]
+    struct HashTarget<'a> {
+        name: &'a str,
+        version: &'a Version,
+        url: Option<&'a str>,
+        zip_sha_256: Option<&'a str>,
+        vpm_dependencies: Vec<(&'a str, String)>,
+        legacy_folders: BTreeMap<&'a str, Option<&'a str>>,
+        legacy_files: BTreeMap<&'a str, Option<&'a str>>,
+        legacy_packages: &'a [Box<str>],
+    }
+
+    let json = package.package_json();
+    let target = HashTarget {
+        name: json.name(),
+        version: json.version(),
+        url: json.url().map(url::Url::as_str),
+        zip_sha_256: json.zip_sha_256(),
+        vpm_dependencies: (json.vpm_dependencies().iter())
+            .map(|(name, range)| (name.as_ref(), range.to_string()))
+            .collect(),
+        legacy_folders: (json.legacy_folders().iter())
+            .map(|(path, guid)| (path.as_ref(), guid.as_deref()))
+            .collect(),
+        legacy_files: (json.legacy_files().iter())
+            .map(|(path, guid)| (path.as_ref(), guid.as_deref()))
+            .collect(),
+        legacy_packages: json.legacy_packages(),
+    };
+
+    let json = serde_json::to_vec(&target).expect("serializing package manifest");
+    hex::encode(Sha256::digest(json)).into()
+}
+
+impl<IO: ProjectIo> UnityProject<IO> {
+    /// Creates the serializable form of the changes to save and apply them later.
+    pub fn save_pending_changes(&self, changes: &PendingProjectChanges) -> SavedPendingChanges {
+        let package_changes = (changes.package_changes.iter())
+            .map(|(name, change)| {
+                let change = match change {
+                    PackageChange::Install(install) => SavedPackageChange::Install {
+                        package: install.install_package().map(|package| SavedPackage {
+                            version: package.version().clone(),
+                            source: package_source(package),
+                            manifest_hash: package_manifest_hash(package),
+                        }),
+                        add_to_locked: install.is_adding_to_locked(),
+                        to_dependencies: install.to_dependencies().cloned(),
+                    },
+                    PackageChange::Remove(remove) => SavedPackageChange::Remove {
+                        reason: remove.reason(),
+                    },
+                };
+                (name.clone(), change)
+            })
+            .collect();
+
+        fn legacy_assets(assets: &[(Box<Path>, &str)]) -> Vec<SavedLegacyAsset> {
+            let mut assets = (assets.iter())
+                .map(|(path, package)| SavedLegacyAsset {
+                    path: path.clone(),
+                    package: (*package).into(),
+                })
+                .collect::<Vec<_>>();
+            assets.sort_by(|a, b| a.path.cmp(&b.path));
+            assets
+        }
+
+        let conflicts = (changes.conflicts.iter())
+            .map(|(name, conflict)| {
+                let mut conflicts_packages = conflict.conflicting_packages().to_vec();
+                conflicts_packages.sort();
+                let info = SavedConflictInfo {
+                    conflicts_packages,
+                    conflicts_with_unity: conflict.conflicts_with_unity(),
+                };
+                (name.clone(), info)
+            })
+            .collect();
+
+        SavedPendingChanges {
+            format_version: FORMAT_VERSION,
+            manifest_hash: self.manifest.content_hash(),
+            package_changes,
+            remove_from_dependencies: {
+                let mut names = changes.remove_from_dependencies.clone();
+                names.sort();
+                names
+            },
+            remove_legacy_files: legacy_assets(&changes.remove_legacy_files),
+            remove_legacy_folders: legacy_assets(&changes.remove_legacy_folders),
+            conflicts,
+        }
+    }
+
+    /// Restores the changes saved with [`UnityProject::save_pending_changes`].
+    ///
+    /// This fails if the project lock or the packages to be installed are changed since the changes are saved.
+    pub fn restore_pending_changes<'env>(
+        &self,
+        collection: &'env impl PackageCollection,
+        saved: &SavedPendingChanges,
+    ) -> Result<PendingProjectChanges<'env>, RestoreChangesErr> {
+        if saved.format_version != FORMAT_VERSION {
+            return Err(RestoreChangesErr::UnsupportedFormat(saved.format_version));
+        }
+
+        if saved.manifest_hash != self.manifest.content_hash() {
+            return Err(RestoreChangesErr::ProjectChanged);
+        }
+
+        let mut builder = Builder::new();
+        let mut installing = HashMap::<&str, PackageInfo<'env>>::new();
+
+        for (name, change) in &saved.package_changes {
+            match change {
+                SavedPackageChange::Install {
+                    package,
+                    add_to_locked,
+                    to_dependencies,
+                } => {
+                    if let Some(saved_package) = package {
+                        let package = collection
+                            .find_packages(name)
+                            .find(|x| {
+                                x.version() == &saved_package.version
+                                    && package_source(*x) == saved_package.source
+                            })
+                            .ok_or_else(|| RestoreChangesErr::PackageNotFound {
+                                package_name: name.clone(),
+                                version: saved_package.version.clone(),
+                            })?;
+
+                        if package_manifest_hash(package) != saved_package.manifest_hash {
+                            return Err(RestoreChangesErr::PackageChanged {
+                                package_name: name.clone(),
+                                version: saved_package.version.clone(),
+                            });
+                        }
+
+                        if *add_to_locked {
+                            builder.install_to_locked(package);
+                        } else {
+                            builder.install_already_locked(package);
+                        }
+                        installing.insert(package.name(), package);
+                    }
+
+                    if let Some(range) = to_dependencies {
+                        builder.add_to_dependencies(name.clone(), range.clone());
+                    }
+                }
+                SavedPackageChange::Remove { reason } => {
+                    // removing deletes Packages/{name} so only packages in the project can be removed
+                    let in_project = self.is_locked(name)
+                        || self.get_installed_package(name).is_some()
+                        || (self.unlocked_packages.iter()).any(|(dir, _)| dir == name);
+                    if !in_project {
+                        return Err(RestoreChangesErr::InvalidChanges);
+                    }
+                    builder.remove(name.clone(), *reason);
+                }
+            }
+        }
+
+        for name in &saved.remove_from_dependencies {
+            let changed_dependency = match saved.package_changes.get(name) {
+                Some(SavedPackageChange::Install {
+                    to_dependencies, ..
+                }) => to_dependencies.is_some(),
+                Some(SavedPackageChange::Remove { .. }) => true,
+                None => false,
+            };
+            if changed_dependency || self.manifest.get_dependency(name).is_none() {
+                return Err(RestoreChangesErr::InvalidChanges);
+            }
+            builder.remove_from_dependencies(name.clone());
+        }
+
+        for (name, conflict) in &saved.conflicts {
+            builder.conflict_multiple(name.clone(), conflict.conflicts_packages.iter().cloned());
+            if conflict.conflicts_with_unity {
+                builder.conflicts_unity(name.clone());
+            }
+        }
+
+        let legacy_assets = |assets: &[SavedLegacyAsset], is_file: bool| {
+            (assets.iter())
+                .map(|asset| {
+                    let package = installing
+                        .get(asset.package.as_ref())
+                        .ok_or(RestoreChangesErr::InvalidChanges)?;
+                    if !is_legacy_asset_of(*package, &asset.path, is_file) {
+                        return Err(RestoreChangesErr::InvalidChanges);
+                    }
+                    Ok((asset.path.clone(), package.name()))
+                })
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        let remove_legacy_files = legacy_assets(&saved.remove_legacy_files, true)?;
+        let remove_legacy_folders = legacy_assets(&saved.remove_legacy_folders, false)?;
+
+        Ok(builder.build_with_legacy_assets(remove_legacy_files, remove_legacy_folders))
+    }
+}
@@ -0,0 +1,73 @@
+use crate::io::ProjectIo;
+use crate::version::VersionRange;
+use crate::{PackageCollection, PackageInfo, UnityProject, VersionSelector};
+use serde::{Deserialize, Serialize};
+
+/// How far the locked package can be upgraded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub enum UpgradeLevel {
+    /// The latest version
+    Latest,
+    /// The latest version with the same major version
+    Minor,
+    /// The latest version with the same major and minor version
+    Patch,
+    /// The latest version in the range of `dependencies` and the ranges required by the other packages
+    Compatible,
+}
+
+// finding upgrades
+impl<IO: ProjectIo> UnityProject<IO> {
+    /// Returns the ranges the upgraded version of the locked package should be in.
+    ///
+    /// Returns `None` if the package is not locked.
+    pub fn upgrade_ranges(&self, name: &str, level: UpgradeLevel) -> Option<Vec<VersionRange>> {
+        let locked = self.get_locked(name)?;
+        let current = locked.version().clone();
+
+        let ranges = match level {
+            UpgradeLevel::Latest => vec![],
+            UpgradeLevel::Minor => vec![VersionRange::same_major_or_later(current)],
+            UpgradeLevel::Patch => vec![VersionRange::same_minor_or_later(current)],
+            UpgradeLevel::Compatible => {
+                if let Some(resolution) = self.get_resolution(name) {
+                    // resolutions override the ranges required by the dependents
+                    vec![resolution.clone()]
+                } else {
+                    let dependency = self.manifest.get_dependency(name);
+                    let required = (self.all_packages())
+                        .filter_map(|package| package.dependencies().get(name).cloned());
+                    (dependency.map(|x| x.as_range()).into_iter())
+                        .chain(required)
+                        .collect()
+                }
+            }
+        };
+
+        Some(ranges)
+    }
+
+    /// Finds the newest version of the locked package allowed by the upgrade level.
+    ///
+    /// Returns `None` if the package is not locked or there is no newer version.
+    pub fn find_upgrade<'env>(
+        &self,
+        env: &'env impl PackageCollection,
+        name: &str,
+        level: UpgradeLevel,
+        include_prerelease: bool,
+    ) -> Option<PackageInfo<'env>> {
+        let locked = self.get_locked(name)?;
+        let ranges = self.upgrade_ranges(name, level)?;
+        let selector = VersionSelector::latest_in_ranges_for(
+            self.unity_version(),
+            &ranges,
+            include_prerelease,
+        );
+
+        env.find_package_by_name(name, selector)
+            .filter(|package| package.version() > locked.version())
+    }
+}
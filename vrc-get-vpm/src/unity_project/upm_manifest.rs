@@ -6,6 +6,7 @@ use serde::de::Error;
 use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::{Map, Value};
 use std::collections::HashMap;
+use std::fmt;
 use std::fmt::Formatter;
 use std::str::FromStr;
 
@@ -17,14 +18,25 @@ struct Parsed {
     dependencies: HashMap<Box<str>, UpmDependency>,
 }
 
+/// The dependency in `Packages/manifest.json` of UPM.
 #[derive(Debug)]
-pub(super) enum UpmDependency {
-    // minimum version name. build meta is not supported by upm
+#[non_exhaustive]
+pub enum UpmDependency {
+    /// The minimum version. build meta is not supported by upm
     Version(Version),
-    // Other Notation including local file and git url
+    /// Other notation including local file and git url
     OtherNotation(Box<str>),
 }
 
+impl fmt::Display for UpmDependency {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            UpmDependency::Version(version) => write!(f, "{version}"),
+            UpmDependency::OtherNotation(notation) => f.write_str(notation),
+        }
+    }
+}
+
 impl<'de> Deserialize<'de> for UpmDependency {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
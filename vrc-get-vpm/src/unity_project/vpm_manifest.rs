@@ -5,6 +5,7 @@ use crate::utils::{load_json_or_default, SaveController};
 use crate::version::{DependencyRange, Version, VersionRange};
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 const MANIFEST_PATH: &str = "Packages/vpm-manifest.json";
 
@@ -43,6 +44,13 @@ impl VpmManifest {
         })
     }
 
+    /// Parses `vpm-manifest.json` not in the project folder, for example, one in the git history.
+    pub(super) fn from_slice(json: &[u8]) -> serde_json::Result<Self> {
+        Ok(Self {
+            controller: SaveController::new(serde_json::from_slice(json)?),
+        })
+    }
+
     pub(super) fn dependencies(&self) -> impl Iterator<Item = (&str, &DependencyRange)> {
         self.controller
             .dependencies
@@ -79,6 +87,10 @@ impl VpmManifest {
             .insert(name.into(), VpmDependency { version });
     }
 
+    pub(super) fn remove_dependency(&mut self, name: &str) {
+        self.controller.as_mut().dependencies.shift_remove(name);
+    }
+
     pub(super) fn add_locked(
         &mut self,
         name: &str,
@@ -105,6 +117,12 @@ impl VpmManifest {
         !self.controller.locked.is_empty() || !self.controller.dependencies.is_empty()
     }
 
+    /// Returns sha256 hash of the dependencies and locked packages.
+    pub(super) fn content_hash(&self) -> Box<str> {
+        let json = serde_json::to_vec(&*self.controller).expect("serializing manifest");
+        hex::encode(Sha256::digest(json)).into()
+    }
+
     pub(super) async fn save(&mut self, io: &impl ProjectIo) -> io::Result<()> {
         self.controller.save(io, MANIFEST_PATH.as_ref()).await
     }
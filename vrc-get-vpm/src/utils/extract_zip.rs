@@ -46,7 +46,45 @@ pub(crate) async fn extract_zip(
     Ok(())
 }
 
-fn is_complete_relative(path: &Path) -> bool {
+/// Reads all files in the zip file into memory.
+///
+/// Returns pairs of the path in the zip file and the contents. Directories are skipped.
+pub(crate) async fn read_zip_files(
+    mut zip_file: impl AsyncRead + AsyncSeek + Unpin,
+) -> io::Result<Vec<(Box<str>, Vec<u8>)>> {
+    zip_file.seek(SeekFrom::Start(0)).await?;
+
+    let mut zip_reader = ZipFileReader::new(zip_file).await.err_mapped()?;
+    let mut files = Vec::new();
+    for i in 0..zip_reader.file().entries().len() {
+        let entry = &zip_reader.file().entries()[i];
+        let Some(filename) = entry.filename().as_str().ok() else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "path in zip file is not utf8".to_string(),
+            ));
+        };
+        if !is_complete_relative(filename.as_ref()) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("directory traversal detected: {}", filename),
+            ));
+        }
+        if filename.ends_with('/') {
+            continue;
+        }
+        let filename = filename.into();
+
+        let mut reader = zip_reader.reader_without_entry(i).await.err_mapped()?;
+        let mut buffer = Vec::new();
+        reader.read_to_end(&mut buffer).await?;
+        files.push((filename, buffer));
+    }
+
+    Ok(files)
+}
+
+pub(crate) fn is_complete_relative(path: &Path) -> bool {
     for x in path.components() {
         match x {
             Component::Prefix(_) => return false,
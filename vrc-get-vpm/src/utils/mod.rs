@@ -4,6 +4,7 @@ mod deup_deserializer;
 mod extract_zip;
 mod save_controller;
 mod sha256_async_write;
+mod tar;
 
 use crate::io;
 use crate::io::{DirEntry, IoTrait};
@@ -21,9 +22,10 @@ use std::task::{ready, Context, Poll};
 pub(crate) use copy_recursive::copy_recursive;
 pub(crate) use crlf_json_formatter::to_vec_pretty_os_eol;
 pub(crate) use deup_deserializer::DedupForwarder;
-pub(crate) use extract_zip::extract_zip;
+pub(crate) use extract_zip::{extract_zip, is_complete_relative, read_zip_files};
 pub(crate) use save_controller::SaveController;
 pub(crate) use sha256_async_write::Sha256AsyncWrite;
+pub(crate) use tar::{TarReader, TarWriter};
 
 pub(crate) trait PathBufExt {
     fn joined(self, into: impl AsRef<Path>) -> Self;
@@ -0,0 +1,280 @@
+//! Minimal tar archive reader and writer for `.unitypackage` files.
+//!
+//! This supports ustar, GNU long names, and pax path records, which is enough for archives
+//! created by unity and other tools.
+
+use crate::io;
+use futures::prelude::*;
+
+const BLOCK_SIZE: usize = 512;
+
+pub(crate) struct TarEntry {
+    pub path: Box<str>,
+    pub is_dir: bool,
+    pub content: Vec<u8>,
+}
+
+pub(crate) struct TarReader<R> {
+    reader: R,
+    finished: bool,
+}
+
+impl<R: AsyncRead + Unpin> TarReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            finished: false,
+        }
+    }
+
+    /// Reads the next file or directory entry. Other kind of entries like symlinks are skipped.
+    pub async fn next_entry(&mut self) -> io::Result<Option<TarEntry>> {
+        let mut long_path = None::<Box<str>>;
+
+        loop {
+            if self.finished {
+                return Ok(None);
+            }
+
+            let mut header = [0u8; BLOCK_SIZE];
+            self.reader.read_exact(&mut header).await?;
+
+            if header.iter().all(|&x| x == 0) {
+                self.finished = true;
+                return Ok(None);
+            }
+
+            verify_checksum(&header)?;
+
+            let size = parse_number(&header[124..136])?;
+            let type_flag = header[156];
+            let content = self.read_content(size).await?;
+
+            match type_flag {
+                b'L' => {
+                    long_path = Some(null_terminated_str(&content)?.into());
+                }
+                b'x' => {
+                    if let Some(path) = parse_pax_path(&content)? {
+                        long_path = Some(path.into());
+                    }
+                }
+                b'0' | b'\0' | b'5' => {
+                    let path = match long_path.take() {
+                        Some(path) => path,
+                        None => header_path(&header)?,
+                    };
+                    return Ok(Some(TarEntry {
+                        path,
+                        is_dir: type_flag == b'5',
+                        content,
+                    }));
+                }
+                _ => {
+                    // global pax headers, links, devices, etc. are not used for unitypackage
+                    long_path = None;
+                }
+            }
+        }
+    }
+
+    async fn read_content(&mut self, size: u64) -> io::Result<Vec<u8>> {
+        // the size is not trusted so the buffer grows with the actual content
+        let mut content = Vec::new();
+        (&mut self.reader)
+            .take(size)
+            .read_to_end(&mut content)
+            .await?;
+        if content.len() as u64 != size {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "bad tar archive: unexpected end of entry",
+            ));
+        }
+
+        let padding = (BLOCK_SIZE - content.len() % BLOCK_SIZE) % BLOCK_SIZE;
+        let mut padding_buffer = [0u8; BLOCK_SIZE];
+        self.reader
+            .read_exact(&mut padding_buffer[..padding])
+            .await?;
+
+        Ok(content)
+    }
+}
+
+pub(crate) struct TarWriter<W> {
+    writer: W,
+}
+
+impl<W: AsyncWrite + Unpin> TarWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    pub async fn write_file(&mut self, path: &str, content: &[u8]) -> io::Result<()> {
+        self.write_entry(path, b'0', content).await
+    }
+
+    pub async fn write_dir(&mut self, path: &str) -> io::Result<()> {
+        self.write_entry(&format!("{}/", path.trim_end_matches('/')), b'5', &[])
+            .await
+    }
+
+    /// Writes the end of archive and returns the inner writer.
+    pub async fn finish(mut self) -> io::Result<W> {
+        self.writer.write_all(&[0u8; BLOCK_SIZE * 2]).await?;
+        self.writer.flush().await?;
+        Ok(self.writer)
+    }
+
+    async fn write_entry(&mut self, path: &str, type_flag: u8, content: &[u8]) -> io::Result<()> {
+        let name = if path.len() < 100 {
+            path
+        } else {
+            // GNU long name extension
+            let mut long_name = path.as_bytes().to_vec();
+            long_name.push(0);
+            self.write_header("././@LongLink", b'L', long_name.len())
+                .await?;
+            self.write_content(&long_name).await?;
+            // the name in the header is ignored by readers but we keep it valid utf8
+            let mut len = 99;
+            while !path.is_char_boundary(len) {
+                len -= 1;
+            }
+            &path[..len]
+        };
+
+        self.write_header(name, type_flag, content.len()).await?;
+        self.write_content(content).await
+    }
+
+    async fn write_header(&mut self, name: &str, type_flag: u8, size: usize) -> io::Result<()> {
+        fn put_octal(field: &mut [u8], value: u64) {
+            let width = field.len() - 1;
+            let formatted = format!("{value:0width$o}");
+            field[..width].copy_from_slice(&formatted.as_bytes()[formatted.len() - width..]);
+            field[width] = 0;
+        }
+
+        let mut header = [0u8; BLOCK_SIZE];
+        header[..name.len()].copy_from_slice(name.as_bytes());
+        put_octal(
+            &mut header[100..108],
+            if type_flag == b'5' { 0o755 } else { 0o644 },
+        );
+        put_octal(&mut header[108..116], 0);
+        put_octal(&mut header[116..124], 0);
+        put_octal(&mut header[124..136], size as u64);
+        put_octal(&mut header[136..148], 0);
+        header[156] = type_flag;
+        header[257..263].copy_from_slice(b"ustar\0");
+        header[263..265].copy_from_slice(b"00");
+
+        header[148..156].fill(b' ');
+        let checksum = header.iter().map(|&x| u64::from(x)).sum::<u64>();
+        put_octal(&mut header[148..155], checksum);
+        header[155] = b' ';
+
+        self.writer.write_all(&header).await
+    }
+
+    async fn write_content(&mut self, content: &[u8]) -> io::Result<()> {
+        self.writer.write_all(content).await?;
+        let padding = (BLOCK_SIZE - content.len() % BLOCK_SIZE) % BLOCK_SIZE;
+        self.writer.write_all(&[0u8; BLOCK_SIZE][..padding]).await
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("bad tar archive: {message}"),
+    )
+}
+
+fn verify_checksum(header: &[u8; BLOCK_SIZE]) -> io::Result<()> {
+    let expected = parse_number(&header[148..156])?;
+    // the checksum field itself is treated as spaces
+    let actual = (header.iter().enumerate())
+        .map(|(i, &x)| if (148..156).contains(&i) { b' ' } else { x })
+        .map(u64::from)
+        .sum::<u64>();
+    if expected != actual {
+        return Err(invalid_data("checksum mismatch"));
+    }
+    Ok(())
+}
+
+fn parse_number(field: &[u8]) -> io::Result<u64> {
+    if field[0] & 0x80 != 0 {
+        // base-256 encoding used by GNU tar for big numbers
+        let mut value = u64::from(field[0] & 0x7f);
+        for &x in &field[1..] {
+            value = (value.checked_mul(256)).ok_or_else(|| invalid_data("too big number"))?;
+            value |= u64::from(x);
+        }
+        return Ok(value);
+    }
+
+    let field = std::str::from_utf8(field).map_err(|_| invalid_data("bad number"))?;
+    let field = field.trim_matches(|c| c == '\0' || c == ' ');
+    if field.is_empty() {
+        return Ok(0);
+    }
+    u64::from_str_radix(field, 8).map_err(|_| invalid_data("bad number"))
+}
+
+fn null_terminated_str(bytes: &[u8]) -> io::Result<&str> {
+    let len = bytes.iter().position(|&x| x == 0).unwrap_or(bytes.len());
+    std::str::from_utf8(&bytes[..len]).map_err(|_| invalid_data("path is not utf8"))
+}
+
+fn header_path(header: &[u8; BLOCK_SIZE]) -> io::Result<Box<str>> {
+    let name = null_terminated_str(&header[0..100])?;
+    if &header[257..262] == b"ustar" {
+        let prefix = null_terminated_str(&header[345..500])?;
+        if !prefix.is_empty() {
+            return Ok(format!("{prefix}/{name}").into());
+        }
+    }
+    Ok(name.into())
+}
+
+fn parse_pax_path(mut records: &[u8]) -> io::Result<Option<String>> {
+    let mut path = None;
+    // each record is "<length> <key>=<value>\n" where length includes itself
+    while !records.is_empty() {
+        let space = (records.iter().position(|&x| x == b' '))
+            .ok_or_else(|| invalid_data("bad pax record"))?;
+        let len = std::str::from_utf8(&records[..space])
+            .ok()
+            .and_then(|x| x.parse::<usize>().ok())
+            .filter(|&len| len > space + 1 && len <= records.len())
+            .ok_or_else(|| invalid_data("bad pax record"))?;
+        let record = &records[space + 1..len - 1];
+        if let Some(value) = record.strip_prefix(b"path=") {
+            let value = std::str::from_utf8(value).map_err(|_| invalid_data("path is not utf8"))?;
+            path = Some(value.to_owned());
+        }
+        records = &records[len..];
+    }
+    Ok(path)
+}
+
+#[test]
+fn truncated_entry_with_huge_size() {
+    futures::executor::block_on(async {
+        let mut writer = TarWriter::new(Vec::new());
+        writer
+            .write_header("file", b'0', 0o77777777777)
+            .await
+            .unwrap();
+        let mut archive = writer.writer;
+        archive.extend_from_slice(b"not so big");
+
+        let mut reader = TarReader::new(archive.as_slice());
+        let err = reader.next_entry().await.err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    })
+}
@@ -17,6 +17,11 @@ impl DependencyRange {
         })
     }
 
+    /// create from range
+    pub fn range(range: VersionRange) -> DependencyRange {
+        Self(range)
+    }
+
     pub fn as_single_version(&self) -> Option<Version> {
         let [ComparatorSet(the_set)] = self.0.comparators.as_slice() else {
             return None;
@@ -54,6 +59,75 @@ impl Display for DependencyRange {
     }
 }
 
+/// The style of the range written to `dependencies` when the package is installed.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum DependencyRangeStyle {
+    /// The installed version, which means the version or later. (`x.y.z`)
+    #[default]
+    Version,
+    /// Only the installed version. (`=x.y.z`)
+    Exact,
+    /// The installed version and later versions with the same major version. (`^x.y.z`)
+    Caret,
+    /// The installed version and later versions with the same minor version. (`~x.y.z`)
+    Tilde,
+    /// The specified range regardless of the installed version.
+    ///
+    /// This is only for the packages explicitly requested so this cannot be parsed from string.
+    Range(VersionRange),
+}
+
+impl DependencyRangeStyle {
+    /// Returns the range to be written to `dependencies` for the installed version.
+    pub fn range_for(&self, version: &Version) -> DependencyRange {
+        let single = |comparator: fn(PartialVersion) -> Comparator| {
+            DependencyRange(VersionRange {
+                comparators: vec![ComparatorSet(vec![comparator(PartialVersion::from(
+                    version.clone(),
+                ))])],
+            })
+        };
+
+        match self {
+            DependencyRangeStyle::Version => DependencyRange::version(version.clone()),
+            DependencyRangeStyle::Exact => single(Comparator::Exact),
+            DependencyRangeStyle::Caret => single(Comparator::Caret),
+            DependencyRangeStyle::Tilde => single(Comparator::Tilde),
+            DependencyRangeStyle::Range(range) => DependencyRange(range.clone()),
+        }
+    }
+}
+
+impl Display for DependencyRangeStyle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DependencyRangeStyle::Version => f.write_str("version"),
+            DependencyRangeStyle::Exact => f.write_str("exact"),
+            DependencyRangeStyle::Caret => f.write_str("caret"),
+            DependencyRangeStyle::Tilde => f.write_str("tilde"),
+            DependencyRangeStyle::Range(range) => Display::fmt(range, f),
+        }
+    }
+}
+
+impl FromStr for DependencyRangeStyle {
+    type Err = ParseVersionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "version" => Ok(Self::Version),
+            "exact" => Ok(Self::Exact),
+            "caret" => Ok(Self::Caret),
+            "tilde" => Ok(Self::Tilde),
+            _ => Err(ParseVersionError::invalid()),
+        }
+    }
+}
+
+serialize_to_string!(DependencyRangeStyle);
+deserialize_from_str!(DependencyRangeStyle, "dependency range style");
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct VersionRange {
     comparators: Vec<ComparatorSet>,
@@ -68,6 +142,32 @@ impl VersionRange {
         }
     }
 
+    /// The version and later versions with the same major and minor version. (`~x.y.z`)
+    pub fn same_minor_or_later(version: Version) -> Self {
+        Self {
+            comparators: vec![ComparatorSet(vec![Comparator::Tilde(
+                PartialVersion::from(version),
+            )])],
+        }
+    }
+
+    /// The version and later versions with the same major version. (`~x >=x.y.z`)
+    pub fn same_major_or_later(version: Version) -> Self {
+        let major = PartialVersion {
+            major: Segment::new(version.major).unwrap(),
+            minor: Segment::NOT_EXISTS,
+            patch: Segment::NOT_EXISTS,
+            pre: Prerelease::EMPTY,
+            build: BuildMetadata::EMPTY,
+        };
+        Self {
+            comparators: vec![ComparatorSet(vec![
+                Comparator::Tilde(major),
+                Comparator::GreaterThanOrEqual(PartialVersion::from(version)),
+            ])],
+        }
+    }
+
     pub fn contains_pre(&self) -> bool {
         self.comparators.iter().any(ComparatorSet::contains_pre)
     }
@@ -694,6 +794,49 @@ impl From<Version> for PartialVersion {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_upgrade_ranges() {
+        let same_minor = VersionRange::same_minor_or_later(Version::new(1, 2, 3));
+        assert!(same_minor.matches(&Version::new(1, 2, 3)));
+        assert!(same_minor.matches(&Version::new(1, 2, 10)));
+        assert!(!same_minor.matches(&Version::new(1, 2, 2)));
+        assert!(!same_minor.matches(&Version::new(1, 3, 0)));
+
+        let same_major = VersionRange::same_major_or_later(Version::new(0, 2, 3));
+        assert!(same_major.matches(&Version::new(0, 2, 3)));
+        assert!(same_major.matches(&Version::new(0, 5, 0)));
+        assert!(!same_major.matches(&Version::new(0, 2, 2)));
+        assert!(!same_major.matches(&Version::new(1, 0, 0)));
+        let next_beta = Version::new_pre(1, 0, 0, Prerelease::from_str("beta").unwrap());
+        assert!(!same_major.match_pre(&next_beta, true));
+    }
+
+    #[test]
+    fn test_dependency_range_style() {
+        fn test(style: &str, expected: &str) {
+            let style = DependencyRangeStyle::from_str(style).unwrap();
+            let range = style.range_for(&Version::new(1, 2, 3));
+            assert_eq!(range.to_string(), expected);
+        }
+
+        test("version", "1.2.3");
+        test("exact", "=1.2.3");
+        test("caret", "^1.2.3");
+        test("tilde", "~1.2.3");
+
+        // the range is not a style since it does not depend on the installed version
+        assert!(DependencyRangeStyle::from_str(">=1.0.0 <2.0.0").is_err());
+        let range = DependencyRangeStyle::Range(VersionRange::from_str(">=1.0.0 <2.0.0").unwrap());
+        assert_eq!(
+            range.range_for(&Version::new(1, 2, 3)).to_string(),
+            ">=1.0.0 <2.0.0"
+        );
+
+        let caret = DependencyRangeStyle::Caret.range_for(&Version::new(1, 2, 3));
+        assert!(caret.matches(&Version::new(1, 9, 0)));
+        assert!(!caret.matches(&Version::new(2, 0, 0)));
+    }
+
     #[test]
     fn test_match_positive() {
         fn test(range: &str, version: &str) {
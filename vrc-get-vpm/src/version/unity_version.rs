@@ -79,6 +79,15 @@ impl UnityVersion {
         }
     }
 
+    /// Parses `m_EditorVersion` in the contents of `ProjectSettings/ProjectVersion.txt`.
+    pub fn parse_project_version(text: &str) -> Option<Self> {
+        let (_, version_info) = text.split_once("m_EditorVersion:")?;
+        let version_info_end = version_info
+            .find(|x: char| x == '\r' || x == '\n')
+            .unwrap_or(version_info.len());
+        Self::parse(version_info[..version_info_end].trim())
+    }
+
     pub fn major(self) -> u16 {
         self.major
     }
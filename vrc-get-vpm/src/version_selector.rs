@@ -1,5 +1,7 @@
+use crate::unity_compatibility::{unity_incompatibility, UnityIncompatibility};
 use crate::version::{UnityVersion, Version, VersionRange};
-use crate::{unity_compatible, PackageManifest};
+use crate::PackageManifest;
+use std::fmt;
 
 #[derive(Clone, Copy)]
 pub struct VersionSelector<'a> {
@@ -21,6 +23,11 @@ enum SelectorInner<'a> {
         project_unity: Option<UnityVersion>,
         ranges: &'a [&'a VersionRange],
     },
+    LatestInRanges {
+        project_unity: Option<UnityVersion>,
+        ranges: &'a [VersionRange],
+        include_prerelease: bool,
+    },
 }
 
 impl<'a> VersionSelector<'a> {
@@ -56,6 +63,21 @@ impl<'a> VersionSelector<'a> {
             },
         }
     }
+
+    /// Selects the latest version in all the ranges like [`latest_for`](Self::latest_for).
+    pub fn latest_in_ranges_for(
+        unity_version: Option<UnityVersion>,
+        ranges: &'a [VersionRange],
+        include_prerelease: bool,
+    ) -> Self {
+        Self {
+            inner: SelectorInner::LatestInRanges {
+                project_unity: unity_version,
+                ranges,
+                include_prerelease,
+            },
+        }
+    }
 }
 
 impl<'a> VersionSelector<'a> {
@@ -69,41 +91,103 @@ impl<'a> VersionSelector<'a> {
 
 impl<'a> VersionSelector<'a> {
     pub fn satisfies(&self, package: &PackageManifest) -> bool {
-        fn unity_and_yank(package: &PackageManifest, project_unity: Option<UnityVersion>) -> bool {
+        self.mismatch(package).is_none()
+    }
+
+    /// Checks the package with the selector and returns why the package is not selected.
+    ///
+    /// Returns `None` if the package is selected.
+    pub fn mismatch(&self, package: &PackageManifest) -> Option<VersionMismatch> {
+        fn unity_and_yank(
+            package: &PackageManifest,
+            project_unity: Option<UnityVersion>,
+        ) -> Option<VersionMismatch> {
             if package.is_yanked() {
-                return false;
+                return Some(VersionMismatch::Yanked);
             }
 
             if let Some(unity) = project_unity {
-                if !unity_compatible(package, unity) {
-                    return false;
+                if let Some(incompatibility) = unity_incompatibility(package, unity) {
+                    return Some(VersionMismatch::Unity(incompatibility));
                 }
             }
 
-            true
+            None
         }
 
+        fn check(matches: bool, mismatch: VersionMismatch) -> Option<VersionMismatch> {
+            if matches {
+                None
+            } else {
+                Some(mismatch)
+            }
+        }
+
+        let version = package.version();
+
         match self.inner {
-            SelectorInner::Specific(finding) => finding == package.version(),
-            SelectorInner::Latest {
-                include_prerelease: true,
-                project_unity,
-            } => unity_and_yank(package, project_unity),
+            SelectorInner::Specific(finding) => check(finding == version, VersionMismatch::Version),
             SelectorInner::Latest {
-                include_prerelease: false,
+                include_prerelease,
                 project_unity,
-            } => package.version().is_stable() && unity_and_yank(package, project_unity),
+            } => check(
+                include_prerelease || version.is_stable(),
+                VersionMismatch::Prerelease,
+            )
+            .or_else(|| unity_and_yank(package, project_unity)),
             SelectorInner::Range {
                 range,
                 project_unity,
-            } => range.matches(package.version()) && unity_and_yank(package, project_unity),
+            } => check(range.matches(version), VersionMismatch::Version)
+                .or_else(|| unity_and_yank(package, project_unity)),
             SelectorInner::Ranges {
                 ranges,
                 project_unity,
-            } => {
-                ranges.iter().all(|x| x.matches(package.version()))
-                    && unity_and_yank(package, project_unity)
-            }
+            } => check(
+                ranges.iter().all(|x| x.matches(version)),
+                VersionMismatch::Version,
+            )
+            .or_else(|| unity_and_yank(package, project_unity)),
+            SelectorInner::LatestInRanges {
+                ranges,
+                project_unity,
+                include_prerelease,
+            } => check(
+                include_prerelease || version.is_stable(),
+                VersionMismatch::Prerelease,
+            )
+            .or_else(|| {
+                check(
+                    (ranges.iter()).all(|x| x.match_pre(version, include_prerelease)),
+                    VersionMismatch::Version,
+                )
+            })
+            .or_else(|| unity_and_yank(package, project_unity)),
+        }
+    }
+}
+
+/// The reason why the package is not selected by [`VersionSelector`].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum VersionMismatch {
+    /// The version is not the requested one or not in the requested ranges.
+    Version,
+    /// The version is a prerelease but prereleases are not requested.
+    Prerelease,
+    /// The version is yanked.
+    Yanked,
+    /// The package is not compatible with the unity of the project.
+    Unity(UnityIncompatibility),
+}
+
+impl fmt::Display for VersionMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VersionMismatch::Version => f.write_str("not in the requested range"),
+            VersionMismatch::Prerelease => f.write_str("prerelease"),
+            VersionMismatch::Yanked => f.write_str("yanked"),
+            VersionMismatch::Unity(incompatibility) => fmt::Display::fmt(incompatibility, f),
         }
     }
 }
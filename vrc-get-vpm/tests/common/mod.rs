@@ -2,19 +2,20 @@
 #![allow(dead_code)]
 #![allow(unused_imports)]
 
-mod package_collection;
-mod virtual_file_system;
-mod virtual_project_builder;
-
-pub use package_collection::PackageCollection;
-pub use package_collection::PackageCollectionBuilder;
-pub use virtual_file_system::VirtualFileSystem;
-pub use virtual_project_builder::VirtualProjectBuilder;
+pub use vrc_get_vpm::memory_io::PackageCollectionBuilder;
+pub use vrc_get_vpm::memory_io::VirtualFileSystem;
+pub use vrc_get_vpm::memory_io::VirtualPackageCollection as PackageCollection;
+pub use vrc_get_vpm::memory_io::VirtualProjectBuilder;
 
 use vrc_get_vpm::unity_project::pending_project_changes::RemoveReason;
 use vrc_get_vpm::unity_project::PendingProjectChanges;
 use vrc_get_vpm::version::{DependencyRange, Version};
-use vrc_get_vpm::PackageInfo;
+use vrc_get_vpm::{PackageInfo, PackageManifest};
+
+/// Parses the `package.json` with the fields not supported by [`PackageManifest::new`].
+pub fn manifest(json: serde_json::Value) -> PackageManifest {
+    serde_json::from_value(json).unwrap()
+}
 
 pub fn assert_removed(result: &PendingProjectChanges, package: &str, reason: RemoveReason) {
     let package_change = result
@@ -0,0 +1,77 @@
+use crate::common::*;
+use futures::executor::block_on;
+use vrc_get_vpm::unity_project::GraphNodeKind;
+use vrc_get_vpm::version::Version;
+
+mod common;
+
+#[test]
+fn dependency_graph() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_dependency("com.vrchat.avatars", Version::new(3, 5, 0))
+            .add_dependency("com.anatawa12.tool", Version::new(1, 0, 0))
+            .add_locked(
+                "com.vrchat.avatars",
+                Version::new(3, 5, 0),
+                &[("com.vrchat.base", "^3.5.0")],
+            )
+            .add_locked("com.vrchat.base", Version::new(3, 4, 0), &[])
+            .add_locked(
+                "com.anatawa12.tool",
+                Version::new(1, 0, 0),
+                &[("com.anatawa12.library", "^1.0.0")],
+            )
+            .add_locked("com.anatawa12.old", Version::new(1, 0, 0), &[])
+            .add_package_json(
+                "com.anatawa12.tool",
+                r#"{"name":"com.anatawa12.tool","version":"1.0.0","legacyPackages":["com.anatawa12.old"]}"#,
+            )
+            .add_file(
+                "Packages/manifest.json",
+                r#"{"dependencies":{"com.unity.timeline":"1.2.3"}}"#,
+            )
+            .build()
+            .await
+            .unwrap();
+
+        let graph = project.dependency_graph(false);
+
+        let names = graph.nodes().iter().map(|x| x.name()).collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                "com.anatawa12.library",
+                "com.anatawa12.old",
+                "com.anatawa12.tool",
+                "com.vrchat.avatars",
+                "com.vrchat.base",
+            ]
+        );
+
+        let node = |name: &str| graph.get_node(name).unwrap();
+        assert!(node("com.vrchat.avatars").is_root());
+        assert!(!node("com.vrchat.base").is_root());
+        assert_eq!(node("com.anatawa12.library").kind(), GraphNodeKind::Missing);
+        assert_eq!(node("com.anatawa12.library").version(), None);
+        assert_eq!(
+            node("com.anatawa12.old").replaced_by(),
+            Some("com.anatawa12.tool")
+        );
+
+        let edge = |from: Option<&str>, to: &str| {
+            (graph.edges().iter())
+                .find(|x| x.from() == from && x.to() == to)
+                .unwrap()
+        };
+        assert_eq!(graph.edges().len(), 4);
+        assert!(!edge(None, "com.vrchat.avatars").is_conflict());
+        assert!(edge(Some("com.vrchat.avatars"), "com.vrchat.base").is_conflict());
+        assert!(edge(Some("com.anatawa12.tool"), "com.anatawa12.library").is_conflict());
+
+        let graph = project.dependency_graph(true);
+        let timeline = graph.get_node("com.unity.timeline").unwrap();
+        assert_eq!(timeline.kind(), GraphNodeKind::Upm);
+        assert_eq!(timeline.version(), Some(&Version::new(1, 2, 3)));
+    })
+}
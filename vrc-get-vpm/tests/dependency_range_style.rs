@@ -0,0 +1,121 @@
+use common::*;
+use futures::executor::block_on;
+use vrc_get_vpm::memory_io::{FakeHttpClient, VirtualFileSystem};
+use vrc_get_vpm::unity_project::AddPackageOperation;
+use vrc_get_vpm::version::{DependencyRange, DependencyRangeStyle, Version};
+use vrc_get_vpm::{Environment, PackageManifest};
+
+mod common;
+
+#[test]
+fn install_with_caret_style() {
+    block_on(async {
+        let mut project = VirtualProjectBuilder::new()
+            .add_dependency("com.vrchat.base", Version::new(1, 0, 0))
+            .add_locked("com.vrchat.base", Version::new(1, 0, 0), &[])
+            .build()
+            .await
+            .unwrap();
+
+        let collection = PackageCollectionBuilder::new()
+            .add(PackageManifest::new(
+                "com.vrchat.base",
+                Version::new(1, 0, 0),
+            ))
+            .add(PackageManifest::new(
+                "com.anatawa12.tool",
+                Version::new(1, 2, 3),
+            ))
+            .build();
+
+        project.set_dependency_range_style(DependencyRangeStyle::Caret);
+
+        let tool = collection.get_package("com.anatawa12.tool", Version::new(1, 2, 3));
+        let base = collection.get_package("com.vrchat.base", Version::new(1, 0, 0));
+
+        let resolve = project
+            .add_package_request(
+                &collection,
+                &[tool, base],
+                AddPackageOperation::InstallToDependencies,
+                false,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(resolve.package_changes().len(), 2);
+        assert_eq!(resolve.conflicts().len(), 0);
+
+        let dependency_range = |name: &str| {
+            let change = resolve.package_changes().get(name).unwrap();
+            change.as_install().unwrap().to_dependencies().cloned()
+        };
+
+        // new dependency is saved with the style
+        let expected = DependencyRange::range("^1.2.3".parse().unwrap());
+        assert_eq!(dependency_range("com.anatawa12.tool"), Some(expected));
+
+        // existing dependency at the same version is rewritten with the style
+        let expected = DependencyRange::range("^1.0.0".parse().unwrap());
+        assert_eq!(dependency_range("com.vrchat.base"), Some(expected));
+    })
+}
+
+#[test]
+fn resolve_template_with_tilde_style() {
+    block_on(async {
+        let mut project = VirtualProjectBuilder::new()
+            .add_dependency("com.vrchat.base", Version::new(1, 0, 0))
+            .build()
+            .await
+            .unwrap();
+
+        let collection = PackageCollectionBuilder::new()
+            .add(PackageManifest::new(
+                "com.vrchat.base",
+                Version::new(1, 0, 0),
+            ))
+            .add(PackageManifest::new(
+                "com.vrchat.base",
+                Version::new(1, 1, 0),
+            ))
+            .build();
+
+        project.set_dependency_range_style(DependencyRangeStyle::Tilde);
+
+        let resolve = project.resolve_request(&collection).await.unwrap();
+
+        let base = collection.get_package("com.vrchat.base", Version::new(1, 1, 0));
+        let change = resolve.package_changes().get("com.vrchat.base").unwrap();
+        let install = change.as_install().unwrap();
+        assert_eq!(install.install_package().unwrap().version(), base.version());
+        assert_eq!(
+            install.to_dependencies(),
+            Some(&DependencyRange::range("~1.1.0".parse().unwrap()))
+        );
+    })
+}
+
+#[test]
+fn invalid_style_in_settings() {
+    block_on(async {
+        let fs = VirtualFileSystem::new();
+        fs.add_file(
+            "vrc-get/settings.json".as_ref(),
+            br#"{"dependencyRangeStyle": ">=1.0.0"}"#,
+        )
+        .await
+        .unwrap();
+
+        // the invalid style does not prevent loading the environment
+        let mut env = Environment::load(None::<FakeHttpClient>, fs).await.unwrap();
+        assert_eq!(env.dependency_range_style(), &DependencyRangeStyle::Version);
+
+        // range cannot be saved to the settings
+        let range = DependencyRangeStyle::Range(">=1.0.0".parse().unwrap());
+        env.set_dependency_range_style(range).unwrap_err();
+        env.set_dependency_range_style(DependencyRangeStyle::Caret)
+            .unwrap();
+        assert_eq!(env.dependency_range_style(), &DependencyRangeStyle::Caret);
+    })
+}
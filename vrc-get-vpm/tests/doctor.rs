@@ -0,0 +1,113 @@
+use crate::common::*;
+use futures::executor::block_on;
+use std::path::Path;
+use vrc_get_vpm::unity_project::ProjectDiagnostic;
+use vrc_get_vpm::version::Version;
+use vrc_get_vpm::PackageManifest;
+
+mod common;
+
+const GUID_A: &str = "0123456789abcdef0123456789abcdef";
+const GUID_B: &str = "22222222222222222222222222222222";
+
+#[test]
+fn diagnose_packages() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_dependency("com.anatawa12.missing", Version::new(1, 0, 0))
+            .add_locked("com.anatawa12.missing", Version::new(1, 0, 0), &[])
+            .add_locked(
+                "com.anatawa12.mismatch",
+                Version::new(1, 0, 0),
+                &[("com.anatawa12.library", "^2.0.0")],
+            )
+            .add_package_json(
+                "com.anatawa12.mismatch",
+                r#"{"name":"com.anatawa12.mismatch","version":"0.9.0"}"#,
+            )
+            .add_dir("Packages/stray")
+            .build()
+            .await
+            .unwrap();
+
+        let collection = PackageCollectionBuilder::new().build();
+
+        let diagnostics = project.diagnose(&collection).await;
+
+        assert!(diagnostics.iter().any(|x| matches!(
+            x,
+            ProjectDiagnostic::MissingPackage { name, .. } if name.as_ref() == "com.anatawa12.missing"
+        )));
+        assert!(diagnostics.iter().any(|x| matches!(
+            x,
+            ProjectDiagnostic::VersionMismatch { name, installed, .. }
+                if name.as_ref() == "com.anatawa12.mismatch" && installed == &Version::new(0, 9, 0)
+        )));
+        assert!(diagnostics.iter().any(|x| matches!(
+            x,
+            ProjectDiagnostic::UnlockedPackage { folder, package_name: None } if folder.as_ref() == "stray"
+        )));
+        assert!(diagnostics.iter().any(|x| matches!(
+            x,
+            ProjectDiagnostic::UnsatisfiedDependency { package: Some(package), dependency, locked: None, .. }
+                if package.as_ref() == "com.anatawa12.mismatch" && dependency.as_ref() == "com.anatawa12.library"
+        )));
+        assert_eq!(diagnostics.len(), 4);
+    })
+}
+
+#[test]
+fn diagnose_and_fix_assets() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_locked("com.anatawa12.package", Version::new(1, 0, 0), &[])
+            .add_package_json(
+                "com.anatawa12.package",
+                r#"{"name":"com.anatawa12.package","version":"1.0.0"}"#,
+            )
+            .add_locked("com.anatawa12.missing", Version::new(1, 0, 0), &[])
+            .add_file("Assets/Old.meta", format!("guid: {GUID_A}\n"))
+            .add_file("Assets/Old/Tool.cs", "class Tool {}")
+            .add_file("Assets/Old/Tool.cs.meta", format!("guid: {GUID_B}\n"))
+            .add_file("Assets/Copy.cs", "class Tool {}")
+            .add_file("Assets/Copy.cs.meta", format!("guid: {GUID_B}\n"))
+            .build()
+            .await
+            .unwrap();
+
+        let collection = PackageCollectionBuilder::new()
+            .add(
+                PackageManifest::new("com.anatawa12.package", Version::new(1, 0, 0))
+                    .add_legacy_folder("Assets/Old", GUID_A),
+            )
+            .add(PackageManifest::new(
+                "com.anatawa12.missing",
+                Version::new(1, 0, 0),
+            ))
+            .build();
+
+        let diagnostics = project.diagnose(&collection).await;
+
+        assert!(diagnostics.iter().any(|x| matches!(
+            x,
+            ProjectDiagnostic::LegacyAsset { path, is_folder: true, .. } if path.as_ref() == Path::new("Assets/Old")
+        )));
+        assert!(diagnostics.iter().any(|x| matches!(
+            x,
+            ProjectDiagnostic::DuplicateGuid { guid, paths } if guid.as_ref() == GUID_B && paths.len() == 2
+        )));
+        assert_eq!(diagnostics.iter().filter(|x| x.is_fixable()).count(), 2);
+
+        let changes = project.doctor_fix_request(&collection).await;
+
+        let missing = collection.get_package("com.anatawa12.missing", Version::new(1, 0, 0));
+        assert_eq!(changes.package_changes().len(), 1);
+        assert_install_only(&changes, &missing);
+        assert_eq!(changes.remove_legacy_folders().len(), 1);
+        assert_eq!(
+            changes.remove_legacy_folders()[0].0.as_ref(),
+            Path::new("Assets/Old")
+        );
+        assert_eq!(changes.remove_legacy_files().len(), 0);
+    })
+}
@@ -0,0 +1,121 @@
+use crate::common::*;
+use futures::executor::block_on;
+use std::path::Path;
+use vrc_get_vpm::unity_project::{ExportUnityPackageErr, UnityPackage};
+use vrc_get_vpm::version::Version;
+
+mod common;
+
+const FOLDER_GUID: &str = "0123456789abcdef0123456789abcdef";
+const SCRIPT_GUID: &str = "22222222222222222222222222222222";
+
+#[test]
+fn export_folder() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_file("Assets/Tool.meta", format!("guid: {FOLDER_GUID}\n"))
+            .add_file("Assets/Tool/Tool.cs", "class Tool {}")
+            .add_file("Assets/Tool/Tool.cs.meta", format!("guid: {SCRIPT_GUID}\n"))
+            // ignored by unity
+            .add_file("Assets/Tool/Samples~/Sample.cs", "class Sample {}")
+            .add_file("Assets/Tool/.gitignore", "")
+            .add_file("Assets/Other.cs", "class Other {}")
+            .build()
+            .await
+            .unwrap();
+
+        let package = project
+            .export_unitypackage(&[Path::new("Assets/Tool")])
+            .await
+            .unwrap();
+
+        let mut archive = Vec::new();
+        package.write(&mut archive).await.unwrap();
+        let package = UnityPackage::read(archive.as_slice()).await.unwrap();
+
+        let assets = package.assets();
+        assert_eq!(assets.len(), 2);
+        assert_eq!(assets[0].path(), "Assets/Tool");
+        assert_eq!(assets[0].guid(), FOLDER_GUID);
+        assert!(assets[0].is_folder());
+        assert_eq!(assets[1].path(), "Assets/Tool/Tool.cs");
+        assert_eq!(assets[1].guid(), SCRIPT_GUID);
+        assert_eq!(assets[1].asset(), Some(b"class Tool {}".as_slice()));
+        assert_eq!(
+            assets[1].meta(),
+            Some(format!("guid: {SCRIPT_GUID}\n").as_bytes())
+        );
+    })
+}
+
+#[test]
+fn missing_meta() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_file("Assets/Tool/Tool.cs", "class Tool {}")
+            .build()
+            .await
+            .unwrap();
+
+        let err = project
+            .export_unitypackage(&[Path::new("Assets/Tool")])
+            .await
+            .unwrap_err();
+        let ExportUnityPackageErr::MissingMeta(paths) = err else {
+            panic!("unexpected error: {err}");
+        };
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths[0].as_ref(), Path::new("Assets/Tool/Tool.cs"));
+    })
+}
+
+#[test]
+fn export_installed_package() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_dependency("com.anatawa12.gists", Version::new(1, 0, 0))
+            .add_locked("com.anatawa12.gists", Version::new(1, 0, 0), &[])
+            .add_package_json(
+                "com.anatawa12.gists",
+                r#"{"name": "com.anatawa12.gists", "version": "1.0.0"}"#,
+            )
+            .add_file(
+                "Packages/com.anatawa12.gists/package.json.meta",
+                format!("guid: {SCRIPT_GUID}\n"),
+            )
+            .build()
+            .await
+            .unwrap();
+
+        let export = |name, destination: &'static str| {
+            project.export_package_unitypackage(name, Path::new(destination))
+        };
+        let paths = |package: &UnityPackage| {
+            (package.assets().iter())
+                .map(|x| x.path().to_owned())
+                .collect::<Vec<_>>()
+        };
+
+        let package = export("com.anatawa12.gists", "Assets/Gists").await.unwrap();
+        assert_eq!(paths(&package), ["Assets/Gists/package.json"]);
+
+        let package =
+            (export("com.anatawa12.gists", "Packages/com.anatawa12.gists").await).unwrap();
+        assert_eq!(
+            paths(&package),
+            ["Packages/com.anatawa12.gists/package.json"]
+        );
+
+        let err = export("com.anatawa12.gists", "Library/Gists")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ExportUnityPackageErr::BadDestination(_)));
+        let err = export("com.anatawa12.gists", "Assets/../Library")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ExportUnityPackageErr::BadDestination(_)));
+
+        let err = export("com.vrchat.base", "Assets/Base").await.unwrap_err();
+        assert!(matches!(err, ExportUnityPackageErr::NotInstalled(_)));
+    })
+}
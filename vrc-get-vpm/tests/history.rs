@@ -0,0 +1,188 @@
+use futures::executor::block_on;
+use serde_json::json;
+use url::Url;
+use vrc_get_vpm::memory_io::{
+    create_package_zip, FakeHttpClient, RepositoryBuilder, VirtualFileSystem, VirtualProjectBuilder,
+};
+use vrc_get_vpm::unity_project::{AddPackageOperation, LockSnapshot};
+use vrc_get_vpm::version::Version;
+use vrc_get_vpm::{Environment, PackageCollection, UnityProject, VersionSelector};
+
+#[test]
+fn undo_upgrade_with_history() {
+    block_on(async {
+        let http = FakeHttpClient::new();
+
+        let base_1_0_0 = json!({ "name": "com.vrchat.base", "version": "1.0.0" });
+        let base_1_1_0 = json!({ "name": "com.vrchat.base", "version": "1.1.0" });
+        let zip_1_0_0 = create_package_zip(&base_1_0_0, &[]).await;
+        let zip_1_1_0 = create_package_zip(&base_1_1_0, &[]).await;
+        let official = RepositoryBuilder::new(
+            Url::parse("https://packages.vrchat.com/official?download").unwrap(),
+        )
+        .with_id("com.vrchat.repos.official")
+        .add_package_with_zip(base_1_0_0, zip_1_0_0)
+        .add_package_with_zip(base_1_1_0, zip_1_1_0);
+        let curated = RepositoryBuilder::new(
+            Url::parse("https://packages.vrchat.com/curated?download").unwrap(),
+        )
+        .with_id("com.vrchat.repos.curated");
+
+        http.add_repository(&official);
+        http.add_repository(&curated);
+
+        let mut env = Environment::load(Some(http), VirtualFileSystem::new())
+            .await
+            .unwrap();
+        env.load_package_infos(true).await.unwrap();
+
+        let project_fs = VirtualFileSystem::new();
+        let mut project = VirtualProjectBuilder::new()
+            .build_on(project_fs.clone())
+            .await
+            .unwrap();
+
+        let version_1_0_0 = Version::new(1, 0, 0);
+        let version_1_1_0 = Version::new(1, 1, 0);
+
+        // install 1.0.0, then upgrade to 1.1.0
+        for (version, operation, reason) in [
+            (
+                &version_1_0_0,
+                AddPackageOperation::InstallToDependencies,
+                "install",
+            ),
+            (
+                &version_1_1_0,
+                AddPackageOperation::UpgradeLocked,
+                "upgrade",
+            ),
+        ] {
+            let package = env
+                .find_package_by_name(
+                    "com.vrchat.base",
+                    VersionSelector::specific_version(version),
+                )
+                .unwrap();
+            let changes = project
+                .add_package_request(&env, &[package], operation, false)
+                .await
+                .unwrap();
+            project.set_history_reason(Some(reason.into()));
+            project.apply_pending_changes(&env, changes).await.unwrap();
+        }
+        project.save().await.unwrap();
+
+        let mut project = UnityProject::load(project_fs.clone()).await.unwrap();
+        let history = project.history().await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].reason(), Some("install"));
+        assert_eq!(history[0].before().get_locked("com.vrchat.base"), None);
+        assert_eq!(history[1].reason(), Some("upgrade"));
+        assert_eq!(
+            history[1].before().get_locked("com.vrchat.base"),
+            Some(&version_1_0_0)
+        );
+        assert_eq!(
+            history[1].after().get_locked("com.vrchat.base"),
+            Some(&version_1_1_0)
+        );
+
+        // undo the upgrade
+        let changes = project
+            .restore_lock_request(&env, history[1].before())
+            .await
+            .unwrap();
+        project.apply_pending_changes(&env, changes).await.unwrap();
+        project.save().await.unwrap();
+
+        let project = UnityProject::load(project_fs).await.unwrap();
+        let locked = project.get_locked("com.vrchat.base").unwrap();
+        assert_eq!(locked.version(), &version_1_0_0);
+        assert_eq!(project.history().await.unwrap().len(), 3);
+    })
+}
+
+#[test]
+fn lock_snapshot_from_vpm_manifest() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_dependency("com.vrchat.avatars", Version::new(3, 5, 0))
+            .add_locked(
+                "com.vrchat.avatars",
+                Version::new(3, 5, 0),
+                &[("com.vrchat.base", "3.5.0")],
+            )
+            .add_locked("com.vrchat.base", Version::new(3, 5, 0), &[])
+            .build()
+            .await
+            .unwrap();
+
+        let snapshot = LockSnapshot::from_vpm_manifest(
+            br#"{
+                "dependencies": { "com.vrchat.avatars": { "version": "3.5.0" } },
+                "locked": {
+                    "com.vrchat.avatars": {
+                        "version": "3.5.0",
+                        "dependencies": { "com.vrchat.base": "3.5.0" }
+                    },
+                    "com.vrchat.base": { "version": "3.5.0" }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(snapshot, project.lock_snapshot());
+        assert_eq!(
+            snapshot.get_locked("com.vrchat.base"),
+            Some(&Version::new(3, 5, 0))
+        );
+    })
+}
+
+#[test]
+fn undo_install_of_locked_package() {
+    block_on(async {
+        let env = Environment::load(None::<FakeHttpClient>, VirtualFileSystem::new())
+            .await
+            .unwrap();
+
+        // com.vrchat.base was locked as a dependency of com.vrchat.avatars before installed
+        let mut project = VirtualProjectBuilder::new()
+            .add_dependency("com.vrchat.avatars", Version::new(1, 0, 0))
+            .add_dependency("com.vrchat.base", Version::new(1, 0, 0))
+            .add_locked(
+                "com.vrchat.avatars",
+                Version::new(1, 0, 0),
+                &[("com.vrchat.base", "1.0.0")],
+            )
+            .add_locked("com.vrchat.base", Version::new(1, 0, 0), &[])
+            .build()
+            .await
+            .unwrap();
+
+        let before = LockSnapshot::from_vpm_manifest(
+            br#"{
+                "dependencies": { "com.vrchat.avatars": { "version": "1.0.0" } },
+                "locked": {
+                    "com.vrchat.avatars": {
+                        "version": "1.0.0",
+                        "dependencies": { "com.vrchat.base": "1.0.0" }
+                    },
+                    "com.vrchat.base": { "version": "1.0.0" }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let changes = project.restore_lock_request(&env, &before).await.unwrap();
+        assert_eq!(changes.package_changes().len(), 0);
+        assert_eq!(
+            changes.remove_from_dependencies(),
+            &["com.vrchat.base".into()]
+        );
+
+        project.apply_pending_changes(&env, changes).await.unwrap();
+        assert_eq!(project.lock_snapshot(), before);
+    })
+}
@@ -0,0 +1,129 @@
+use common::*;
+use futures::executor::block_on;
+use vrc_get_vpm::memory_io::VirtualFileSystem;
+use vrc_get_vpm::unity_project::AddPackageOperation;
+use vrc_get_vpm::version::Version;
+use vrc_get_vpm::{PackageManifest, UnityProject};
+
+mod common;
+
+#[test]
+fn hold_saved_to_project_settings() {
+    block_on(async {
+        let fs = VirtualFileSystem::new();
+        let mut project = VirtualProjectBuilder::new()
+            .add_dependency("com.vrchat.avatars", Version::new(1, 0, 0))
+            .add_locked("com.vrchat.avatars", Version::new(1, 0, 0), &[])
+            .add_locked("com.vrchat.base", Version::new(1, 0, 0), &[])
+            .build_on(fs.clone())
+            .await
+            .unwrap();
+
+        project.hold_package("com.vrchat.avatars", Some("event build".into()));
+        project.hold_package("com.vrchat.base", None);
+        project.save().await.unwrap();
+
+        let mut project = UnityProject::load(fs.clone()).await.unwrap();
+        assert!(project.is_held("com.vrchat.avatars"));
+        assert_eq!(
+            project.hold_reason("com.vrchat.avatars"),
+            Some("event build")
+        );
+        assert!(project.is_held("com.vrchat.base"));
+        assert_eq!(project.hold_reason("com.vrchat.base"), None);
+
+        assert!(project.unhold_package("com.vrchat.avatars"));
+        assert!(!project.unhold_package("com.vrchat.avatars"));
+        project.save().await.unwrap();
+
+        let project = UnityProject::load(fs).await.unwrap();
+        assert_eq!(
+            project.held_packages().collect::<Vec<_>>(),
+            [("com.vrchat.base", None)]
+        );
+    })
+}
+
+#[test]
+fn held_package_required_by_upgrade() {
+    block_on(async {
+        let mut project = VirtualProjectBuilder::new()
+            .add_dependency("com.anatawa12.tool", Version::new(1, 0, 0))
+            .add_locked(
+                "com.anatawa12.tool",
+                Version::new(1, 0, 0),
+                &[("com.anatawa12.library", "1.0.0")],
+            )
+            .add_locked("com.anatawa12.library", Version::new(1, 0, 0), &[])
+            .build()
+            .await
+            .unwrap();
+        project.hold_package("com.anatawa12.library", None);
+
+        let collection = PackageCollectionBuilder::new()
+            .add(
+                PackageManifest::new("com.anatawa12.tool", Version::new(2, 0, 0))
+                    .add_vpm_dependency("com.anatawa12.library", ">=2.0.0"),
+            )
+            .add(PackageManifest::new(
+                "com.anatawa12.library",
+                Version::new(1, 0, 0),
+            ))
+            .add(PackageManifest::new(
+                "com.anatawa12.library",
+                Version::new(2, 0, 0),
+            ))
+            .build();
+
+        // upgrading the tool, which is not held, requires the newer held library
+        let tool = collection.get_package("com.anatawa12.tool", Version::new(2, 0, 0));
+        let result = project
+            .add_package_request(
+                &collection,
+                &[tool],
+                AddPackageOperation::UpgradeLocked,
+                false,
+            )
+            .await
+            .unwrap();
+
+        let held = project.held_changes(&result);
+        assert_eq!(held.len(), 1);
+        assert_eq!(held[0].name(), "com.anatawa12.library");
+        assert_eq!(held[0].version(), &Version::new(2, 0, 0));
+    })
+}
+
+#[test]
+fn held_package_not_changed() {
+    block_on(async {
+        let mut project = VirtualProjectBuilder::new()
+            .add_dependency("com.anatawa12.tool", Version::new(1, 0, 0))
+            .add_locked("com.anatawa12.tool", Version::new(1, 0, 0), &[])
+            .add_locked("com.anatawa12.library", Version::new(1, 0, 0), &[])
+            .build()
+            .await
+            .unwrap();
+        project.hold_package("com.anatawa12.library", None);
+
+        let collection = PackageCollectionBuilder::new()
+            .add(PackageManifest::new(
+                "com.anatawa12.tool",
+                Version::new(2, 0, 0),
+            ))
+            .build();
+
+        let tool = collection.get_package("com.anatawa12.tool", Version::new(2, 0, 0));
+        let result = project
+            .add_package_request(
+                &collection,
+                &[tool],
+                AddPackageOperation::UpgradeLocked,
+                false,
+            )
+            .await
+            .unwrap();
+
+        assert!(project.held_changes(&result).is_empty());
+    })
+}
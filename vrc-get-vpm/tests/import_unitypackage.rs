@@ -0,0 +1,156 @@
+use crate::common::*;
+use futures::executor::block_on;
+use vrc_get_vpm::io::IoTrait;
+use vrc_get_vpm::memory_io::UnityPackageBuilder;
+use vrc_get_vpm::unity_project::{ImportAssetStatus, ImportFilter, UnityPackage};
+
+mod common;
+
+const FOLDER_GUID: &str = "0123456789abcdef0123456789abcdef";
+const PREFAB_GUID: &str = "11111111111111111111111111111111";
+const SCRIPT_GUID: &str = "22222222222222222222222222222222";
+
+async fn create_package() -> UnityPackage {
+    let archive = UnityPackageBuilder::new()
+        .add_folder(FOLDER_GUID, "Assets/Tool")
+        .add_asset(PREFAB_GUID, "Assets/Tool/Tool.prefab", b"prefab")
+        .add_asset(SCRIPT_GUID, "Assets/Tool/Tool.cs", b"class Tool {}")
+        .build()
+        .await;
+    UnityPackage::read(archive.as_slice()).await.unwrap()
+}
+
+fn status_of<'a>(
+    import: &'a vrc_get_vpm::unity_project::PendingImport,
+    path: &str,
+) -> &'a ImportAssetStatus {
+    import
+        .assets()
+        .iter()
+        .find(|x| x.asset().path() == path)
+        .expect("asset not found")
+        .status()
+}
+
+#[test]
+fn read_unitypackage() {
+    block_on(async {
+        let package = create_package().await;
+
+        let assets = package.assets();
+        assert_eq!(assets.len(), 3);
+        assert_eq!(assets[0].path(), "Assets/Tool");
+        assert!(assets[0].is_folder());
+        assert_eq!(assets[1].path(), "Assets/Tool/Tool.cs");
+        assert_eq!(assets[1].guid(), SCRIPT_GUID);
+        assert_eq!(assets[1].asset(), Some(b"class Tool {}".as_slice()));
+    })
+}
+
+#[test]
+fn import_to_empty_project() {
+    block_on(async {
+        let fs = VirtualFileSystem::new();
+        let project = VirtualProjectBuilder::new()
+            .add_dir("Assets")
+            .build_on(fs.clone())
+            .await
+            .unwrap();
+        let package = create_package().await;
+
+        let import = project
+            .import_unitypackage_request(&package, &ImportFilter::new())
+            .await
+            .unwrap();
+        assert!(!import.has_conflicts());
+        assert!(matches!(
+            status_of(&import, "Assets/Tool/Tool.cs"),
+            ImportAssetStatus::New
+        ));
+
+        project.import_unitypackage(&import).await.unwrap();
+
+        assert_eq!(
+            fs.read_file("Assets/Tool/Tool.prefab".as_ref())
+                .await
+                .unwrap(),
+            b"prefab"
+        );
+        assert!(fs.is_file("Assets/Tool.meta".as_ref()).await);
+        let meta = fs.read_file("Assets/Tool/Tool.cs.meta".as_ref()).await;
+        assert!(String::from_utf8(meta.unwrap())
+            .unwrap()
+            .contains(SCRIPT_GUID));
+    })
+}
+
+#[test]
+fn detect_conflicts() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_file("Assets/Tool.meta", format!("guid: {FOLDER_GUID}\n"))
+            // the same asset moved by the user
+            .add_file("Assets/Moved.prefab", "prefab")
+            .add_file("Assets/Moved.prefab.meta", format!("guid: {PREFAB_GUID}\n"))
+            // another asset at the path
+            .add_file("Assets/Tool/Tool.cs", "class Other {}")
+            .add_file(
+                "Assets/Tool/Tool.cs.meta",
+                "guid: 33333333333333333333333333333333\n",
+            )
+            .build()
+            .await
+            .unwrap();
+        let package = create_package().await;
+
+        let import = project
+            .import_unitypackage_request(&package, &ImportFilter::new())
+            .await
+            .unwrap();
+
+        assert!(import.has_conflicts());
+        assert!(matches!(
+            status_of(&import, "Assets/Tool"),
+            ImportAssetStatus::Update
+        ));
+        assert!(matches!(
+            status_of(&import, "Assets/Tool/Tool.prefab"),
+            ImportAssetStatus::GuidConflict(path) if path.as_ref() == std::path::Path::new("Assets/Moved.prefab")
+        ));
+        assert!(matches!(
+            status_of(&import, "Assets/Tool/Tool.cs"),
+            ImportAssetStatus::PathConflict
+        ));
+    })
+}
+
+#[test]
+fn filter_assets() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new().build().await.unwrap();
+        let package = create_package().await;
+
+        let filter = ImportFilter::new()
+            .include("Assets/Tool")
+            .exclude("Assets/Tool/Tool.cs");
+        let import = project
+            .import_unitypackage_request(&package, &filter)
+            .await
+            .unwrap();
+        let paths = (import.assets().iter())
+            .map(|x| x.asset().path())
+            .collect::<Vec<_>>();
+        assert_eq!(paths, ["Assets/Tool", "Assets/Tool/Tool.prefab"]);
+
+        // parent folders are included
+        let filter = ImportFilter::new().include("Assets/Tool/Tool.cs");
+        let import = project
+            .import_unitypackage_request(&package, &filter)
+            .await
+            .unwrap();
+        let paths = (import.assets().iter())
+            .map(|x| x.asset().path())
+            .collect::<Vec<_>>();
+        assert_eq!(paths, ["Assets/Tool", "Assets/Tool/Tool.cs"]);
+    })
+}
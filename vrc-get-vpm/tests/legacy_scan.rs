@@ -0,0 +1,66 @@
+use crate::common::*;
+use futures::executor::block_on;
+use std::path::Path;
+use vrc_get_vpm::memory_io::UnityPackageBuilder;
+use vrc_get_vpm::unity_project::{scan_legacy_assets, top_level_legacy_assets, UnityPackage};
+
+mod common;
+
+const FOLDER_GUID: &str = "0123456789abcdef0123456789abcdef";
+const SCRIPT_GUID: &str = "22222222222222222222222222222222";
+const PLUGIN_GUID: &str = "33333333333333333333333333333333";
+const PLUGINS_FOLDER_GUID: &str = "44444444444444444444444444444444";
+
+#[test]
+fn scan_folder() {
+    block_on(async {
+        let fs = VirtualFileSystem::new();
+        VirtualProjectBuilder::new()
+            .add_file("Assets/Tool.meta", format!("guid: {FOLDER_GUID}\n"))
+            .add_file("Assets/Tool/Tool.cs", "class Tool {}")
+            .add_file("Assets/Tool/Tool.cs.meta", format!("guid: {SCRIPT_GUID}\n"))
+            .add_file("Assets/Tool/NoMeta.cs", "class NoMeta {}")
+            .build_on(fs.clone())
+            .await
+            .unwrap();
+
+        let assets = scan_legacy_assets(&fs, Path::new("Assets/Tool")).await;
+
+        assert_eq!(assets.len(), 2);
+        assert_eq!(assets[0].path(), "Assets/Tool");
+        assert_eq!(assets[0].guid(), FOLDER_GUID);
+        assert!(assets[0].is_folder());
+        assert_eq!(assets[1].path(), "Assets/Tool/Tool.cs");
+        assert!(!assets[1].is_folder());
+
+        let top_level = top_level_legacy_assets(&assets);
+        assert_eq!(top_level.len(), 1);
+        assert_eq!(top_level[0].path(), "Assets/Tool");
+    })
+}
+
+#[test]
+fn scan_unitypackage() {
+    block_on(async {
+        let archive = UnityPackageBuilder::new()
+            .add_folder(FOLDER_GUID, "Assets/Tool")
+            .add_asset(SCRIPT_GUID, "Assets/Tool/Tool.cs", b"class Tool {}")
+            .add_folder(PLUGINS_FOLDER_GUID, "Assets/Plugins")
+            .add_asset(PLUGIN_GUID, "Assets/Plugins/Tool.dll", b"dll")
+            .build()
+            .await;
+        let package = UnityPackage::read(archive.as_slice()).await.unwrap();
+
+        let assets = package.legacy_assets();
+        // the shared Assets/Plugins folder is not removed as a whole
+        let top_level = top_level_legacy_assets(&assets);
+        let paths = (top_level.iter())
+            .map(|x| (x.path(), x.is_folder()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            [("Assets/Plugins/Tool.dll", false), ("Assets/Tool", true)]
+        );
+        assert!(top_level.iter().all(|x| x.is_valid_path()));
+    })
+}
@@ -0,0 +1,303 @@
+use futures::executor::block_on;
+use serde_json::json;
+use std::path::Path;
+use url::Url;
+use vrc_get_vpm::io::IoTrait;
+use vrc_get_vpm::memory_io::{
+    create_package_zip, FakeHttpClient, RepositoryBuilder, VirtualFileSystem, VirtualProjectBuilder,
+};
+use vrc_get_vpm::unity_project::{AddPackageOperation, CreatePatchErr};
+use vrc_get_vpm::{Environment, PackageCollection, UnityProject, VersionSelector};
+
+async fn install_base(
+    project_fs: &VirtualFileSystem,
+) -> Environment<FakeHttpClient, VirtualFileSystem> {
+    let http = FakeHttpClient::new();
+
+    let base_json = json!({
+        "name": "com.vrchat.base",
+        "version": "1.0.0",
+    });
+    let zip = create_package_zip(
+        &base_json,
+        &[
+            ("Runtime/Base.cs", b"class Base {\n}\n"),
+            ("Runtime/Other.cs", b"class Other {}\n"),
+        ],
+    )
+    .await;
+    let official = RepositoryBuilder::new(
+        Url::parse("https://packages.vrchat.com/official?download").unwrap(),
+    )
+    .with_id("com.vrchat.repos.official")
+    .add_package_with_zip(base_json, zip);
+    let curated =
+        RepositoryBuilder::new(Url::parse("https://packages.vrchat.com/curated?download").unwrap())
+            .with_id("com.vrchat.repos.curated");
+
+    http.add_repository(&official);
+    http.add_repository(&curated);
+
+    let mut env = Environment::load(Some(http), VirtualFileSystem::new())
+        .await
+        .unwrap();
+    env.load_package_infos(true).await.unwrap();
+
+    let package = env
+        .find_package_by_name("com.vrchat.base", VersionSelector::latest_for(None, false))
+        .expect("package not found");
+
+    let mut project = VirtualProjectBuilder::new()
+        .build_on(project_fs.clone())
+        .await
+        .unwrap();
+
+    let changes = project
+        .add_package_request(
+            &env,
+            &[package],
+            AddPackageOperation::InstallToDependencies,
+            false,
+        )
+        .await
+        .unwrap();
+    project.apply_pending_changes(&env, changes).await.unwrap();
+    project.save().await.unwrap();
+
+    env
+}
+
+#[test]
+fn clean_after_install() {
+    block_on(async {
+        let project_fs = VirtualFileSystem::new();
+        install_base(&project_fs).await;
+
+        let project = UnityProject::load(project_fs).await.unwrap();
+        let modifications = project
+            .package_modifications("com.vrchat.base")
+            .await
+            .unwrap()
+            .expect("hashes not recorded");
+
+        assert_eq!(modifications.version().to_string(), "1.0.0");
+        assert!(!modifications.is_modified());
+    })
+}
+
+#[test]
+fn detect_and_diff_modifications() {
+    block_on(async {
+        let project_fs = VirtualFileSystem::new();
+        let env = install_base(&project_fs).await;
+
+        let folder = "Packages/com.vrchat.base";
+        project_fs
+            .write(
+                format!("{folder}/Runtime/Base.cs").as_ref(),
+                b"class Base {\n    // fixed\n}\n",
+            )
+            .await
+            .unwrap();
+        project_fs
+            .write(
+                format!("{folder}/Runtime/Added.cs").as_ref(),
+                b"class Added {}\n",
+            )
+            .await
+            .unwrap();
+        project_fs
+            .remove_file(format!("{folder}/Runtime/Other.cs").as_ref())
+            .await
+            .unwrap();
+
+        let project = UnityProject::load(project_fs).await.unwrap();
+        let modifications = project
+            .package_modifications("com.vrchat.base")
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert!(modifications.is_modified());
+        assert_eq!(modifications.modified(), [Box::from("Runtime/Base.cs")]);
+        assert_eq!(modifications.added(), [Box::from("Runtime/Added.cs")]);
+        assert_eq!(modifications.deleted(), [Box::from("Runtime/Other.cs")]);
+
+        let changes = project.remove_request(&["com.vrchat.base"]).await.unwrap();
+        let overwritten = project.overwritten_modifications(&changes).await.unwrap();
+        assert_eq!(overwritten.len(), 1);
+        assert_eq!(overwritten[0].name(), "com.vrchat.base");
+
+        let diff = project
+            .package_diff(&env, modifications.name(), modifications.version())
+            .await
+            .unwrap();
+        assert!(diff.contains("diff --git a/Runtime/Base.cs b/Runtime/Base.cs\n"));
+        assert!(diff.contains("+    // fixed\n"));
+        assert!(diff.contains("--- /dev/null\n+++ b/Runtime/Added.cs\n"));
+        assert!(diff.contains("--- a/Runtime/Other.cs\n+++ /dev/null\n"));
+    })
+}
+
+async fn reinstall_base(
+    project_fs: &VirtualFileSystem,
+    env: &Environment<FakeHttpClient, VirtualFileSystem>,
+) {
+    project_fs
+        .remove_dir_all("Packages/com.vrchat.base".as_ref())
+        .await
+        .unwrap();
+    let mut project = UnityProject::load(project_fs.clone()).await.unwrap();
+    let changes = project.doctor_fix_request(env).await;
+    project.apply_pending_changes(env, changes).await.unwrap();
+}
+
+#[test]
+fn patch_reapplied_on_install() {
+    block_on(async {
+        let project_fs = VirtualFileSystem::new();
+        let env = install_base(&project_fs).await;
+
+        let base = "Packages/com.vrchat.base/Runtime/Base.cs";
+        let patched = b"class Base {\n    // fixed\n}\n";
+        project_fs.write(base.as_ref(), patched).await.unwrap();
+
+        let project = UnityProject::load(project_fs.clone()).await.unwrap();
+        let path = project
+            .create_package_patch(&env, "com.vrchat.base")
+            .await
+            .unwrap()
+            .expect("patch not created");
+        assert_eq!(
+            path.as_path(),
+            Path::new("Packages/vrc-get-patches/com.vrchat.base@1.0.0.patch")
+        );
+
+        // the saved modifications are not reported as local modifications
+        let modifications = project
+            .package_modifications("com.vrchat.base")
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!modifications.is_modified());
+
+        reinstall_base(&project_fs, &env).await;
+
+        assert_eq!(project_fs.read_file(base.as_ref()).await.unwrap(), patched);
+
+        // the patches folder is not a package
+        let project = UnityProject::load(project_fs.clone()).await.unwrap();
+        assert!(project.unlocked_packages().is_empty());
+    })
+}
+
+#[test]
+fn patch_not_applied_on_conflict() {
+    block_on(async {
+        let project_fs = VirtualFileSystem::new();
+        let env = install_base(&project_fs).await;
+
+        let patch = "diff --git a/Runtime/Base.cs b/Runtime/Base.cs\n\
+            --- a/Runtime/Base.cs\n\
+            +++ b/Runtime/Base.cs\n\
+            @@ -1,2 +1,2 @@\n\
+            -class Renamed {\n\
+            +class Patched {\n \
+            }\n";
+        project_fs
+            .create_dir_all("Packages/vrc-get-patches".as_ref())
+            .await
+            .unwrap();
+        project_fs
+            .write(
+                "Packages/vrc-get-patches/com.vrchat.base@1.0.0.patch".as_ref(),
+                patch.as_bytes(),
+            )
+            .await
+            .unwrap();
+
+        reinstall_base(&project_fs, &env).await;
+
+        assert_eq!(
+            project_fs
+                .read_file("Packages/com.vrchat.base/Runtime/Base.cs".as_ref())
+                .await
+                .unwrap(),
+            b"class Base {\n}\n"
+        );
+    })
+}
+
+#[test]
+fn patch_with_binary_file() {
+    block_on(async {
+        let project_fs = VirtualFileSystem::new();
+        let env = install_base(&project_fs).await;
+
+        let texture = "Packages/com.vrchat.base/Runtime/Texture.png";
+        project_fs
+            .write(texture.as_ref(), b"\x89PNG\r\n\x1a\n\xff")
+            .await
+            .unwrap();
+
+        let project = UnityProject::load(project_fs.clone()).await.unwrap();
+        let err = project
+            .create_package_patch(&env, "com.vrchat.base")
+            .await
+            .unwrap_err();
+        let CreatePatchErr::BinaryFile(path) = err else {
+            panic!("unexpected error: {err}");
+        };
+        assert_eq!(path.as_ref(), "Runtime/Texture.png");
+
+        // the modifications are not recorded since they are not saved
+        assert!(
+            !project_fs
+                .is_file("Packages/vrc-get-patches/com.vrchat.base@1.0.0.patch".as_ref())
+                .await
+        );
+        let modifications = project
+            .package_modifications("com.vrchat.base")
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(modifications.is_modified());
+    })
+}
+
+#[test]
+fn patch_for_newer_version_not_applied() {
+    block_on(async {
+        let project_fs = VirtualFileSystem::new();
+        let env = install_base(&project_fs).await;
+
+        let patch = "diff --git a/Runtime/Base.cs b/Runtime/Base.cs\n\
+            --- a/Runtime/Base.cs\n\
+            +++ b/Runtime/Base.cs\n\
+            @@ -1,2 +1,2 @@\n\
+            -class Base {\n\
+            +class Patched {\n \
+            }\n";
+        project_fs
+            .create_dir_all("Packages/vrc-get-patches".as_ref())
+            .await
+            .unwrap();
+        project_fs
+            .write(
+                "Packages/vrc-get-patches/com.vrchat.base@1.1.0.patch".as_ref(),
+                patch.as_bytes(),
+            )
+            .await
+            .unwrap();
+
+        reinstall_base(&project_fs, &env).await;
+
+        assert_eq!(
+            project_fs
+                .read_file("Packages/com.vrchat.base/Runtime/Base.cs".as_ref())
+                .await
+                .unwrap(),
+            b"class Base {\n}\n"
+        );
+    })
+}
@@ -0,0 +1,81 @@
+//! This file contains tests for running Environment on the memory_io module.
+
+use futures::executor::block_on;
+use serde_json::json;
+use url::Url;
+use vrc_get_vpm::io::IoTrait;
+use vrc_get_vpm::memory_io::{
+    create_package_zip, FakeHttpClient, RepositoryBuilder, VirtualFileSystem, VirtualProjectBuilder,
+};
+use vrc_get_vpm::unity_project::AddPackageOperation;
+use vrc_get_vpm::{Environment, PackageCollection, UnityProject, VersionSelector};
+
+#[test]
+fn install_from_fake_repository() {
+    block_on(async {
+        let http = FakeHttpClient::new();
+
+        let base_json = json!({
+            "name": "com.vrchat.base",
+            "version": "1.0.0",
+        });
+        let zip = create_package_zip(&base_json, &[("Runtime/Base.cs", b"class Base {}")]).await;
+        let official = RepositoryBuilder::new(
+            Url::parse("https://packages.vrchat.com/official?download").unwrap(),
+        )
+        .with_id("com.vrchat.repos.official")
+        .add_package_with_zip(base_json, zip);
+        let curated = RepositoryBuilder::new(
+            Url::parse("https://packages.vrchat.com/curated?download").unwrap(),
+        )
+        .with_id("com.vrchat.repos.curated");
+
+        http.add_repository(&official);
+        http.add_repository(&curated);
+
+        let env_fs = VirtualFileSystem::new();
+        let mut env = Environment::load(Some(http), env_fs.clone()).await.unwrap();
+        env.load_package_infos(true).await.unwrap();
+
+        let package = env
+            .find_package_by_name("com.vrchat.base", VersionSelector::latest_for(None, false))
+            .expect("package not found");
+
+        let project_fs = VirtualFileSystem::new();
+        let mut project = VirtualProjectBuilder::new()
+            .build_on(project_fs.clone())
+            .await
+            .unwrap();
+
+        let changes = project
+            .add_package_request(
+                &env,
+                &[package],
+                AddPackageOperation::InstallToDependencies,
+                false,
+            )
+            .await
+            .unwrap();
+        project.apply_pending_changes(&env, changes).await.unwrap();
+        project.save().await.unwrap();
+
+        // the package is downloaded to the cache of the environment
+        assert!(
+            env_fs
+                .is_file("Repos/com.vrchat.base/vrc-get-com.vrchat.base-1.0.0.zip".as_ref())
+                .await
+        );
+
+        assert_eq!(
+            project_fs
+                .read_file("Packages/com.vrchat.base/Runtime/Base.cs".as_ref())
+                .await
+                .unwrap(),
+            b"class Base {}"
+        );
+
+        let project = UnityProject::load(project_fs).await.unwrap();
+        let locked = project.get_locked("com.vrchat.base").unwrap();
+        assert_eq!(locked.version().to_string(), "1.0.0");
+    })
+}
@@ -0,0 +1,220 @@
+use common::*;
+use futures::executor::block_on;
+use serde_json::json;
+use vrc_get_vpm::unity_project::pending_project_changes::RemoveReason;
+use vrc_get_vpm::unity_project::AddPackageErr;
+use vrc_get_vpm::version::Version;
+use vrc_get_vpm::PackageManifest;
+
+mod common;
+
+#[test]
+fn list_deprecated() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_dependency("com.anatawa12.old-tool", Version::new(1, 0, 0))
+            .add_locked("com.anatawa12.old-tool", Version::new(1, 0, 0), &[])
+            .add_dependency("com.anatawa12.gists", Version::new(1, 0, 0))
+            .add_locked("com.anatawa12.gists", Version::new(1, 0, 0), &[])
+            .build()
+            .await
+            .unwrap();
+
+        let collection = PackageCollectionBuilder::new()
+            .add(PackageManifest::new(
+                "com.anatawa12.old-tool",
+                Version::new(1, 0, 0),
+            ))
+            .add(manifest(json!({
+                "name": "com.anatawa12.old-tool",
+                "version": "1.0.1",
+                "vrc-get": { "deprecated": "no longer maintained" },
+            })))
+            .add(PackageManifest::new(
+                "com.anatawa12.gists",
+                Version::new(1, 0, 0),
+            ))
+            .build();
+
+        let deprecated = project.deprecated_packages(&collection);
+
+        assert_eq!(deprecated.len(), 1);
+        assert_eq!(deprecated[0].name(), "com.anatawa12.old-tool");
+        assert_eq!(deprecated[0].version(), &Version::new(1, 0, 0));
+        assert_eq!(
+            deprecated[0].deprecation().message(),
+            Some("no longer maintained")
+        );
+        assert_eq!(deprecated[0].deprecation().replaced_by(), None);
+    })
+}
+
+#[test]
+fn replace_dependency() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_dependency("com.anatawa12.old-tool", Version::new(1, 0, 0))
+            .add_locked("com.anatawa12.old-tool", Version::new(1, 0, 0), &[])
+            .build()
+            .await
+            .unwrap();
+
+        let collection = PackageCollectionBuilder::new()
+            .add(manifest(json!({
+                "name": "com.anatawa12.old-tool",
+                "version": "1.0.0",
+                "vrc-get": {
+                    "deprecated": {
+                        "message": "renamed to com.anatawa12.new-tool",
+                        "replacedBy": { "name": "com.anatawa12.new-tool", "range": "^2.0.0" },
+                    },
+                },
+            })))
+            .add(PackageManifest::new(
+                "com.anatawa12.new-tool",
+                Version::new(2, 0, 0),
+            ))
+            .add(PackageManifest::new(
+                "com.anatawa12.new-tool",
+                Version::new(3, 0, 0),
+            ))
+            .build();
+
+        let result = project
+            .migrate_replaced_request(&collection, false)
+            .await
+            .unwrap();
+
+        let new_tool = collection.get_package("com.anatawa12.new-tool", Version::new(2, 0, 0));
+
+        assert_eq!(result.package_changes().len(), 2);
+        assert_eq!(result.conflicts().len(), 0);
+
+        assert_removed(&result, "com.anatawa12.old-tool", RemoveReason::Legacy);
+        assert_installing_to_both(&result, &new_tool);
+    })
+}
+
+#[test]
+fn replace_locked_dependency() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_dependency("com.anatawa12.avatar-tool", Version::new(1, 0, 0))
+            .add_locked(
+                "com.anatawa12.avatar-tool",
+                Version::new(1, 0, 0),
+                &[("com.anatawa12.old-library", "1.0.0")],
+            )
+            .add_locked("com.anatawa12.old-library", Version::new(1, 0, 0), &[])
+            .build()
+            .await
+            .unwrap();
+
+        let collection = PackageCollectionBuilder::new()
+            .add(
+                PackageManifest::new("com.anatawa12.avatar-tool", Version::new(1, 0, 0))
+                    .add_vpm_dependency("com.anatawa12.old-library", "1.0.0"),
+            )
+            .add(manifest(json!({
+                "name": "com.anatawa12.old-library",
+                "version": "1.0.0",
+                "vrc-get": {
+                    "deprecated": { "replacedBy": "com.anatawa12.new-library" },
+                },
+            })))
+            .add(
+                PackageManifest::new("com.anatawa12.new-library", Version::new(1, 0, 0))
+                    .add_legacy_package("com.anatawa12.old-library"),
+            )
+            .build();
+
+        let result = project
+            .migrate_replaced_request(&collection, false)
+            .await
+            .unwrap();
+
+        let new_library =
+            collection.get_package("com.anatawa12.new-library", Version::new(1, 0, 0));
+
+        // the replacement takes over the old package with legacyPackages so no conflicts
+        assert_eq!(result.package_changes().len(), 2);
+        assert_eq!(result.conflicts().len(), 0);
+
+        assert_removed(&result, "com.anatawa12.old-library", RemoveReason::Legacy);
+        assert_installing_to_locked_only(&result, &new_library);
+    })
+}
+
+#[test]
+fn conflicts_with_dependents() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_dependency("com.anatawa12.avatar-tool", Version::new(1, 0, 0))
+            .add_locked(
+                "com.anatawa12.avatar-tool",
+                Version::new(1, 0, 0),
+                &[("com.anatawa12.old-library", "1.0.0")],
+            )
+            .add_locked("com.anatawa12.old-library", Version::new(1, 0, 0), &[])
+            .build()
+            .await
+            .unwrap();
+
+        let collection = PackageCollectionBuilder::new()
+            .add(manifest(json!({
+                "name": "com.anatawa12.old-library",
+                "version": "1.0.0",
+                "vrc-get": {
+                    "deprecated": { "replacedBy": "com.anatawa12.new-library" },
+                },
+            })))
+            .add(PackageManifest::new(
+                "com.anatawa12.new-library",
+                Version::new(1, 0, 0),
+            ))
+            .build();
+
+        let result = project
+            .migrate_replaced_request(&collection, false)
+            .await
+            .unwrap();
+
+        assert_removed(&result, "com.anatawa12.old-library", RemoveReason::Legacy);
+        assert_eq!(
+            result.conflicts()["com.anatawa12.old-library"].conflicting_packages(),
+            &["com.anatawa12.avatar-tool".into()]
+        );
+    })
+}
+
+#[test]
+fn replacement_not_found() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_dependency("com.anatawa12.old-tool", Version::new(1, 0, 0))
+            .add_locked("com.anatawa12.old-tool", Version::new(1, 0, 0), &[])
+            .build()
+            .await
+            .unwrap();
+
+        let collection = PackageCollectionBuilder::new()
+            .add(manifest(json!({
+                "name": "com.anatawa12.old-tool",
+                "version": "1.0.0",
+                "vrc-get": {
+                    "deprecated": { "replacedBy": "com.anatawa12.new-tool" },
+                },
+            })))
+            .build();
+
+        let err = project
+            .migrate_replaced_request(&collection, false)
+            .await
+            .unwrap_err();
+
+        let AddPackageErr::DependencyNotFound { dependency_name } = err else {
+            panic!("unexpected error: {err}");
+        };
+        assert_eq!(dependency_name.as_ref(), "com.anatawa12.new-tool");
+    })
+}
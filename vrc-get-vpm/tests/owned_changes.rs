@@ -0,0 +1,96 @@
+use common::*;
+use futures::executor::block_on;
+use vrc_get_vpm::unity_project::{AddPackageOperation, OwnedPendingProjectChanges};
+use vrc_get_vpm::version::Version;
+use vrc_get_vpm::{PackageManifest, SharedPackageCollection};
+
+mod common;
+
+fn assert_send_static<T: Send + Sync + 'static>(_: &T) {}
+
+#[test]
+fn owned_changes() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new().build().await.unwrap();
+
+        let collection = PackageCollectionBuilder::new()
+            .add(
+                PackageManifest::new("com.vrchat.avatars", Version::new(1, 0, 0))
+                    .add_vpm_dependency("com.vrchat.base", "1.0.0"),
+            )
+            .add(PackageManifest::new(
+                "com.vrchat.base",
+                Version::new(1, 0, 0),
+            ))
+            .build();
+        let collection = SharedPackageCollection::new(&collection);
+        assert_send_static(&collection);
+
+        let avatars_package = (collection.packages().iter())
+            .find(|x| x.name() == "com.vrchat.avatars")
+            .unwrap();
+        let base_package = (collection.packages().iter())
+            .find(|x| x.name() == "com.vrchat.base")
+            .unwrap();
+
+        let changes = project
+            .add_package_request(
+                &collection,
+                &[avatars_package.as_package_info()],
+                AddPackageOperation::InstallToDependencies,
+                false,
+            )
+            .await
+            .unwrap();
+
+        let owned = OwnedPendingProjectChanges::new(&collection, &changes).unwrap();
+        drop(changes);
+        assert_send_static(&owned);
+
+        // owned changes can be serialized
+        let json = serde_json::to_value(&owned).unwrap();
+        assert_eq!(
+            json["packageChanges"]["com.vrchat.avatars"]["package"]["version"],
+            "1.0.0"
+        );
+
+        let changes = owned.as_pending_changes();
+
+        assert_eq!(changes.package_changes().len(), 2);
+        assert_eq!(changes.remove_legacy_folders().len(), 0);
+        assert_eq!(changes.remove_legacy_files().len(), 0);
+        assert_eq!(changes.conflicts().len(), 0);
+
+        assert_installing_to_both(&changes, &avatars_package.as_package_info());
+        assert_installing_to_locked_only(&changes, &base_package.as_package_info());
+    })
+}
+
+#[test]
+fn owned_changes_from_other_collection() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new().build().await.unwrap();
+
+        let collection = PackageCollectionBuilder::new()
+            .add(PackageManifest::new(
+                "com.vrchat.base",
+                Version::new(1, 0, 0),
+            ))
+            .build();
+        let shared = SharedPackageCollection::new(&collection);
+
+        let base_package = collection.get_package("com.vrchat.base", Version::new(1, 0, 0));
+
+        let changes = project
+            .add_package_request(
+                &collection,
+                &[base_package],
+                AddPackageOperation::InstallToDependencies,
+                false,
+            )
+            .await
+            .unwrap();
+
+        assert!(OwnedPendingProjectChanges::new(&shared, &changes).is_none());
+    })
+}
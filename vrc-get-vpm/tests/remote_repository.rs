@@ -0,0 +1,110 @@
+use serde_json::json;
+use vrc_get_vpm::repository::RemoteRepository;
+use vrc_get_vpm::version::Version;
+
+fn parse(json: serde_json::Value) -> RemoteRepository {
+    RemoteRepository::parse(json.as_object().unwrap().clone()).unwrap()
+}
+
+#[test]
+fn malformed_metadata() {
+    let repository = parse(json!({
+        "packages": {
+            "com.anatawa12.tool": {
+                "versions": {
+                    "1.0.0": {
+                        "name": "com.anatawa12.tool",
+                        "version": "1.0.0",
+                        "documentationUrl": "",
+                        "licensesUrl": "see LICENSE",
+                        "author": {"email": "a@b"},
+                        "license": 5,
+                        "keywords": "a b",
+                        "samples": [{"displayName": 5}],
+                        "hideInEditor": "false",
+                        "unityRelease": 3,
+                        "type": ["tool"],
+                        "vrc-get": { "deprecated": 5, "aliases": ["tool"] },
+                    },
+                }
+            },
+            "com.anatawa12.gists": {
+                "versions": {
+                    "1.0.0": {
+                        "name": "com.anatawa12.gists",
+                        "version": "1.0.0",
+                        "documentationUrl": "https://example.com/docs",
+                        "license": "MIT",
+                    },
+                }
+            },
+        },
+    }));
+
+    // the broken metadata is ignored
+    let tool =
+        (repository.get_package_version("com.anatawa12.tool", &Version::new(1, 0, 0))).unwrap();
+    assert_eq!(tool.documentation_url(), None);
+    assert_eq!(tool.licenses_url(), None);
+    assert_eq!(tool.author(), None);
+    assert_eq!(tool.license(), None);
+    assert!(tool.keywords().is_empty());
+    assert!(tool.samples().is_empty());
+    assert_eq!(tool.hide_in_editor(), None);
+    assert_eq!(tool.unity_release(), None);
+    assert_eq!(tool.package_type(), None);
+    assert_eq!(tool.deprecation(), None);
+    assert_eq!(tool.aliases(), &["tool".into()]);
+
+    // the other packages are not affected
+    let gists =
+        (repository.get_package_version("com.anatawa12.gists", &Version::new(1, 0, 0))).unwrap();
+    assert_eq!(
+        gists.documentation_url().map(|x| x.as_str()),
+        Some("https://example.com/docs")
+    );
+    assert_eq!(gists.license(), Some("MIT"));
+}
+
+#[test]
+fn malformed_unity_compatibility() {
+    let repository = parse(json!({
+        "packages": {
+            "com.anatawa12.tool": {
+                "versions": {
+                    "1.0.0": {
+                        "name": "com.anatawa12.tool",
+                        "version": "1.0.0",
+                        "vrc-get": { "maxUnity": "abc" },
+                    },
+                    "2.0.0": {"name": "com.anatawa12.tool", "version": "2.0.0"},
+                }
+            },
+        },
+        "vrc-get": {
+            "unityCompatibility": [
+                {"package": "com.anatawa12.tool", "maxUnity": "2019.4"},
+                {"package": "com.anatawa12.tool", "versions": ">=2.0.0", "maxUnity": "2022.3"},
+            ]
+        }
+    }));
+
+    let v1 =
+        (repository.get_package_version("com.anatawa12.tool", &Version::new(1, 0, 0))).unwrap();
+    let v2 =
+        (repository.get_package_version("com.anatawa12.tool", &Version::new(2, 0, 0))).unwrap();
+
+    // the invalid maxUnity and the rule without versions are ignored
+    assert_eq!(v1.max_unity(), None);
+    assert_eq!(repository.unity_compatibility_rules().len(), 1);
+    assert_eq!(
+        v2.max_unity().map(|x| x.to_string()).as_deref(),
+        Some("2022.3")
+    );
+
+    let repository = parse(json!({
+        "packages": {},
+        "vrc-get": 5,
+    }));
+    assert!(repository.unity_compatibility_rules().is_empty());
+}
@@ -0,0 +1,101 @@
+use common::*;
+use futures::executor::block_on;
+use vrc_get_vpm::io::IoTrait;
+use vrc_get_vpm::memory_io::VirtualFileSystem;
+use vrc_get_vpm::unity_project::AddPackageOperation;
+use vrc_get_vpm::version::{Version, VersionRange};
+use vrc_get_vpm::{PackageManifest, UnityProject};
+
+mod common;
+
+#[test]
+fn resolution_overrides_dependents_range() {
+    block_on(async {
+        let mut project = VirtualProjectBuilder::new()
+            .add_dependency("com.vrchat.avatars", Version::new(1, 0, 0))
+            .add_locked(
+                "com.vrchat.avatars",
+                Version::new(1, 0, 0),
+                &[("com.vrchat.base", "1.0.0")],
+            )
+            .add_locked("com.vrchat.base", Version::new(1, 0, 0), &[])
+            .build()
+            .await
+            .unwrap();
+
+        let collection = PackageCollectionBuilder::new()
+            .add(
+                PackageManifest::new("com.vrchat.avatars", Version::new(1, 0, 0))
+                    .add_vpm_dependency("com.vrchat.base", "1.0.0"),
+            )
+            .add(PackageManifest::new(
+                "com.vrchat.base",
+                Version::new(1, 0, 0),
+            ))
+            .add(PackageManifest::new(
+                "com.vrchat.base",
+                Version::new(1, 1, 0),
+            ))
+            .add(PackageManifest::new(
+                "com.vrchat.base",
+                Version::new(1, 2, 0),
+            ))
+            .add(
+                PackageManifest::new("com.anatawa12.tool", Version::new(1, 0, 0))
+                    .add_vpm_dependency("com.vrchat.base", "^1.1.0"),
+            )
+            .build();
+
+        project.set_resolution("com.vrchat.base", "1.1.0".parse().unwrap());
+
+        let tool = collection.get_package("com.anatawa12.tool", Version::new(1, 0, 0));
+        let base_1_1_0 = collection.get_package("com.vrchat.base", Version::new(1, 1, 0));
+
+        let resolve = project
+            .add_package_request(
+                &collection,
+                &[tool],
+                AddPackageOperation::InstallToDependencies,
+                false,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(resolve.package_changes().len(), 2);
+        assert_eq!(resolve.conflicts().len(), 0);
+
+        assert_installing_to_both(&resolve, &tool);
+        assert_installing_to_locked_only(&resolve, &base_1_1_0);
+    })
+}
+
+#[test]
+fn resolution_saved_to_project_settings() {
+    block_on(async {
+        let fs = VirtualFileSystem::new();
+        let mut project = VirtualProjectBuilder::new()
+            .build_on(fs.clone())
+            .await
+            .unwrap();
+
+        let range = "^1.3".parse::<VersionRange>().unwrap();
+        project.set_resolution("com.vrchat.base", range.clone());
+        project.save().await.unwrap();
+
+        assert!(fs.is_file("Packages/vrc-get-project.json".as_ref()).await);
+        assert_eq!(project.get_resolution("com.vrchat.base"), Some(&range));
+
+        let mut project = UnityProject::load(fs.clone()).await.unwrap();
+        assert_eq!(
+            project.resolutions().collect::<Vec<_>>(),
+            [("com.vrchat.base", &range)]
+        );
+
+        assert!(project.remove_resolution("com.vrchat.base"));
+        assert!(!project.remove_resolution("com.vrchat.base"));
+        project.save().await.unwrap();
+
+        let project = UnityProject::load(fs).await.unwrap();
+        assert_eq!(project.resolutions().count(), 0);
+    })
+}
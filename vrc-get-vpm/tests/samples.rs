@@ -0,0 +1,159 @@
+use crate::common::*;
+use futures::executor::block_on;
+use vrc_get_vpm::io::IoTrait;
+use vrc_get_vpm::unity_project::ImportSampleErr;
+use vrc_get_vpm::version::Version;
+
+mod common;
+
+const PACKAGE_JSON: &str = r#"{
+    "name": "com.anatawa12.tool",
+    "displayName": "Anatawa12's Tool",
+    "version": "1.0.0",
+    "samples": [
+        {"displayName": "Basic Usage", "description": "The basic usage", "path": "Samples~/Basic"},
+        {"displayName": "Missing", "path": "Samples~/Missing"}
+    ]
+}"#;
+
+#[test]
+fn import_sample() {
+    block_on(async {
+        let fs = VirtualFileSystem::new();
+        let project = VirtualProjectBuilder::new()
+            .add_locked("com.anatawa12.tool", Version::new(1, 0, 0), &[])
+            .add_package_json("com.anatawa12.tool", PACKAGE_JSON)
+            .add_file(
+                "Packages/com.anatawa12.tool/Samples~/Basic/Scene.unity",
+                "scene",
+            )
+            .add_file(
+                "Packages/com.anatawa12.tool/Samples~/Basic/Scene.unity.meta",
+                "guid: 0123456789abcdef0123456789abcdef\n",
+            )
+            .add_file(
+                "Packages/com.anatawa12.tool/Samples~/Basic/Scripts/Sample.cs",
+                "class Sample {}",
+            )
+            .build_on(fs.clone())
+            .await
+            .unwrap();
+
+        let samples = project.list_samples("com.anatawa12.tool").await.unwrap();
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[0].sample().display_name(), "Basic Usage");
+        assert_eq!(
+            samples[0].import_path(),
+            "Assets/Samples/Anatawa12's Tool/1.0.0/Basic Usage".as_ref() as &std::path::Path
+        );
+        assert!(!samples[0].is_imported());
+
+        // the sample can be specified by the name of the folder
+        let imported = project
+            .import_sample("com.anatawa12.tool", "Basic", false)
+            .await
+            .unwrap();
+        assert_eq!(imported, samples[0].import_path());
+
+        let base = "Assets/Samples/Anatawa12's Tool/1.0.0/Basic Usage";
+        assert_eq!(
+            fs.read_file(format!("{base}/Scene.unity").as_ref())
+                .await
+                .unwrap(),
+            b"scene"
+        );
+        assert!(
+            fs.is_file(format!("{base}/Scene.unity.meta").as_ref())
+                .await
+        );
+        assert!(
+            fs.is_file(format!("{base}/Scripts/Sample.cs").as_ref())
+                .await
+        );
+
+        let samples = project.list_samples("com.anatawa12.tool").await.unwrap();
+        assert!(samples[0].is_imported());
+
+        assert!(matches!(
+            project
+                .import_sample("com.anatawa12.tool", "Basic Usage", false)
+                .await,
+            Err(ImportSampleErr::AlreadyImported(_))
+        ));
+        project
+            .import_sample("com.anatawa12.tool", "Basic Usage", true)
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            project
+                .import_sample("com.anatawa12.tool", "Missing", false)
+                .await,
+            Err(ImportSampleErr::SampleFolderNotFound(_))
+        ));
+        assert!(matches!(
+            project
+                .import_sample("com.anatawa12.tool", "Unknown", false)
+                .await,
+            Err(ImportSampleErr::SampleNotFound { .. })
+        ));
+        assert!(matches!(
+            project.list_samples("com.anatawa12.other").await,
+            Err(ImportSampleErr::NotInstalled(_))
+        ));
+    })
+}
+
+#[test]
+fn import_sample_with_dot_names() {
+    block_on(async {
+        let fs = VirtualFileSystem::new();
+        let project = VirtualProjectBuilder::new()
+            .add_locked("com.anatawa12.tool", Version::new(1, 0, 0), &[])
+            .add_package_json(
+                "com.anatawa12.tool",
+                r#"{
+                    "name": "com.anatawa12.tool",
+                    "displayName": "..",
+                    "version": "1.0.0",
+                    "samples": [
+                        {"displayName": ".", "path": "Samples~/Dot"},
+                        {"displayName": " ", "path": "Samples~/Empty"}
+                    ]
+                }"#,
+            )
+            .add_file("Packages/com.anatawa12.tool/Samples~/Dot/Dot.cs", "dot")
+            .add_file(
+                "Packages/com.anatawa12.tool/Samples~/Empty/Empty.cs",
+                "empty",
+            )
+            .add_file("Assets/Samples/1.0.0/Keep.cs", "keep")
+            .build_on(fs.clone())
+            .await
+            .unwrap();
+
+        let samples = project.list_samples("com.anatawa12.tool").await.unwrap();
+        assert_eq!(
+            samples[0].import_path(),
+            "Assets/Samples/_/1.0.0/_".as_ref() as &std::path::Path
+        );
+        assert_eq!(samples[1].import_path(), samples[0].import_path());
+
+        project
+            .import_sample("com.anatawa12.tool", "Dot", false)
+            .await
+            .unwrap();
+        // overwriting does not remove the folders outside the sample folder
+        project
+            .import_sample("com.anatawa12.tool", "Empty", true)
+            .await
+            .unwrap();
+
+        assert!(fs.is_file("Assets/Samples/1.0.0/Keep.cs".as_ref()).await);
+        assert!(
+            fs.is_file("Assets/Samples/_/1.0.0/_/Empty.cs".as_ref())
+                .await
+        );
+        assert!(!fs.is_file("Assets/Samples/_/1.0.0/_/Dot.cs".as_ref()).await);
+    })
+}
@@ -0,0 +1,207 @@
+use common::*;
+use futures::executor::block_on;
+use vrc_get_vpm::unity_project::{AddPackageOperation, RestoreChangesErr, SavedPendingChanges};
+use vrc_get_vpm::version::Version;
+use vrc_get_vpm::PackageManifest;
+
+mod common;
+
+fn collection() -> PackageCollection {
+    PackageCollectionBuilder::new()
+        .add(
+            PackageManifest::new("com.vrchat.avatars", Version::new(1, 0, 0))
+                .add_vpm_dependency("com.vrchat.base", "1.0.0"),
+        )
+        .add(PackageManifest::new(
+            "com.vrchat.base",
+            Version::new(1, 0, 0),
+        ))
+        .build()
+}
+
+fn round_trip(saved: &SavedPendingChanges) -> SavedPendingChanges {
+    serde_json::from_slice(&serde_json::to_vec(saved).unwrap()).unwrap()
+}
+
+#[test]
+fn restore_saved_changes() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new().build().await.unwrap();
+        let collection = collection();
+
+        let avatars_package = collection.get_package("com.vrchat.avatars", Version::new(1, 0, 0));
+        let base_package = collection.get_package("com.vrchat.base", Version::new(1, 0, 0));
+
+        let changes = project
+            .add_package_request(
+                &collection,
+                &[avatars_package],
+                AddPackageOperation::InstallToDependencies,
+                false,
+            )
+            .await
+            .unwrap();
+
+        let saved = round_trip(&project.save_pending_changes(&changes));
+
+        let restored = project
+            .restore_pending_changes(&collection, &saved)
+            .unwrap();
+
+        assert_eq!(restored.package_changes().len(), 2);
+        assert_eq!(restored.remove_legacy_folders().len(), 0);
+        assert_eq!(restored.remove_legacy_files().len(), 0);
+        assert_eq!(restored.conflicts().len(), 0);
+
+        assert_installing_to_both(&restored, &avatars_package);
+        assert_installing_to_locked_only(&restored, &base_package);
+    })
+}
+
+#[test]
+fn refuse_restore_changed_project() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new().build().await.unwrap();
+        let collection = collection();
+
+        let avatars_package = collection.get_package("com.vrchat.avatars", Version::new(1, 0, 0));
+
+        let changes = project
+            .add_package_request(
+                &collection,
+                &[avatars_package],
+                AddPackageOperation::InstallToDependencies,
+                false,
+            )
+            .await
+            .unwrap();
+
+        let saved = round_trip(&project.save_pending_changes(&changes));
+
+        let changed_project = VirtualProjectBuilder::new()
+            .add_locked("com.vrchat.base", Version::new(1, 0, 0), &[])
+            .build()
+            .await
+            .unwrap();
+
+        let err = changed_project
+            .restore_pending_changes(&collection, &saved)
+            .unwrap_err();
+        assert!(matches!(err, RestoreChangesErr::ProjectChanged));
+    })
+}
+
+#[test]
+fn refuse_restore_changed_package() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new().build().await.unwrap();
+        let collection = collection();
+
+        let avatars_package = collection.get_package("com.vrchat.avatars", Version::new(1, 0, 0));
+
+        let changes = project
+            .add_package_request(
+                &collection,
+                &[avatars_package],
+                AddPackageOperation::InstallToDependencies,
+                false,
+            )
+            .await
+            .unwrap();
+
+        let saved = round_trip(&project.save_pending_changes(&changes));
+
+        // the repository is updated with the same version
+        let changed_collection = PackageCollectionBuilder::new()
+            .add(
+                PackageManifest::new("com.vrchat.avatars", Version::new(1, 0, 0))
+                    .add_vpm_dependency("com.vrchat.base", "1.0.1"),
+            )
+            .add(PackageManifest::new(
+                "com.vrchat.base",
+                Version::new(1, 0, 0),
+            ))
+            .build();
+
+        let err = project
+            .restore_pending_changes(&changed_collection, &saved)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            RestoreChangesErr::PackageChanged { ref package_name, .. }
+                if package_name.as_ref() == "com.vrchat.avatars"
+        ));
+    })
+}
+
+#[test]
+fn refuse_restore_crafted_changes() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_locked("com.vrchat.base", Version::new(1, 0, 0), &[])
+            .build()
+            .await
+            .unwrap();
+        let collection = PackageCollectionBuilder::new()
+            .add(
+                PackageManifest::new("com.anatawa12.legacy", Version::new(1, 0, 0))
+                    .add_legacy_folder("Assets\\LegacyFolder", ""),
+            )
+            .build();
+
+        let legacy_package = collection.get_package("com.anatawa12.legacy", Version::new(1, 0, 0));
+
+        let changes = project
+            .add_package_request(
+                &collection,
+                &[legacy_package],
+                AddPackageOperation::InstallToDependencies,
+                false,
+            )
+            .await
+            .unwrap();
+
+        let saved = serde_json::to_value(project.save_pending_changes(&changes)).unwrap();
+
+        let restore = |saved: &serde_json::Value| {
+            let saved = serde_json::from_value::<SavedPendingChanges>(saved.clone()).unwrap();
+            project.restore_pending_changes(&collection, &saved)
+        };
+
+        let mut crafted = saved.clone();
+        crafted["removeLegacyFolders"] = serde_json::json!([
+            { "path": "Assets/LegacyFolder", "package": "com.anatawa12.legacy" },
+        ]);
+        let restored = restore(&crafted).unwrap();
+        assert_eq!(restored.remove_legacy_folders().len(), 1);
+
+        // removing the package not in the project
+        let mut crafted = saved.clone();
+        crafted["packageChanges"]["../Assets"] =
+            serde_json::json!({ "type": "remove", "reason": "requested" });
+        let err = restore(&crafted).unwrap_err();
+        assert!(matches!(err, RestoreChangesErr::InvalidChanges));
+
+        // the package is in the project
+        let mut crafted = saved.clone();
+        crafted["packageChanges"]["com.vrchat.base"] =
+            serde_json::json!({ "type": "remove", "reason": "requested" });
+        restore(&crafted).unwrap();
+
+        // the legacy folder outside the project
+        let mut crafted = saved.clone();
+        crafted["removeLegacyFolders"] = serde_json::json!([
+            { "path": "../../x", "package": "com.anatawa12.legacy" },
+        ]);
+        let err = restore(&crafted).unwrap_err();
+        assert!(matches!(err, RestoreChangesErr::InvalidChanges));
+
+        // the legacy folder not declared by the package
+        let mut crafted = saved.clone();
+        crafted["removeLegacyFolders"] = serde_json::json!([
+            { "path": "Assets/Other", "package": "com.anatawa12.legacy" },
+        ]);
+        let err = restore(&crafted).unwrap_err();
+        assert!(matches!(err, RestoreChangesErr::InvalidChanges));
+    })
+}
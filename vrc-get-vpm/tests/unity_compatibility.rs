@@ -0,0 +1,158 @@
+use common::*;
+use futures::executor::block_on;
+use serde_json::json;
+use vrc_get_vpm::repository::RemoteRepository;
+use vrc_get_vpm::unity_compatibility::{unity_incompatibility, UnityIncompatibility};
+use vrc_get_vpm::unity_project::AddPackageOperation;
+use vrc_get_vpm::version::{UnityVersion, Version};
+use vrc_get_vpm::{VersionMismatch, VersionSelector};
+
+mod common;
+
+fn unity(version: &str) -> UnityVersion {
+    UnityVersion::parse(version).unwrap()
+}
+
+#[test]
+fn builtin_rules() {
+    let sdk_2019 =
+        manifest(json!({"name": "com.vrchat.avatars", "version": "3.4.2", "unity": "2019.4"}));
+    let sdk_2019_beta = manifest(json!({"name": "com.vrchat.base", "version": "3.4.0-beta.1"}));
+    let sdk_2022 =
+        manifest(json!({"name": "com.vrchat.avatars", "version": "3.5.0", "unity": "2022.3"}));
+    let resolver = manifest(json!({"name": "com.vrchat.core.vpm-resolver", "version": "0.1.26"}));
+
+    assert!(unity_incompatibility(&sdk_2019, unity("2019.4.31f1")).is_none());
+    assert!(matches!(
+        unity_incompatibility(&sdk_2019, unity("2022.3.6f1")),
+        Some(UnityIncompatibility::TooNew { .. })
+    ));
+    assert!(unity_incompatibility(&sdk_2019_beta, unity("2022.3.6f1")).is_some());
+    assert!(unity_incompatibility(&sdk_2022, unity("2022.3.6f1")).is_none());
+    assert!(unity_incompatibility(&resolver, unity("2022.3.6f1")).is_some());
+}
+
+#[test]
+fn unity_release() {
+    let package = manifest(json!({
+        "name": "com.anatawa12.tool",
+        "version": "1.0.0",
+        "unity": "2022.3",
+        "unityRelease": "22f1",
+    }));
+
+    assert!(unity_incompatibility(&package, unity("2022.3.22f1")).is_none());
+    assert!(unity_incompatibility(&package, unity("2023.1.0f1")).is_none());
+    let Some(UnityIncompatibility::TooOld { minimum }) =
+        unity_incompatibility(&package, unity("2022.3.6f1"))
+    else {
+        panic!("should be too old")
+    };
+    assert_eq!(minimum, unity("2022.3.22f1"));
+}
+
+#[test]
+fn max_unity() {
+    let package = manifest(json!({
+        "name": "com.anatawa12.tool",
+        "version": "1.0.0",
+        "vrc-get": { "maxUnity": "2022.3" },
+    }));
+
+    assert!(unity_incompatibility(&package, unity("2022.3.6f1")).is_none());
+    assert!(unity_incompatibility(&package, unity("2023.2.0f1")).is_some());
+}
+
+#[test]
+fn repository_rules() {
+    let repository = RemoteRepository::parse(
+        json!({
+            "packages": {
+                "com.anatawa12.tool": {
+                    "versions": {
+                        "1.0.0": {"name": "com.anatawa12.tool", "version": "1.0.0"},
+                        "2.0.0": {"name": "com.anatawa12.tool", "version": "2.0.0"},
+                    }
+                }
+            },
+            "vrc-get": {
+                "unityCompatibility": [
+                    {"package": "com.anatawa12.tool", "versions": "<2.0.0", "maxUnity": "2019.4"}
+                ]
+            }
+        })
+        .as_object()
+        .unwrap()
+        .clone(),
+    )
+    .unwrap();
+
+    let v1 =
+        (repository.get_package_version("com.anatawa12.tool", &Version::new(1, 0, 0))).unwrap();
+    let v2 =
+        (repository.get_package_version("com.anatawa12.tool", &Version::new(2, 0, 0))).unwrap();
+
+    assert!(unity_incompatibility(v1, unity("2022.3.6f1")).is_some());
+    assert!(unity_incompatibility(v2, unity("2022.3.6f1")).is_none());
+
+    // the selector tells why the version is not selected
+    let selector = VersionSelector::latest_for(Some(unity("2022.3.6f1")), false);
+    assert!(matches!(
+        selector.mismatch(v1),
+        Some(VersionMismatch::Unity(UnityIncompatibility::TooNew { .. }))
+    ));
+    assert!(selector.mismatch(v2).is_none());
+
+    let version = Version::new(2, 0, 0);
+    let selector = VersionSelector::specific_version(&version);
+    assert!(matches!(
+        selector.mismatch(v1),
+        Some(VersionMismatch::Version)
+    ));
+}
+
+#[test]
+fn conflict_tells_reason() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_file(
+                "ProjectSettings/ProjectVersion.txt",
+                "m_EditorVersion: 2022.3.6f1\n",
+            )
+            .build()
+            .await
+            .unwrap();
+
+        let collection = PackageCollectionBuilder::new()
+            .add(manifest(json!({
+                "name": "com.anatawa12.tool",
+                "version": "1.0.0",
+                "vpmDependencies": { "com.anatawa12.library": ">=1.0.0" },
+            })))
+            .add(manifest(json!({
+                "name": "com.anatawa12.library",
+                "version": "1.0.0",
+                "vrc-get": { "maxUnity": "2019.4" },
+            })))
+            .build();
+
+        let tool = collection.get_package("com.anatawa12.tool", Version::new(1, 0, 0));
+
+        let result = project
+            .add_package_request(
+                &collection,
+                &[tool],
+                AddPackageOperation::InstallToDependencies,
+                false,
+            )
+            .await
+            .unwrap();
+
+        let conflict = &result.conflicts()["com.anatawa12.library"];
+        assert!(conflict.conflicts_with_unity());
+        assert!(matches!(
+            conflict.unity_incompatibility(),
+            Some(UnityIncompatibility::TooNew { .. })
+        ));
+    })
+}
@@ -0,0 +1,67 @@
+use common::*;
+use futures::executor::block_on;
+use vrc_get_vpm::unity_project::UpgradeLevel;
+use vrc_get_vpm::version::Version;
+use vrc_get_vpm::PackageManifest;
+
+mod common;
+
+#[test]
+fn find_upgrade_for_each_level() {
+    block_on(async {
+        let project = VirtualProjectBuilder::new()
+            .add_dependency("com.vrchat.avatars", Version::new(1, 0, 0))
+            .add_locked(
+                "com.vrchat.avatars",
+                Version::new(1, 0, 0),
+                &[("com.vrchat.base", "^1.2.0")],
+            )
+            .add_locked("com.vrchat.base", Version::new(1, 2, 3), &[])
+            .build()
+            .await
+            .unwrap();
+
+        let collection = PackageCollectionBuilder::new()
+            .add(
+                PackageManifest::new("com.vrchat.avatars", Version::new(1, 0, 0))
+                    .add_vpm_dependency("com.vrchat.base", "^1.2.0"),
+            )
+            .add(PackageManifest::new(
+                "com.vrchat.base",
+                Version::new(1, 2, 3),
+            ))
+            .add(PackageManifest::new(
+                "com.vrchat.base",
+                Version::new(1, 2, 5),
+            ))
+            .add(PackageManifest::new(
+                "com.vrchat.base",
+                Version::new(1, 3, 0),
+            ))
+            .add(PackageManifest::new(
+                "com.vrchat.base",
+                Version::new(2, 0, 0),
+            ))
+            .build();
+
+        let find = |level| {
+            project
+                .find_upgrade(&collection, "com.vrchat.base", level, false)
+                .map(|x| x.version().clone())
+        };
+
+        assert_eq!(find(UpgradeLevel::Patch), Some(Version::new(1, 2, 5)));
+        assert_eq!(find(UpgradeLevel::Minor), Some(Version::new(1, 3, 0)));
+        assert_eq!(find(UpgradeLevel::Compatible), Some(Version::new(1, 3, 0)));
+        assert_eq!(find(UpgradeLevel::Latest), Some(Version::new(2, 0, 0)));
+
+        // no newer version
+        let avatars = project.find_upgrade(
+            &collection,
+            "com.vrchat.avatars",
+            UpgradeLevel::Latest,
+            false,
+        );
+        assert!(avatars.is_none());
+    })
+}
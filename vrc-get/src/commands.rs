@@ -2,6 +2,7 @@ use clap::{Args, Parser, Subcommand};
 use indexmap::IndexMap;
 use itertools::Itertools;
 
+use crate::output::{ErrorExitCode, OutputFormat};
 use log::warn;
 use reqwest::header::{HeaderName, HeaderValue, InvalidHeaderName, InvalidHeaderValue};
 use reqwest::{Client, Url};
@@ -13,15 +14,17 @@ use std::ffi::OsStr;
 use std::fmt::{Debug, Display};
 use std::num::NonZeroU32;
 use std::path::{Component, Path, PathBuf};
-use std::process::exit;
 use std::str::FromStr;
 use vrc_get_vpm::io::{DefaultEnvironmentIo, DefaultProjectIo};
 use vrc_get_vpm::repository::RemoteRepository;
 use vrc_get_vpm::unity_project::pending_project_changes::{PackageChange, RemoveReason};
-use vrc_get_vpm::unity_project::{AddPackageOperation, PendingProjectChanges};
-use vrc_get_vpm::version::Version;
+use vrc_get_vpm::unity_project::{
+    AddPackageOperation, PendingProjectChanges, SavedPendingChanges, UpgradeLevel,
+};
+use vrc_get_vpm::version::{DependencyRangeStyle, Version, VersionRange};
 use vrc_get_vpm::{
-    PackageCollection, PackageInfo, PackageManifest, UserRepoSetting, VersionSelector,
+    PackageCollection, PackageDeprecation, PackageInfo, PackageManifest, UserRepoSetting,
+    VersionMismatch, VersionSelector,
 };
 
 type Environment = vrc_get_vpm::Environment<Client, DefaultEnvironmentIo>;
@@ -64,10 +67,25 @@ macro_rules! multi_command {
 // small wrapper utilities
 
 macro_rules! exit_with {
-    ($($tt:tt)*) => {{
-        eprintln!($($tt)*);
-        ::std::process::exit(1)
-    }};
+    ($code: ident, $($tt:tt)*) => {
+        $crate::output::exit_with($crate::output::ExitCode::$code, format_args!($($tt)*))
+    };
+    ($($tt:tt)*) => {
+        $crate::output::exit_with($crate::output::ExitCode::Error, format_args!($($tt)*))
+    };
+}
+
+/// println for human readable messages.
+///
+/// With `--output json`, the message will be printed to stderr to keep stdout machine-readable.
+macro_rules! human_println {
+    ($($tt:tt)*) => {
+        if $crate::output::is_json() {
+            eprintln!($($tt)*);
+        } else {
+            println!($($tt)*);
+        }
+    };
 }
 
 #[derive(Args, Default)]
@@ -104,9 +122,18 @@ async fn load_unity(path: Option<Box<Path>>) -> UnityProject {
         Some(path) => DefaultProjectIo::new(path),
     };
 
-    UnityProject::load(io)
+    let mut unity = UnityProject::load(io)
         .await
-        .exit_context("loading unity project")
+        .exit_context("loading unity project");
+
+    // record the command line as the reason of the changes in the history
+    // arguments may not be valid unicode, like paths on linux
+    let args = (env::args_os().skip(1))
+        .map(|x| x.to_string_lossy().into_owned())
+        .join(" ");
+    unity.set_history_reason(Some(format!("vrc-get {args}").into()));
+
+    unity
 }
 
 #[cfg(feature = "experimental-vcc")]
@@ -130,8 +157,29 @@ fn get_package<'env>(
     name: &str,
     selector: VersionSelector,
 ) -> PackageInfo<'env> {
-    env.find_package_by_name(name, selector)
-        .unwrap_or_else(|| exit_with!("no matching package not found"))
+    env.find_package_by_name(name, selector).unwrap_or_else(|| {
+        // tell why the latest version is not selected if it's only because of the unity version
+        let incompatible = (env.find_packages(name))
+            .filter_map(|package| {
+                if let Some(VersionMismatch::Unity(reason)) =
+                    selector.mismatch(package.package_json())
+                {
+                    Some((package, reason))
+                } else {
+                    None
+                }
+            })
+            .max_by_key(|(package, _)| package.version());
+        match incompatible {
+            Some((package, reason)) => exit_with!(
+                NotFound,
+                "no matching package not found: {} version {} {reason}",
+                package.name(),
+                package.version()
+            ),
+            None => exit_with!(NotFound, "no matching package not found"),
+        }
+    })
 }
 
 async fn save_unity(unity: &mut UnityProject) {
@@ -146,7 +194,12 @@ fn confirm_prompt(msg: &str) -> bool {
     use std::io;
     use std::io::Write;
     fn _impl(msg: &str) -> io::Result<bool> {
-        let mut stdout = io::stdout();
+        // with json output, stdout is reserved for the result document
+        let mut stdout: Box<dyn Write> = if crate::output::is_json() {
+            Box::new(io::stderr())
+        } else {
+            Box::new(io::stdout())
+        };
         let stdin = io::stdin();
         let mut buf = String::new();
         loop {
@@ -155,7 +208,11 @@ fn confirm_prompt(msg: &str) -> bool {
             stdout.flush()?;
 
             buf.clear();
-            stdin.read_line(&mut buf)?;
+            if stdin.read_line(&mut buf)? == 0 {
+                // EOF: non-interactive environment
+                writeln!(stdout)?;
+                return Ok(false);
+            }
 
             buf.make_ascii_lowercase();
 
@@ -170,86 +227,78 @@ fn confirm_prompt(msg: &str) -> bool {
     _impl(msg).unwrap_or(false)
 }
 
-fn print_prompt_install(changes: &PendingProjectChanges) {
-    if changes.package_changes().is_empty() {
+fn print_prompt_install(changes: &PendingProjectChanges) -> PlannedChanges {
+    let planned = PlannedChanges::new(changes);
+
+    if changes.package_changes().is_empty()
+        && changes.remove_from_dependencies().is_empty()
+        && changes.remove_legacy_files().is_empty()
+        && changes.remove_legacy_folders().is_empty()
+    {
+        if crate::output::is_json() {
+            // nothing to do is not an error for scripts
+            crate::output::set_result(ChangesResult::new(planned, false));
+            crate::output::exit_success();
+        }
         exit_with!("nothing to do")
     }
 
-    let mut newly_installed = Vec::new();
-    let mut adding_to_dependencies = Vec::new();
-    let mut removed = Vec::new();
+    crate::output::set_result(ChangesResult::new(planned.clone(), false));
 
-    for (name, change) in changes.package_changes() {
-        match change {
-            PackageChange::Install(change) => {
-                if let Some(package) = change.install_package() {
-                    newly_installed.push(package);
-                }
-                if let Some(v) = change.to_dependencies() {
-                    adding_to_dependencies.push((name, v));
-                }
-            }
-            PackageChange::Remove(change) => {
-                removed.push((change.reason(), name));
+    if !planned.install.is_empty() {
+        human_println!("You're installing the following packages:");
+        for x in &planned.install {
+            if x.yanked {
+                human_println!("- {} version {} (yanked)", x.name, x.version);
+            } else {
+                human_println!("- {} version {}", x.name, x.version);
             }
         }
     }
 
-    if !newly_installed.is_empty() {
-        println!("You're installing the following packages:");
-        for x in &newly_installed {
-            if x.is_yanked() {
-                println!("- {} version {} (yanked)", x.name(), x.version());
-            } else {
-                println!("- {} version {}", x.name(), x.version());
-            }
+    if !planned.add_to_dependencies.is_empty() {
+        human_println!("You're adding the following packages to dependencies:");
+        for x in &planned.add_to_dependencies {
+            human_println!("- {} version {}", x.name, x.range);
         }
     }
 
-    if !adding_to_dependencies.is_empty() {
-        println!("You're adding the following packages to dependencies:");
-        for (name, range) in &adding_to_dependencies {
-            println!("- {} version {}", name, range);
+    if !planned.remove_from_dependencies.is_empty() {
+        human_println!("You're removing the following packages from dependencies:");
+        for x in &planned.remove_from_dependencies {
+            human_println!("- {x}");
         }
     }
 
-    if !changes.remove_legacy_folders().is_empty() || !changes.remove_legacy_files().is_empty() {
-        println!("You're removing the following legacy assets:");
-        for (x, _) in changes
-            .remove_legacy_folders()
-            .iter()
-            .chain(changes.remove_legacy_files())
-        {
-            println!("- {}", x.display());
+    if !planned.remove_legacy_folders.is_empty() || !planned.remove_legacy_files.is_empty() {
+        human_println!("You're removing the following legacy assets:");
+        for x in (planned.remove_legacy_folders.iter()).chain(&planned.remove_legacy_files) {
+            human_println!("- {}", x.path);
         }
     }
 
-    if !removed.is_empty() {
-        println!("You're removing the following packages:");
-        removed.sort_by_key(|(reason, _)| *reason);
-        for (reason, name) in removed {
-            let reason_name = match reason {
-                RemoveReason::Requested => "requested",
-                RemoveReason::Legacy => "legacy",
-                RemoveReason::Unused => "unused",
-            };
-            println!("- {} (removed since {})", name, reason_name);
+    if !planned.remove.is_empty() {
+        human_println!("You're removing the following packages:");
+        let mut removed = planned.remove.iter().collect::<Vec<_>>();
+        removed.sort_by_key(|x| x.reason);
+        for x in removed {
+            human_println!("- {} (removed since {})", x.name, x.reason.as_str());
         }
     }
 
     // process package conflicts
     {
-        let mut conflicts = (changes.conflicts().iter())
-            .filter(|(_, conflicts)| !conflicts.conflicting_packages().is_empty())
+        let mut conflicts = (planned.conflicts.iter())
+            .filter(|x| !x.conflicts_with.is_empty())
             .peekable();
 
         if conflicts.peek().is_some() {
-            println!("**Those changes conflicts with the following packages**");
+            human_println!("**Those changes conflicts with the following packages**");
 
-            for (package, conflicts) in conflicts {
-                println!("{package} conflicts with:");
-                for conflict in conflicts.conflicting_packages() {
-                    println!("- {conflict}");
+            for x in conflicts {
+                human_println!("{} conflicts with:", x.package);
+                for conflict in &x.conflicts_with {
+                    human_println!("- {conflict}");
                 }
             }
         }
@@ -257,25 +306,311 @@ fn print_prompt_install(changes: &PendingProjectChanges) {
 
     // process unity conflicts
     {
-        let mut unity_conflicts = (changes.conflicts().iter())
-            .filter(|(_, conflicts)| conflicts.conflicts_with_unity())
-            .map(|(package, _)| package)
+        let mut unity_conflicts = (planned.conflicts.iter())
+            .filter(|x| x.unity_incompatible)
             .peekable();
 
         if unity_conflicts.peek().is_some() {
-            println!("**Those packages are incompatible with your unity version**");
-            for package in unity_conflicts {
-                println!("- {}", package);
+            human_println!("**Those packages are incompatible with your unity version**");
+            for x in unity_conflicts {
+                match &x.unity_incompatibility {
+                    Some(reason) => human_println!("- {} ({reason})", x.package),
+                    None => human_println!("- {}", x.package),
+                }
+            }
+        }
+    }
+
+    planned
+}
+
+/// The result of commands that modify the project
+#[derive(Serialize)]
+struct ChangesResult {
+    changes: PlannedChanges,
+    applied: bool,
+}
+
+impl ChangesResult {
+    fn new(changes: PlannedChanges, applied: bool) -> Self {
+        Self { changes, applied }
+    }
+}
+
+/// The serializable form of [`PendingProjectChanges`].
+///
+/// All lists are sorted to make the output deterministic.
+#[derive(Serialize, Clone, Default)]
+struct PlannedChanges {
+    install: Vec<PlannedInstall>,
+    add_to_dependencies: Vec<PlannedDependency>,
+    remove_from_dependencies: Vec<String>,
+    remove: Vec<PlannedRemove>,
+    remove_legacy_files: Vec<PlannedLegacyAsset>,
+    remove_legacy_folders: Vec<PlannedLegacyAsset>,
+    conflicts: Vec<PlannedConflict>,
+}
+
+#[derive(Serialize, Clone)]
+struct PlannedInstall {
+    name: String,
+    version: Version,
+    yanked: bool,
+}
+
+#[derive(Serialize, Clone)]
+struct PlannedDependency {
+    name: String,
+    range: String,
+}
+
+#[derive(Serialize, Clone)]
+struct PlannedRemove {
+    name: String,
+    reason: PlannedRemoveReason,
+}
+
+#[derive(Serialize, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+#[serde(rename_all = "snake_case")]
+enum PlannedRemoveReason {
+    Requested,
+    Legacy,
+    Unused,
+}
+
+impl PlannedRemoveReason {
+    fn as_str(self) -> &'static str {
+        match self {
+            PlannedRemoveReason::Requested => "requested",
+            PlannedRemoveReason::Legacy => "legacy",
+            PlannedRemoveReason::Unused => "unused",
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+struct PlannedLegacyAsset {
+    path: String,
+    package: String,
+}
+
+#[derive(Serialize, Clone)]
+struct PlannedConflict {
+    package: String,
+    conflicts_with: Vec<String>,
+    unity_incompatible: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unity_incompatibility: Option<String>,
+}
+
+impl PlannedChanges {
+    fn new(changes: &PendingProjectChanges) -> Self {
+        let mut planned = Self::default();
+
+        for (name, change) in changes.package_changes() {
+            match change {
+                PackageChange::Install(change) => {
+                    if let Some(package) = change.install_package() {
+                        planned.install.push(PlannedInstall {
+                            name: package.name().to_owned(),
+                            version: package.version().clone(),
+                            yanked: package.is_yanked(),
+                        });
+                    }
+                    if let Some(range) = change.to_dependencies() {
+                        planned.add_to_dependencies.push(PlannedDependency {
+                            name: name.to_string(),
+                            range: range.to_string(),
+                        });
+                    }
+                }
+                PackageChange::Remove(change) => {
+                    let reason = match change.reason() {
+                        RemoveReason::Requested => PlannedRemoveReason::Requested,
+                        RemoveReason::Legacy => PlannedRemoveReason::Legacy,
+                        RemoveReason::Unused => PlannedRemoveReason::Unused,
+                    };
+                    planned.remove.push(PlannedRemove {
+                        name: name.to_string(),
+                        reason,
+                    });
+                }
+            }
+        }
+
+        fn legacy_assets(assets: &[(Box<Path>, &str)]) -> Vec<PlannedLegacyAsset> {
+            (assets.iter())
+                .map(|(path, package)| PlannedLegacyAsset {
+                    path: path.display().to_string(),
+                    package: package.to_string(),
+                })
+                .sorted_by(|a, b| a.path.cmp(&b.path))
+                .collect()
+        }
+
+        planned.remove_from_dependencies = (changes.remove_from_dependencies().iter())
+            .map(ToString::to_string)
+            .sorted()
+            .collect();
+        planned.remove_legacy_files = legacy_assets(changes.remove_legacy_files());
+        planned.remove_legacy_folders = legacy_assets(changes.remove_legacy_folders());
+
+        planned.conflicts = (changes.conflicts().iter())
+            .map(|(package, conflict)| PlannedConflict {
+                package: package.to_string(),
+                conflicts_with: (conflict.conflicting_packages().iter())
+                    .map(ToString::to_string)
+                    .sorted()
+                    .collect(),
+                unity_incompatible: conflict.conflicts_with_unity(),
+                unity_incompatibility: conflict.unity_incompatibility().map(ToString::to_string),
+            })
+            .collect();
+
+        planned.install.sort_by(|a, b| a.name.cmp(&b.name));
+        planned
+            .add_to_dependencies
+            .sort_by(|a, b| a.name.cmp(&b.name));
+        planned.remove.sort_by(|a, b| a.name.cmp(&b.name));
+        planned.conflicts.sort_by(|a, b| a.package.cmp(&b.package));
+
+        planned
+    }
+}
+
+#[derive(Args)]
+struct PlanArgs {
+    /// Print the planned changes without applying them
+    #[arg(long)]
+    dry_run: bool,
+    /// Save the planned changes to the file instead of applying them.
+    ///
+    /// The saved changes can be applied later with `vrc-get apply`.
+    #[arg(long, value_name = "FILE")]
+    save_plan: Option<PathBuf>,
+    #[command(flatten)]
+    overwrite: OverwriteArgs,
+}
+
+#[derive(Args)]
+#[group(multiple = false)]
+struct SaveArgs {
+    /// Save the exact installed version to dependencies (`=x.y.z`)
+    #[arg(long)]
+    save_exact: bool,
+    /// Save the installed version and later versions with the same major version to dependencies (`^x.y.z`)
+    #[arg(long)]
+    save_caret: bool,
+    /// Save the installed version and later versions with the same minor version to dependencies (`~x.y.z`)
+    #[arg(long)]
+    save_tilde: bool,
+}
+
+impl SaveArgs {
+    /// Returns the style of the range written to dependencies.
+    ///
+    /// Without any options, `dependencyRangeStyle` in vrc-get settings is used.
+    fn style(&self, env: &Environment) -> DependencyRangeStyle {
+        if self.save_exact {
+            DependencyRangeStyle::Exact
+        } else if self.save_caret {
+            DependencyRangeStyle::Caret
+        } else if self.save_tilde {
+            DependencyRangeStyle::Tilde
+        } else {
+            env.dependency_range_style().clone()
+        }
+    }
+}
+
+impl PlanArgs {
+    /// Saves the changes if `--save-plan` is specified, and exits without applying the changes
+    /// if `--dry-run` or `--save-plan` is specified.
+    async fn save_or_dry_run(&self, unity: &UnityProject, changes: &PendingProjectChanges<'_>) {
+        if let Some(path) = &self.save_plan {
+            let saved = unity.save_pending_changes(changes);
+            let json = serde_json::to_vec_pretty(&saved).expect("serializing plan");
+            tokio::fs::write(path, json)
+                .await
+                .exit_context("saving plan");
+            human_println!("Saved the planned changes to {}", path.display());
+        } else if self.dry_run {
+            human_println!("--dry-run is set. no changes are applied");
+        } else {
+            return;
+        }
+
+        crate::output::exit_success()
+    }
+}
+
+#[derive(Args)]
+struct OverwriteArgs {
+    /// Overwrite or remove packages with local modifications without confirmation
+    #[arg(long)]
+    force: bool,
+    /// Save the local modifications of packages to be overwritten as patch files in the folder
+    #[arg(long, value_name = "DIR")]
+    save_diff: Option<PathBuf>,
+}
+
+impl OverwriteArgs {
+    /// Warns and asks for confirmation if the changes overwrite or remove packages
+    /// with local modifications.
+    async fn check_modified(
+        &self,
+        unity: &UnityProject,
+        env: &Environment,
+        changes: &PendingProjectChanges<'_>,
+    ) {
+        let modified = unity
+            .overwritten_modifications(changes)
+            .await
+            .exit_context("checking local modifications");
+        if modified.is_empty() {
+            return;
+        }
+
+        for x in &modified {
+            warn!(
+                "{} has local modifications that will be lost: {} modified, {} added, {} deleted",
+                x.name(),
+                x.modified().len(),
+                x.added().len(),
+                x.deleted().len(),
+            );
+        }
+
+        if let Some(dir) = &self.save_diff {
+            tokio::fs::create_dir_all(dir)
+                .await
+                .exit_context("creating folder for diff");
+            for x in &modified {
+                let diff = unity
+                    .package_diff(env, x.name(), x.version())
+                    .await
+                    .exit_context("creating diff");
+                let path = dir.join(format!("{}@{}.patch", x.name(), x.version()));
+                tokio::fs::write(&path, diff)
+                    .await
+                    .exit_context("saving diff");
+                human_println!("Saved the diff of {} to {}", x.name(), path.display());
             }
         }
+
+        if self.force {
+            human_println!("--force is set. overwriting local modifications");
+        } else if !confirm_prompt("Do you want to discard the local modifications?") {
+            exit_with!(Aborted, "aborted by user");
+        }
     }
 }
 
 fn prompt_install(yes: bool) {
     if yes {
-        println!("--yes is set. skipping confirm");
+        human_println!("--yes is set. skipping confirm");
     } else if !confirm_prompt("Do you want to apply those changes?") {
-        exit(1);
+        exit_with!(Aborted, "aborted by user");
     }
 }
 
@@ -320,46 +655,105 @@ fn require_prompt_for_install(
 trait ResultExt<T, E>: Sized {
     fn exit_context(self, context: &str) -> T
     where
-        E: Display;
+        E: Display + ErrorExitCode;
 }
 
 impl<T, E> ResultExt<T, E> for Result<T, E> {
     fn exit_context(self, context: &str) -> T
     where
-        E: Display,
+        E: Display + ErrorExitCode,
     {
         match self {
             Ok(value) => value,
-            Err(err) => exit_with!("error {context}: {err}"),
+            Err(err) => {
+                crate::output::exit_with(err.exit_code(), format_args!("error {context}: {err}"))
+            }
         }
     }
 }
 
+mod diff;
+mod doctor;
+mod graph;
+mod history;
+mod hold;
 mod info;
 mod migrate;
+mod package;
+mod patch;
+mod resolution;
+mod samples;
+mod sbom;
+mod serve;
+mod status;
+mod unitypackage;
 #[cfg(feature = "experimental-vcc")]
 mod vcc;
 
 /// Open Source command line interface of VRChat Package Manager.
 #[derive(Parser)]
-#[command(author, version, about)]
+#[command(author, version, about, after_long_help = EXIT_CODES_HELP)]
+pub struct Cli {
+    /// The output format.
+    ///
+    /// With `json`, exactly one json document with the result, planned changes, and warnings
+    /// is printed to stdout when the command finishes.
+    #[arg(long, global = true, value_enum, default_value_t)]
+    pub output: OutputFormat,
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+const EXIT_CODES_HELP: &str = "\
+Exit Codes:
+  0  success
+  1  other errors
+  2  invalid command line arguments
+  3  package, project, repository, or file not found
+  4  requested changes conflict with the project
+  5  network error
+  6  aborted by user
+  7  file system error";
+
+#[derive(Subcommand)]
 pub enum Command {
     #[command(alias = "i")]
     Install(Install),
     Resolve(Resolve),
+    Apply(Apply),
     #[command(alias = "rm")]
     Remove(Remove),
     Update(Update),
     Outdated(Outdated),
     Upgrade(Upgrade),
     Downgrade(Downgrade),
+    History(history::History),
+    Undo(history::Undo),
+    Hold(hold::Hold),
+    Unhold(hold::Unhold),
     Search(Search),
+    Sbom(sbom::Sbom),
+    Status(status::Status),
+    Diff(diff::Diff),
+    Doctor(doctor::Doctor),
+    Graph(graph::Graph),
+    Import(unitypackage::Import),
+    ExportUnitypackage(unitypackage::ExportUnitypackage),
     #[command(subcommand)]
     Repo(Repo),
     #[command(subcommand)]
     Info(info::Info),
     #[command(subcommand)]
     Migrate(migrate::Migrate),
+    #[command(subcommand)]
+    Package(package::Package),
+    #[command(subcommand)]
+    Patch(patch::Patch),
+    #[command(subcommand)]
+    Resolution(resolution::Resolution),
+    #[command(subcommand)]
+    Samples(samples::Samples),
+    Serve(serve::Serve),
     #[cfg(feature = "experimental-vcc")]
     #[command(subcommand)]
     Vcc(vcc::Vcc),
@@ -373,15 +767,32 @@ pub enum Command {
 multi_command!(Command is
     Install,
     Resolve,
+    Apply,
     Remove,
     Update,
     Outdated,
     Upgrade,
     Downgrade,
+    History,
+    Undo,
+    Hold,
+    Unhold,
     Search,
+    Sbom,
+    Status,
+    Diff,
+    Doctor,
+    Graph,
+    Import,
+    ExportUnitypackage,
     Repo,
     Info,
     Migrate,
+    Package,
+    Patch,
+    Resolution,
+    Samples,
+    Serve,
     Vcc,
     Completion,
 );
@@ -408,12 +819,24 @@ pub struct Install {
     /// This option is experimental and behavior may change in the future.
     #[arg(long = "name", short = 'n')]
     name: bool,
+    #[command(flatten)]
+    save_args: SaveArgs,
+    /// Save the specified range to dependencies. Only allowed when one package is installed
+    #[arg(
+        long,
+        value_name = "RANGE",
+        requires = "id",
+        conflicts_with_all = ["save_exact", "save_caret", "save_tilde"]
+    )]
+    save_range: Option<VersionRange>,
 
     /// Path to project dir. by default CWD or parents of CWD will be used
     #[arg(short = 'p', long = "project")]
     project: Option<Box<Path>>,
     #[command(flatten)]
     env_args: EnvArgs,
+    #[command(flatten)]
+    plan_args: PlanArgs,
 
     /// skip confirm
     #[arg(short, long)]
@@ -425,8 +848,10 @@ impl Install {
         let Some(name) = self.id else {
             // if resolve
             return Resolve {
+                save_args: self.save_args,
                 project: self.project,
                 env_args: self.env_args,
+                plan_args: self.plan_args,
             }
             .run()
             .await;
@@ -459,13 +884,32 @@ impl Install {
                         .any(|x| normalize_name(x) == normalized)
             });
             if packages.is_empty() {
-                exit_with!("no matching package not found")
+                exit_with!(NotFound, "no matching package not found")
             }
             packages.into_iter().unique_by(|x| x.name()).collect()
         } else {
             vec![get_package(&env, &name, version_selector)]
         };
 
+        let style = match &self.save_range {
+            None => self.save_args.style(&env),
+            Some(range) => {
+                let [package] = packages.as_slice() else {
+                    exit_with!("--save-range can only be used when installing one package");
+                };
+                if !range.match_pre(package.version(), true) {
+                    exit_with!(
+                        Conflict,
+                        "{} version {} does not match --save-range {range}",
+                        package.name(),
+                        package.version()
+                    );
+                }
+                DependencyRangeStyle::Range(range.clone())
+            }
+        };
+        unity.set_dependency_range_style(style);
+
         let changes = unity
             .add_package_request(
                 &env,
@@ -476,7 +920,11 @@ impl Install {
             .await
             .exit_context("collecting packages to be installed");
 
-        print_prompt_install(&changes);
+        let planned = print_prompt_install(&changes);
+        self.plan_args.save_or_dry_run(&unity, &changes).await;
+        (self.plan_args.overwrite)
+            .check_modified(&unity, &env, &changes)
+            .await;
 
         if require_prompt_for_install(&changes, name.as_str(), None) {
             prompt_install(self.yes);
@@ -488,6 +936,7 @@ impl Install {
             .exit_context("adding package");
 
         unity.save().await.exit_context("saving manifest file");
+        crate::output::set_result(ChangesResult::new(planned, true));
         update_project_last_modified(env, unity.project_dir()).await;
     }
 }
@@ -499,11 +948,16 @@ impl Install {
 #[derive(Parser)]
 #[command(author, version)]
 pub struct Resolve {
+    #[command(flatten)]
+    save_args: SaveArgs,
+
     /// Path to project dir. by default CWD or parents of CWD will be used
     #[arg(short = 'p', long = "project")]
     project: Option<Box<Path>>,
     #[command(flatten)]
     env_args: EnvArgs,
+    #[command(flatten)]
+    plan_args: PlanArgs,
 }
 
 impl Resolve {
@@ -511,12 +965,18 @@ impl Resolve {
         let env = load_env(&self.env_args).await;
         let mut unity = load_unity(self.project).await;
 
+        unity.set_dependency_range_style(self.save_args.style(&env));
+
         let changes = unity
             .resolve_request(&env)
             .await
             .exit_context("collecting packages to be installed");
 
-        print_prompt_install(&changes);
+        let planned = print_prompt_install(&changes);
+        self.plan_args.save_or_dry_run(&unity, &changes).await;
+        (self.plan_args.overwrite)
+            .check_modified(&unity, &env, &changes)
+            .await;
 
         unity
             .apply_pending_changes(&env, changes)
@@ -524,6 +984,62 @@ impl Resolve {
             .exit_context("installing packages");
 
         unity.save().await.exit_context("saving manifest file");
+        crate::output::set_result(ChangesResult::new(planned, true));
+    }
+}
+
+/// Apply the changes saved with `--save-plan`
+///
+/// This refuses to apply the changes if the lock of the project or the packages in the repositories
+/// are changed since the changes are saved.
+#[derive(Parser)]
+#[command(author, version)]
+pub struct Apply {
+    /// Path to the saved changes
+    #[arg()]
+    plan: PathBuf,
+
+    /// Path to project dir. by default CWD or parents of CWD will be used
+    #[arg(short = 'p', long = "project")]
+    project: Option<Box<Path>>,
+    #[command(flatten)]
+    env_args: EnvArgs,
+
+    /// skip confirm
+    #[arg(short, long)]
+    yes: bool,
+    #[command(flatten)]
+    overwrite: OverwriteArgs,
+}
+
+impl Apply {
+    pub async fn run(self) {
+        let env = load_env(&self.env_args).await;
+        let mut unity = load_unity(self.project).await;
+
+        let json = tokio::fs::read(&self.plan)
+            .await
+            .exit_context("reading saved changes");
+        let saved = serde_json::from_slice::<SavedPendingChanges>(&json)
+            .exit_context("parsing saved changes");
+
+        let changes = unity
+            .restore_pending_changes(&env, &saved)
+            .exit_context("verifying saved changes");
+
+        let planned = print_prompt_install(&changes);
+
+        prompt_install(self.yes);
+        self.overwrite.check_modified(&unity, &env, &changes).await;
+
+        unity
+            .apply_pending_changes(&env, changes)
+            .await
+            .exit_context("applying changes");
+
+        save_unity(&mut unity).await;
+        crate::output::set_result(ChangesResult::new(planned, true));
+        update_project_last_modified(env, unity.project_dir()).await;
     }
 }
 
@@ -540,6 +1056,8 @@ pub struct Remove {
     project: Option<Box<Path>>,
     #[command(flatten)]
     env_args: EnvArgs,
+    #[command(flatten)]
+    plan_args: PlanArgs,
 
     /// skip confirm
     #[arg(short, long)]
@@ -556,7 +1074,11 @@ impl Remove {
             .await
             .exit_context("collecting packages to be removed");
 
-        print_prompt_install(&changes);
+        let planned = print_prompt_install(&changes);
+        self.plan_args.save_or_dry_run(&unity, &changes).await;
+        (self.plan_args.overwrite)
+            .check_modified(&unity, &env, &changes)
+            .await;
 
         let confirm =
             changes.package_changes().len() >= self.names.len() || !changes.conflicts().is_empty();
@@ -571,6 +1093,7 @@ impl Remove {
             .exit_context("removing packages");
 
         save_unity(&mut unity).await;
+        crate::output::set_result(ChangesResult::new(planned, true));
         update_project_last_modified(env, unity.project_dir()).await;
     }
 }
@@ -596,8 +1119,15 @@ pub struct Outdated {
     /// Include prerelease
     #[arg(long = "prerelease")]
     prerelease: bool,
+    /// Show the outdated packages in all projects registered to VCC instead of one project.
+    ///
+    /// This requires the experimental-vcc feature.
+    #[arg(long = "all-projects", conflicts_with_all = ["project", "json_format"])]
+    all_projects: bool,
 
     /// With this option, output is printed in json format
+    ///
+    /// Version 2 lists the deprecated packages in addition to the outdated packages.
     #[arg(long = "json-format")]
     json_format: Option<NonZeroU32>,
 
@@ -607,67 +1137,211 @@ pub struct Outdated {
 
 impl Outdated {
     pub async fn run(self) {
+        if self.all_projects {
+            #[cfg(feature = "experimental-vcc")]
+            return vcc::outdated_all_projects(&self.env_args, self.prerelease).await;
+            #[cfg(not(feature = "experimental-vcc"))]
+            exit_with!("--all-projects requires experimental-vcc feature");
+        }
+
         let env = load_env(&self.env_args).await;
         let unity = load_unity(self.project).await;
 
-        let mut outdated_packages = HashMap::new();
-
         let selector = VersionSelector::latest_for(unity.unity_version(), self.prerelease);
 
+        // versions not allowed by other packages cannot be upgraded to
+        let allowed_by_dependents = |name: &str, version: &Version| {
+            (unity.all_packages())
+                .filter_map(|package| package.dependencies().get(name))
+                .all(|range| range.matches(version))
+        };
+
+        let deprecated = (unity.deprecated_packages(&env).into_iter())
+            .map(|x| (x.name(), x.deprecation()))
+            .collect::<HashMap<_, _>>();
+
+        let mut outdated_packages = vec![];
+        let mut deprecated_packages = vec![];
+
         for locked in unity.locked_packages() {
-            match env.find_package_by_name(locked.name(), selector) {
-                None => log::error!("latest version for package {} not found.", locked.name()),
-                // if found version is newer: add to outdated
-                Some(pkg) if locked.version() < pkg.version() => {
-                    outdated_packages.insert(pkg.name(), (pkg, locked.version()));
+            let latest = match env.find_package_by_name(locked.name(), selector) {
+                None => {
+                    log::error!("latest version for package {} not found.", locked.name());
+                    None
                 }
-                Some(_) => (),
+                // if found version is newer: add to outdated
+                Some(pkg) if locked.version() < pkg.version() => Some(pkg),
+                Some(_) => None,
+            };
+            let find_upgrade =
+                |level| unity.find_upgrade(&env, locked.name(), level, self.prerelease);
+            let [latest, minor, patch] = [
+                latest,
+                find_upgrade(UpgradeLevel::Minor),
+                find_upgrade(UpgradeLevel::Patch),
+            ]
+            .map(|found| found.filter(|x| allowed_by_dependents(locked.name(), x.version())));
+
+            let deprecation = deprecated.get(locked.name()).copied();
+            if let Some(deprecation) = deprecation {
+                deprecated_packages.push(DeprecatedInfo {
+                    package_name: locked.name(),
+                    installed_version: locked.version(),
+                    deprecated: DeprecationInfo::new(deprecation),
+                });
             }
+
+            let Some(newer) = latest.or(minor).or(patch) else {
+                continue;
+            };
+
+            outdated_packages.push(OutdatedInfo {
+                package_name: locked.name(),
+                installed_version: locked.version(),
+                newer_version: newer.version(),
+                latest_patch_version: patch.map(|x| x.version()),
+                latest_minor_version: minor.map(|x| x.version()),
+                latest_version: latest.map(|x| x.version()),
+                held: unity.is_held(locked.name()),
+                hold_reason: unity.hold_reason(locked.name()),
+                deprecated: deprecation.map(DeprecationInfo::new),
+            });
         }
 
-        for locked in unity.all_packages() {
-            for (name, range) in locked.dependencies() {
-                if let Some((outdated, _)) = outdated_packages.get(name.as_ref()) {
-                    if !range.matches(outdated.version()) {
-                        outdated_packages.remove(name.as_ref());
-                    }
-                }
-            }
+        outdated_packages.sort_by_key(|x| x.package_name);
+        deprecated_packages.sort_by_key(|x| x.package_name);
+
+        // since json format 2, deprecated packages are listed separately
+        let result = OutdatedResult {
+            outdated: outdated_packages,
+            deprecated: deprecated_packages,
+        };
+
+        if crate::output::is_json() {
+            return crate::output::set_result(result);
         }
 
         match self.json_format.map(|x| x.get()).unwrap_or(0) {
             0 => {
-                for (name, (found, installed)) in &outdated_packages {
+                fn or_none(version: Option<&Version>) -> String {
+                    version.map_or("-".to_owned(), ToString::to_string)
+                }
+
+                for info in &result.outdated {
+                    let held = match info.hold_reason {
+                        Some(reason) => format!(" (held: {reason})"),
+                        None if info.held => " (held)".to_owned(),
+                        None => String::new(),
+                    };
+                    let deprecated = match &info.deprecated {
+                        Some(deprecated) => format!(" ({deprecated})"),
+                        None => String::new(),
+                    };
+                    println!(
+                        "{}: installed: {}, patch: {}, minor: {}, latest: {}{}{}",
+                        info.package_name,
+                        info.installed_version,
+                        or_none(info.latest_patch_version),
+                        or_none(info.latest_minor_version),
+                        or_none(info.latest_version),
+                        held,
+                        deprecated
+                    );
+                }
+
+                // the deprecated packages not listed above
+                for info in (result.deprecated.iter()).filter(|deprecated| {
+                    !(result.outdated.iter()).any(|x| x.package_name == deprecated.package_name)
+                }) {
                     println!(
-                        "{}: installed: {}, found: {}",
-                        name,
-                        installed,
-                        &found.version()
+                        "{}: installed: {} ({})",
+                        info.package_name, info.installed_version, info.deprecated
                     );
                 }
             }
             1 => {
-                #[derive(Serialize)]
-                struct OutdatedInfo<'a> {
-                    package_name: &'a str,
-                    installed_version: &'a Version,
-                    newer_version: &'a Version,
-                }
-                let info = outdated_packages
-                    .into_iter()
-                    .map(|(package_name, (found, installed))| OutdatedInfo {
-                        package_name,
-                        installed_version: installed,
-                        newer_version: found.version(),
-                    })
-                    .collect::<Vec<_>>();
-                println!("{}", serde_json::to_string(&info).unwrap());
+                println!("{}", serde_json::to_string(&result.outdated).unwrap());
+            }
+            2 => {
+                println!("{}", serde_json::to_string(&result).unwrap());
             }
             v => exit_with!("unsupported json version: {v}"),
         }
     }
 }
 
+#[derive(Serialize)]
+struct OutdatedResult<'a> {
+    outdated: Vec<OutdatedInfo<'a>>,
+    deprecated: Vec<DeprecatedInfo<'a>>,
+}
+
+#[derive(Serialize)]
+struct OutdatedInfo<'a> {
+    package_name: &'a str,
+    installed_version: &'a Version,
+    newer_version: &'a Version,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    latest_patch_version: Option<&'a Version>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    latest_minor_version: Option<&'a Version>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    latest_version: Option<&'a Version>,
+    held: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hold_reason: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    deprecated: Option<DeprecationInfo<'a>>,
+}
+
+#[derive(Serialize)]
+struct DeprecatedInfo<'a> {
+    package_name: &'a str,
+    installed_version: &'a Version,
+    deprecated: DeprecationInfo<'a>,
+}
+
+/// The serializable form of [`PackageDeprecation`] shown in `outdated` and `info project`.
+#[derive(Serialize)]
+struct DeprecationInfo<'a> {
+    message: Option<&'a str>,
+    replaced_by: Option<ReplacementInfo<'a>>,
+}
+
+#[derive(Serialize)]
+struct ReplacementInfo<'a> {
+    name: &'a str,
+    range: Option<&'a VersionRange>,
+}
+
+impl<'a> DeprecationInfo<'a> {
+    fn new(deprecation: &'a PackageDeprecation) -> Self {
+        Self {
+            message: deprecation.message(),
+            replaced_by: deprecation.replaced_by().map(|x| ReplacementInfo {
+                name: x.name(),
+                range: x.range(),
+            }),
+        }
+    }
+}
+
+impl Display for DeprecationInfo<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("deprecated")?;
+        if let Some(message) = self.message {
+            write!(f, ": {message}")?;
+        }
+        if let Some(replaced_by) = &self.replaced_by {
+            write!(f, ", replaced by {}", replaced_by.name)?;
+            if let Some(range) = replaced_by.range {
+                write!(f, "@{range}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Upgrade specified package or all packages to latest or specified version.
 ///
 /// With install command, you'll add to dependencies. With upgrade command,
@@ -684,12 +1358,28 @@ pub struct Upgrade {
     /// Include prerelease
     #[arg(long = "prerelease")]
     prerelease: bool,
+    /// Upgrade the package even if the package is held with `vrc-get hold`
+    ///
+    /// Without package name, this allows upgrading held packages required by other packages.
+    #[arg(long = "ignore-hold")]
+    ignore_hold: bool,
+    /// Upgrade to the latest version with the same major and minor version
+    #[arg(long, group = "level", conflicts_with = "VERSION")]
+    patch: bool,
+    /// Upgrade to the latest version with the same major version
+    #[arg(long, group = "level", conflicts_with = "VERSION")]
+    minor: bool,
+    /// Upgrade to the latest version in the range of dependencies and ranges required by other packages
+    #[arg(long, group = "level", conflicts_with = "VERSION")]
+    compatible: bool,
 
     /// Path to project dir. by default CWD or parents of CWD will be used
     #[arg(short = 'p', long = "project")]
     project: Option<Box<Path>>,
     #[command(flatten)]
     env_args: EnvArgs,
+    #[command(flatten)]
+    plan_args: PlanArgs,
 
     /// skip confirm
     #[arg(short, long)]
@@ -697,26 +1387,71 @@ pub struct Upgrade {
 }
 
 impl Upgrade {
+    fn level(&self) -> UpgradeLevel {
+        if self.patch {
+            UpgradeLevel::Patch
+        } else if self.minor {
+            UpgradeLevel::Minor
+        } else if self.compatible {
+            UpgradeLevel::Compatible
+        } else {
+            UpgradeLevel::Latest
+        }
+    }
+
     pub async fn run(self) {
+        let level = self.level();
         let env = load_env(&self.env_args).await;
         let mut unity = load_unity(self.project).await;
 
         let updates = if let Some(name) = &self.name {
-            let version_selector = match self.version {
-                None => VersionSelector::latest_for(unity.unity_version(), self.prerelease),
-                Some(ref version) => VersionSelector::specific_version(version),
-            };
-            let package = get_package(&env, name, version_selector);
-
-            vec![package]
+            if unity.is_held(name) && !self.ignore_hold {
+                match unity.hold_reason(name) {
+                    Some(reason) => exit_with!(
+                        Conflict,
+                        "package {name} is held: {reason}. use --ignore-hold to upgrade it"
+                    ),
+                    None => exit_with!(
+                        Conflict,
+                        "package {name} is held. use --ignore-hold to upgrade it"
+                    ),
+                }
+            }
+            if let Some(version) = &self.version {
+                let version_selector = VersionSelector::specific_version(version);
+                vec![get_package(&env, name, version_selector)]
+            } else if level == UpgradeLevel::Latest {
+                let version_selector =
+                    VersionSelector::latest_for(unity.unity_version(), self.prerelease);
+                vec![get_package(&env, name, version_selector)]
+            } else {
+                (unity.find_upgrade(&env, name, level, self.prerelease))
+                    .into_iter()
+                    .collect()
+            }
         } else {
             let version_selector =
                 VersionSelector::latest_for(unity.unity_version(), self.prerelease);
 
-            unity
-                .locked_packages()
-                .map(|locked| get_package(&env, locked.name(), version_selector))
-                .collect()
+            let not_held = unity.locked_packages().filter(|locked| {
+                let held = unity.is_held(locked.name());
+                if held {
+                    human_println!("skipping held package {}", locked.name());
+                }
+                !held
+            });
+
+            if level == UpgradeLevel::Latest {
+                not_held
+                    .map(|locked| get_package(&env, locked.name(), version_selector))
+                    .collect()
+            } else {
+                not_held
+                    .filter_map(|locked| {
+                        unity.find_upgrade(&env, locked.name(), level, self.prerelease)
+                    })
+                    .collect()
+            }
         };
 
         let changes = unity
@@ -729,7 +1464,29 @@ impl Upgrade {
             .await
             .exit_context("collecting packages to be upgraded");
 
-        print_prompt_install(&changes);
+        if !self.ignore_hold {
+            let held = unity.held_changes(&changes);
+            if !held.is_empty() {
+                for package in &held {
+                    human_println!(
+                        "held package {} would be upgraded to {} since other packages require it",
+                        package.name(),
+                        package.version()
+                    );
+                }
+                exit_with!(
+                    Conflict,
+                    "upgrading held packages: {}. use --ignore-hold to upgrade them",
+                    held.iter().map(|x| x.name()).join(", ")
+                );
+            }
+        }
+
+        let planned = print_prompt_install(&changes);
+        self.plan_args.save_or_dry_run(&unity, &changes).await;
+        (self.plan_args.overwrite)
+            .check_modified(&unity, &env, &changes)
+            .await;
 
         let require_prompt = if let Some(name) = &self.name {
             require_prompt_for_install(&changes, name.as_str(), None)
@@ -753,10 +1510,11 @@ impl Upgrade {
             .exit_context("upgrading packages");
 
         for (name, version) in updates {
-            println!("upgraded {} to {}", name, version);
+            human_println!("upgraded {} to {}", name, version);
         }
 
         save_unity(&mut unity).await;
+        crate::output::set_result(ChangesResult::new(planned, true));
         update_project_last_modified(env, unity.project_dir()).await;
     }
 }
@@ -783,6 +1541,8 @@ pub struct Downgrade {
     project: Option<Box<Path>>,
     #[command(flatten)]
     env_args: EnvArgs,
+    #[command(flatten)]
+    plan_args: PlanArgs,
 
     /// skip confirm
     #[arg(short, long)]
@@ -810,7 +1570,11 @@ impl Downgrade {
             .await
             .exit_context("collecting packages to be upgraded");
 
-        print_prompt_install(&changes);
+        let planned = print_prompt_install(&changes);
+        self.plan_args.save_or_dry_run(&unity, &changes).await;
+        (self.plan_args.overwrite)
+            .check_modified(&unity, &env, &changes)
+            .await;
 
         if require_prompt_for_install(&changes, self.name.as_str(), None) {
             prompt_install(self.yes)
@@ -828,10 +1592,11 @@ impl Downgrade {
             .exit_context("upgrading packages");
 
         for (name, version) in downgrades {
-            println!("downgraded {} to {}", name, version);
+            human_println!("downgraded {} to {}", name, version);
         }
 
         save_unity(&mut unity).await;
+        crate::output::set_result(ChangesResult::new(planned, true));
         update_project_last_modified(env, unity.project_dir()).await;
     }
 }
@@ -843,9 +1608,17 @@ impl Downgrade {
 #[command(author, version)]
 pub struct Search {
     /// Name of Package
-    #[arg(required = true, name = "QUERY")]
+    #[arg(required_unless_present_any = ["keywords", "author"], name = "QUERY")]
     queries: Vec<String>,
 
+    /// Only packages with the keyword in `keywords`. can be specified multiple times
+    #[arg(long = "keyword", value_name = "KEYWORD")]
+    keywords: Vec<String>,
+
+    /// Only packages whose author name contains the text
+    #[arg(long)]
+    author: Option<String>,
+
     #[command(flatten)]
     env_args: EnvArgs,
 }
@@ -854,30 +1627,15 @@ impl Search {
     pub async fn run(self) {
         let env = load_env(&self.env_args).await;
 
-        let mut queries = self.queries;
-        for query in &mut queries {
-            query.make_ascii_lowercase();
-        }
-
-        fn search_targets(pkg: &PackageManifest) -> Vec<String> {
-            let mut sources = Vec::with_capacity(3);
-
-            sources.push(pkg.name().to_ascii_lowercase());
-            sources.extend(pkg.display_name().map(|x| x.to_ascii_lowercase()));
-            sources.extend(pkg.description().map(|x| x.to_ascii_lowercase()));
-
-            sources
-        }
-
         let found_packages =
-            env.find_whole_all_packages(VersionSelector::latest_for(None, true), |pkg| {
-                // filtering
-                let search_targets = search_targets(pkg);
+            search_packages(&env, &self.queries, &self.keywords, self.author.as_deref());
 
-                queries
-                    .iter()
-                    .all(|query| search_targets.iter().any(|x| x.contains(query)))
-            });
+        if crate::output::is_json() {
+            let result = (found_packages.iter())
+                .map(|x| SearchResult::new(*x))
+                .collect::<Vec<_>>();
+            return crate::output::set_result(result);
+        }
 
         if found_packages.is_empty() {
             println!("No matching package found!")
@@ -898,6 +1656,72 @@ impl Search {
     }
 }
 
+/// Finds the latest versions of packages that include all queries in either name, displayName, or description.
+fn search_packages<'env>(
+    env: &'env Environment,
+    queries: &[String],
+    keywords: &[String],
+    author: Option<&str>,
+) -> Vec<PackageInfo<'env>> {
+    let queries = (queries.iter())
+        .map(|x| x.to_ascii_lowercase())
+        .collect::<Vec<_>>();
+    let author = author.map(|x| x.to_ascii_lowercase());
+
+    fn search_targets(pkg: &PackageManifest) -> Vec<String> {
+        let mut sources = Vec::with_capacity(3 + pkg.keywords().len());
+
+        sources.push(pkg.name().to_ascii_lowercase());
+        sources.extend(pkg.display_name().map(|x| x.to_ascii_lowercase()));
+        sources.extend(pkg.description().map(|x| x.to_ascii_lowercase()));
+        sources.extend(pkg.keywords().iter().map(|x| x.to_ascii_lowercase()));
+
+        sources
+    }
+
+    env.find_whole_all_packages(VersionSelector::latest_for(None, true), |pkg| {
+        // filtering
+        let has_keywords = keywords
+            .iter()
+            .all(|keyword| (pkg.keywords().iter()).any(|x| x.eq_ignore_ascii_case(keyword)));
+        let has_author = author.as_ref().is_none_or(|author| {
+            (pkg.author()).is_some_and(|x| x.name().to_ascii_lowercase().contains(author))
+        });
+        if !has_keywords || !has_author {
+            return false;
+        }
+
+        let search_targets = search_targets(pkg);
+
+        queries
+            .iter()
+            .all(|query| search_targets.iter().any(|x| x.contains(query)))
+    })
+}
+
+#[derive(Serialize)]
+struct SearchResult<'a> {
+    name: &'a str,
+    display_name: Option<&'a str>,
+    version: &'a Version,
+    description: Option<&'a str>,
+    keywords: &'a [Box<str>],
+    author: Option<&'a str>,
+}
+
+impl<'a> SearchResult<'a> {
+    fn new(package: PackageInfo<'a>) -> Self {
+        Self {
+            name: package.name(),
+            display_name: package.display_name(),
+            version: package.version(),
+            description: package.package_json().description(),
+            keywords: package.package_json().keywords(),
+            author: package.package_json().author().map(|x| x.name()),
+        }
+    }
+}
+
 /// Commands around repositories
 #[derive(Subcommand)]
 #[command(author, version)]
@@ -923,6 +1747,26 @@ impl RepoList {
     pub async fn run(self) {
         let env = load_env(&self.env_args).await;
 
+        if crate::output::is_json() {
+            #[derive(Serialize)]
+            struct RepoInfo<'a> {
+                id: Option<&'a str>,
+                name: Option<&'a str>,
+                url: Option<&'a str>,
+                local_path: &'a Path,
+            }
+
+            let result = (env.get_repos())
+                .map(|(local_path, repo)| RepoInfo {
+                    id: repo.id(),
+                    name: repo.name(),
+                    url: repo.url().map(Url::as_str),
+                    local_path,
+                })
+                .collect::<Vec<_>>();
+            return crate::output::set_result(result);
+        }
+
         for (local_path, repo) in env.get_repos() {
             println!(
                 "{}: {} (from {} at {})",
@@ -1024,7 +1868,7 @@ impl RepoAdd {
             let joined = cwd.join(&self.path_or_url);
             let normalized = normalize_path(&joined);
             if !normalized.exists() {
-                exit_with!("path not found: {}", normalized.display());
+                exit_with!(NotFound, "path not found: {}", normalized.display());
             }
             env.add_local_repo(normalized.as_ref(), self.name.as_deref())
                 .exit_context("adding repository")
@@ -1136,7 +1980,8 @@ impl RepoRemove {
 
         let count = env.remove_repo(|x| searcher.get(x) == Some(finder)).await;
 
-        println!("removed {} repositories with {}", count, searcher);
+        human_println!("removed {} repositories with {}", count, searcher);
+        crate::output::set_result(serde_json::json!({ "removed": count }));
 
         save_env(&mut env).await;
     }
@@ -1174,7 +2019,48 @@ pub struct RepoPackages {
 
 impl RepoPackages {
     pub async fn run(self) {
+        #[derive(Serialize)]
+        struct PackageVersions<'a> {
+            name: &'a str,
+            display_name: Option<&'a str>,
+            description: Option<&'a str>,
+            versions: Vec<PackageVersion<'a>>,
+        }
+
+        #[derive(Serialize)]
+        struct PackageVersion<'a> {
+            version: &'a Version,
+            url: Option<&'a str>,
+        }
+
+        fn repo_json(packages: &RemoteRepository) -> Vec<PackageVersions<'_>> {
+            let mut result = Vec::new();
+            for versions in packages.get_packages() {
+                if let Some(pkg) =
+                    versions.get_latest_may_yanked(VersionSelector::latest_for(None, true))
+                {
+                    result.push(PackageVersions {
+                        name: pkg.name(),
+                        display_name: pkg.display_name(),
+                        description: pkg.description(),
+                        versions: (versions.all_versions())
+                            .sorted_by_key(|pkg| pkg.version())
+                            .map(|pkg| PackageVersion {
+                                version: pkg.version(),
+                                url: pkg.url().map(Url::as_str),
+                            })
+                            .collect(),
+                    });
+                }
+            }
+            result
+        }
+
         fn print_repo(packages: &RemoteRepository) {
+            if crate::output::is_json() {
+                return crate::output::set_result(repo_json(packages));
+            }
+
             for versions in packages.get_packages() {
                 if let Some(pkg) =
                     versions.get_latest_may_yanked(VersionSelector::latest_for(None, true))
@@ -1203,7 +2089,7 @@ impl RepoPackages {
 
         if let Ok(url) = Url::parse(&self.name_or_url) {
             if self.env_args.offline {
-                exit_with!("remote repository specified but offline mode.");
+                exit_with!(Network, "remote repository specified but offline mode.");
             }
             let client = crate::create_client(self.env_args.offline).unwrap();
             let (repo, _) = RemoteRepository::download(&client, &url, &IndexMap::new())
@@ -1216,17 +2102,24 @@ impl RepoPackages {
 
             let some_name = Some(self.name_or_url.as_str());
             let mut found = false;
+            let mut json = Vec::new();
 
             for (_, repo) in env.get_repos() {
                 if repo.name() == some_name || repo.id() == some_name {
-                    print_repo(repo.repo());
+                    if crate::output::is_json() {
+                        json.extend(repo_json(repo.repo()));
+                    } else {
+                        print_repo(repo.repo());
+                    }
                     found = true;
                 }
             }
 
             if !found {
-                exit_with!("no repository named {} found!", self.name_or_url);
+                exit_with!(NotFound, "no repository named {} found!", self.name_or_url);
             }
+
+            crate::output::set_result(json);
         }
     }
 }
@@ -1249,12 +2142,7 @@ impl Completion {
             bin_name = bin_name[slash + 1..].to_owned();
         }
 
-        clap_complete::generate(
-            shell,
-            &mut Command::command(),
-            bin_name,
-            &mut std::io::stdout(),
-        );
+        clap_complete::generate(shell, &mut Cli::command(), bin_name, &mut std::io::stdout());
     }
 }
 
@@ -1269,10 +2157,11 @@ pub struct FakeVcc {
 #[cfg(not(feature = "experimental-vcc"))]
 impl FakeVcc {
     pub async fn run(self) {
-        eprintln!("vrc-get vcc is not enabled in this build of vrc-get.");
-        eprintln!("experimental features are disabled for prebuilt binaries.");
-        eprintln!("If you want to use vrc-get vcc command, please install vrc-get with ");
-        eprintln!("cargo install --features experimental-vcc vrc-get");
-        exit(1);
+        exit_with!(
+            "vrc-get vcc is not enabled in this build of vrc-get.\n\
+            experimental features are disabled for prebuilt binaries.\n\
+            If you want to use vrc-get vcc command, please install vrc-get with \n\
+            cargo install --features experimental-vcc vrc-get"
+        );
     }
 }
@@ -0,0 +1,251 @@
+use super::{load_env, load_unity, EnvArgs, Environment, ResultExt};
+use clap::Parser;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::path::Path;
+use vrc_get_vpm::unity_project::LockSnapshot;
+use vrc_get_vpm::version::{DependencyRange, UnityVersion, Version};
+use vrc_get_vpm::{PackageCollection, VersionSelector};
+
+/// Compare the packages of two projects, or the project and its state committed to git
+///
+/// The locked packages, the dependencies, and the unity version are compared.
+/// With `--git <rev>`, the project is compared with `vpm-manifest.json` and `ProjectVersion.txt`
+/// in the revision.
+#[derive(Parser)]
+#[command(author, version)]
+pub struct Diff {
+    /// The project to compare from.
+    /// With `--git`, the project to compare with the revision. by default CWD or parents of CWD will be used
+    #[arg(required_unless_present = "git")]
+    project_a: Option<Box<Path>>,
+    /// The project to compare to
+    #[arg(required_unless_present = "git", conflicts_with = "git")]
+    project_b: Option<Box<Path>>,
+    /// Compare the project with the state in the git revision
+    #[arg(long, value_name = "REV")]
+    git: Option<String>,
+
+    #[command(flatten)]
+    env_args: EnvArgs,
+}
+
+#[derive(Serialize)]
+struct DiffResult<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unity: Option<UnityChange>,
+    dependencies: Vec<DependencyChange<'a>>,
+    packages: Vec<PackageChange<'a>>,
+}
+
+#[derive(Serialize)]
+struct UnityChange {
+    before: Option<UnityVersion>,
+    after: Option<UnityVersion>,
+}
+
+#[derive(Serialize)]
+struct DependencyChange<'a> {
+    name: &'a str,
+    before: Option<&'a DependencyRange>,
+    after: Option<&'a DependencyRange>,
+}
+
+#[derive(Serialize)]
+struct PackageChange<'a> {
+    name: &'a str,
+    change: &'static str,
+    before: Option<&'a Version>,
+    after: Option<&'a Version>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    changelog_url: Option<&'a str>,
+}
+
+impl Diff {
+    pub async fn run(self) {
+        let env = load_env(&self.env_args).await;
+
+        let (before_unity, before, after_unity, after) = if let Some(rev) = &self.git {
+            let unity = load_unity(self.project_a).await;
+            let (before_unity, before) = load_git_revision(unity.project_dir(), rev).await;
+            (
+                before_unity,
+                before,
+                unity.unity_version(),
+                unity.lock_snapshot(),
+            )
+        } else {
+            let a = load_unity(self.project_a).await;
+            let b = load_unity(self.project_b).await;
+            (
+                a.unity_version(),
+                a.lock_snapshot(),
+                b.unity_version(),
+                b.lock_snapshot(),
+            )
+        };
+
+        let result = DiffResult {
+            unity: (before_unity != after_unity).then_some(UnityChange {
+                before: before_unity,
+                after: after_unity,
+            }),
+            dependencies: dependency_changes(&before, &after),
+            packages: package_changes(&env, &before, &after),
+        };
+
+        print_diff(&result);
+        crate::output::set_result(result);
+    }
+}
+
+async fn load_git_revision(project_dir: &Path, rev: &str) -> (Option<UnityVersion>, LockSnapshot) {
+    let manifest = git_show(project_dir, rev, "Packages/vpm-manifest.json")
+        .await
+        .unwrap_or_else(|message| {
+            exit_with!(NotFound, "reading vpm-manifest.json in {rev}: {message}")
+        });
+    let snapshot = LockSnapshot::from_vpm_manifest(&manifest)
+        .exit_context("parsing vpm-manifest.json in the revision");
+
+    // ProjectVersion.txt may not be committed
+    let unity = (git_show(project_dir, rev, "ProjectSettings/ProjectVersion.txt").await)
+        .ok()
+        .and_then(|text| UnityVersion::parse_project_version(&String::from_utf8_lossy(&text)));
+
+    (unity, snapshot)
+}
+
+/// Reads the file relative to the project in the git revision.
+async fn git_show(project_dir: &Path, rev: &str, path: &str) -> Result<Vec<u8>, String> {
+    let output = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(project_dir)
+        .arg("show")
+        // the revision given by the user must not be parsed as an option
+        .arg("--end-of-options")
+        // './' makes the path relative to the project instead of the root of the repository
+        .arg(format!("{rev}:./{path}"))
+        .output()
+        .await
+        .map_err(|e| format!("failed to run git: {e}"))?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_owned());
+    }
+
+    Ok(output.stdout)
+}
+
+fn dependency_changes<'a>(
+    before: &'a LockSnapshot,
+    after: &'a LockSnapshot,
+) -> Vec<DependencyChange<'a>> {
+    let names = (before.dependencies().chain(after.dependencies()))
+        .map(|(name, _)| name)
+        .collect::<BTreeSet<_>>();
+
+    names
+        .into_iter()
+        .map(|name| DependencyChange {
+            name,
+            before: before.get_dependency(name),
+            after: after.get_dependency(name),
+        })
+        .filter(|change| change.before != change.after)
+        .collect()
+}
+
+fn package_changes<'a>(
+    env: &'a Environment,
+    before: &'a LockSnapshot,
+    after: &'a LockSnapshot,
+) -> Vec<PackageChange<'a>> {
+    let names = (before.locked().chain(after.locked()))
+        .map(|(name, _)| name)
+        .collect::<BTreeSet<_>>();
+
+    let mut changes = vec![];
+    for name in names {
+        let before = before.get_locked(name);
+        let after = after.get_locked(name);
+        let change = match (before, after) {
+            (None, Some(_)) => "added",
+            (Some(_), None) => "removed",
+            (Some(before), Some(after)) if before < after => "upgraded",
+            (Some(before), Some(after)) if before > after => "downgraded",
+            _ => continue,
+        };
+
+        // the changelog of the new version tells what is changed
+        let changelog_url = after
+            .and_then(|version| {
+                env.find_package_by_name(name, VersionSelector::specific_version(version))
+            })
+            .and_then(|package| package.package_json().changelog_url())
+            .map(|url| url.as_str());
+
+        changes.push(PackageChange {
+            name,
+            change,
+            before,
+            after,
+            changelog_url,
+        });
+    }
+    changes
+}
+
+fn print_diff(result: &DiffResult) {
+    fn or_none(value: Option<impl ToString>) -> String {
+        value.map_or("none".to_owned(), |x| x.to_string())
+    }
+
+    if result.unity.is_none() && result.dependencies.is_empty() && result.packages.is_empty() {
+        human_println!("no differences");
+        return;
+    }
+
+    if let Some(unity) = &result.unity {
+        human_println!(
+            "unity: {} -> {}",
+            or_none(unity.before),
+            or_none(unity.after)
+        );
+    }
+
+    if !result.dependencies.is_empty() {
+        human_println!("dependencies:");
+        for change in &result.dependencies {
+            match (change.before, change.after) {
+                (None, Some(after)) => human_println!("  + {} {after}", change.name),
+                (Some(before), None) => human_println!("  - {} {before}", change.name),
+                (before, after) => human_println!(
+                    "  ~ {}: {} -> {}",
+                    change.name,
+                    or_none(before),
+                    or_none(after)
+                ),
+            }
+        }
+    }
+
+    if !result.packages.is_empty() {
+        human_println!("packages:");
+        for change in &result.packages {
+            let versions = match (change.before, change.after) {
+                (Some(before), Some(after)) => format!("{before} -> {after}"),
+                (before, after) => or_none(after.or(before)),
+            };
+            let changelog = match change.changelog_url {
+                Some(url) => format!(" (changelog: {url})"),
+                None => String::new(),
+            };
+            human_println!(
+                "  {:10} {} {versions}{changelog}",
+                change.change,
+                change.name
+            );
+        }
+    }
+}
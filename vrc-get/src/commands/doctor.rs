@@ -0,0 +1,152 @@
+use super::{
+    load_env, load_unity, print_prompt_install, prompt_install, save_unity,
+    update_project_last_modified, ChangesResult, EnvArgs, OverwriteArgs, PlannedChanges, ResultExt,
+};
+use clap::Parser;
+use serde::Serialize;
+use std::path::Path;
+use vrc_get_vpm::unity_project::{PendingProjectChanges, ProjectDiagnostic};
+
+/// Check the project for problems
+///
+/// This checks missing or broken packages, packages not locked, remaining legacy assets,
+/// duplicated GUIDs, unsatisfied dependencies, and packages not compatible with the unity version.
+/// With `--fix`, missing or broken packages are reinstalled and legacy assets are removed.
+#[derive(Parser)]
+#[command(author, version)]
+pub struct Doctor {
+    /// Path to project dir. by default CWD or parents of CWD will be used
+    #[arg(short = 'p', long = "project")]
+    project: Option<Box<Path>>,
+    #[command(flatten)]
+    env_args: EnvArgs,
+
+    /// Fix the problems can be fixed safely
+    #[arg(long)]
+    fix: bool,
+
+    /// skip confirm
+    #[arg(short, long, requires = "fix")]
+    yes: bool,
+    #[command(flatten)]
+    overwrite: OverwriteArgs,
+}
+
+#[derive(Serialize)]
+struct DoctorResult {
+    diagnostics: Vec<Diagnostic>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fix: Option<ChangesResult>,
+}
+
+#[derive(Serialize)]
+struct Diagnostic {
+    kind: &'static str,
+    message: String,
+    fixable: bool,
+    fixed: bool,
+}
+
+fn kind_name(diagnostic: &ProjectDiagnostic) -> &'static str {
+    match diagnostic {
+        ProjectDiagnostic::MissingPackage { .. } => "missing_package",
+        ProjectDiagnostic::VersionMismatch { .. } => "version_mismatch",
+        ProjectDiagnostic::UnlockedPackage { .. } => "unlocked_package",
+        ProjectDiagnostic::LegacyAsset { .. } => "legacy_asset",
+        ProjectDiagnostic::DuplicateGuid { .. } => "duplicate_guid",
+        ProjectDiagnostic::UnsatisfiedDependency { .. } => "unsatisfied_dependency",
+        ProjectDiagnostic::IncompatibleUnity { .. } => "incompatible_unity",
+        _ => "unknown",
+    }
+}
+
+fn is_fixed_by(diagnostic: &ProjectDiagnostic, changes: &PendingProjectChanges) -> bool {
+    match diagnostic {
+        ProjectDiagnostic::MissingPackage { name, .. }
+        | ProjectDiagnostic::VersionMismatch { name, .. } => {
+            changes.package_changes().contains_key(name)
+        }
+        ProjectDiagnostic::LegacyAsset { path, .. } => (changes.remove_legacy_files().iter())
+            .chain(changes.remove_legacy_folders())
+            .any(|(x, _)| x == path),
+        ProjectDiagnostic::UnlockedPackage { .. }
+        | ProjectDiagnostic::DuplicateGuid { .. }
+        | ProjectDiagnostic::UnsatisfiedDependency { .. }
+        | ProjectDiagnostic::IncompatibleUnity { .. } => false,
+        _ => false,
+    }
+}
+
+impl Doctor {
+    pub async fn run(self) {
+        let env = load_env(&self.env_args).await;
+        let mut unity = load_unity(self.project).await;
+
+        let diagnostics = unity.diagnose(&env).await;
+
+        let mut result = DoctorResult {
+            diagnostics: (diagnostics.iter())
+                .map(|x| Diagnostic {
+                    kind: kind_name(x),
+                    message: x.to_string(),
+                    fixable: x.is_fixable(),
+                    fixed: false,
+                })
+                .collect(),
+            fix: None,
+        };
+
+        for diagnostic in &result.diagnostics {
+            if diagnostic.fixable {
+                human_println!("{} (fixable)", diagnostic.message);
+            } else {
+                human_println!("{}", diagnostic.message);
+            }
+        }
+
+        if diagnostics.is_empty() {
+            human_println!("no problems found");
+            crate::output::set_result(&result);
+            return;
+        }
+
+        if self.fix && diagnostics.iter().any(ProjectDiagnostic::is_fixable) {
+            let changes = unity.doctor_fix_request(&env).await;
+
+            let planned = if (changes.package_changes().is_empty())
+                && changes.remove_legacy_files().is_empty()
+                && changes.remove_legacy_folders().is_empty()
+            {
+                PlannedChanges::default()
+            } else {
+                let planned = print_prompt_install(&changes);
+                crate::output::set_result(&result);
+                prompt_install(self.yes);
+                self.overwrite.check_modified(&unity, &env, &changes).await;
+                planned
+            };
+
+            for (diagnostic, output) in diagnostics.iter().zip(&mut result.diagnostics) {
+                output.fixed = is_fixed_by(diagnostic, &changes);
+            }
+
+            unity
+                .apply_pending_changes(&env, changes)
+                .await
+                .exit_context("fixing project");
+
+            save_unity(&mut unity).await;
+            update_project_last_modified(env, unity.project_dir()).await;
+
+            result.fix = Some(ChangesResult::new(planned, true));
+        }
+
+        let remaining = result.diagnostics.iter().filter(|x| !x.fixed).count();
+        crate::output::set_result(&result);
+
+        if remaining != 0 {
+            exit_with!("{remaining} problem(s) found");
+        }
+        human_println!("fixed all problems");
+    }
+}
@@ -0,0 +1,251 @@
+use super::load_unity;
+use clap::{Parser, ValueEnum};
+use serde::Serialize;
+use std::fmt::Write;
+use std::path::Path;
+use vrc_get_vpm::unity_project::{DependencyGraph, GraphNode, GraphNodeKind};
+use vrc_get_vpm::version::{Version, VersionRange};
+
+/// Show the dependency graph of the packages in the project
+///
+/// The graph is created from `dependencies` and `locked` in `vpm-manifest.json`.
+/// Dependencies of the project are marked as root, dependencies not satisfied by the locked versions
+/// are marked as conflict, and packages replaced by `legacyPackages` of other packages are marked as legacy.
+#[derive(Parser)]
+#[command(author, version)]
+pub struct Graph {
+    /// Path to project dir. by default CWD or parents of CWD will be used
+    #[arg(short = 'p', long = "project")]
+    project: Option<Box<Path>>,
+
+    /// The format of the graph
+    #[arg(long, value_enum, default_value_t)]
+    format: GraphFormat,
+
+    /// Include the packages in `Packages/manifest.json` of UPM
+    #[arg(long = "include-upm")]
+    include_upm: bool,
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, ValueEnum)]
+enum GraphFormat {
+    /// Graphviz dot language
+    #[default]
+    Dot,
+    /// Mermaid flowchart
+    Mermaid,
+    /// JSON with nodes and edges
+    Json,
+}
+
+#[derive(Serialize)]
+struct GraphResult<'a> {
+    nodes: Vec<NodeInfo<'a>>,
+    edges: Vec<EdgeInfo<'a>>,
+}
+
+#[derive(Serialize)]
+struct NodeInfo<'a> {
+    name: &'a str,
+    version: Option<&'a Version>,
+    kind: &'static str,
+    root: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    replaced_by: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct EdgeInfo<'a> {
+    from: Option<&'a str>,
+    to: &'a str,
+    range: &'a VersionRange,
+    conflict: bool,
+}
+
+impl Graph {
+    pub async fn run(self) {
+        let unity = load_unity(self.project).await;
+
+        let graph = unity.dependency_graph(self.include_upm);
+
+        let result = GraphResult {
+            nodes: (graph.nodes().iter())
+                .map(|node| NodeInfo {
+                    name: node.name(),
+                    version: node.version(),
+                    kind: kind_name(node.kind()),
+                    root: node.is_root(),
+                    replaced_by: node.replaced_by(),
+                })
+                .collect(),
+            edges: (graph.edges().iter())
+                .map(|edge| EdgeInfo {
+                    from: edge.from(),
+                    to: edge.to(),
+                    range: edge.range(),
+                    conflict: edge.is_conflict(),
+                })
+                .collect(),
+        };
+
+        match self.format {
+            GraphFormat::Dot => human_println!("{}", render_dot(&graph)),
+            GraphFormat::Mermaid => human_println!("{}", render_mermaid(&graph)),
+            GraphFormat::Json => {
+                if !crate::output::is_json() {
+                    println!("{}", serde_json::to_string_pretty(&result).unwrap());
+                }
+            }
+        }
+
+        crate::output::set_result(result);
+    }
+}
+
+fn kind_name(kind: GraphNodeKind) -> &'static str {
+    match kind {
+        GraphNodeKind::Locked => "locked",
+        GraphNodeKind::Unlocked => "unlocked",
+        GraphNodeKind::Missing => "missing",
+        GraphNodeKind::Upm => "upm",
+        _ => "unknown",
+    }
+}
+
+/// The lines of the label of the node.
+fn node_label(node: &GraphNode) -> Vec<String> {
+    let mut lines = vec![node.name().to_owned()];
+    match node.version() {
+        Some(version) => lines.push(version.to_string()),
+        None if node.kind() == GraphNodeKind::Missing => lines.push("(missing)".to_owned()),
+        None => {}
+    }
+    if node.kind() == GraphNodeKind::Upm {
+        lines.push("(upm)".to_owned());
+    }
+    if let Some(replaced_by) = node.replaced_by() {
+        lines.push(format!("(legacy, replaced by {replaced_by})"));
+    }
+    lines
+}
+
+const PROJECT_NODE: &str = "(project)";
+
+fn render_dot(graph: &DependencyGraph) -> String {
+    fn escape(value: &str) -> String {
+        value.replace('\\', "\\\\").replace('"', "\\\"")
+    }
+
+    fn quote(value: &str) -> String {
+        format!("\"{}\"", escape(value))
+    }
+
+    let mut dot = String::new();
+    writeln!(dot, "digraph dependencies {{").unwrap();
+    writeln!(dot, "  rankdir=LR;").unwrap();
+    writeln!(dot, "  {} [shape=box];", quote(PROJECT_NODE)).unwrap();
+
+    for node in graph.nodes() {
+        let label = node_label(node)
+            .iter()
+            .map(|x| escape(x))
+            .collect::<Vec<_>>();
+        let mut attributes = vec![format!("label=\"{}\"", label.join("\\n"))];
+        if node.is_root() {
+            attributes.push("penwidth=2".to_owned());
+        }
+        if node.kind() == GraphNodeKind::Upm {
+            attributes.push("shape=box".to_owned());
+        }
+        if node.kind() == GraphNodeKind::Missing {
+            attributes.push("color=red".to_owned());
+        }
+        if node.kind() == GraphNodeKind::Missing || node.replaced_by().is_some() {
+            attributes.push("style=dashed".to_owned());
+        }
+        writeln!(dot, "  {} [{}];", quote(node.name()), attributes.join(", ")).unwrap();
+    }
+
+    for edge in graph.edges() {
+        let from = edge.from().unwrap_or(PROJECT_NODE);
+        let mut attributes = vec![format!("label={}", quote(&edge.range().to_string()))];
+        if edge.is_conflict() {
+            attributes.push("color=red".to_owned());
+            attributes.push("fontcolor=red".to_owned());
+        }
+        writeln!(
+            dot,
+            "  {} -> {} [{}];",
+            quote(from),
+            quote(edge.to()),
+            attributes.join(", ")
+        )
+        .unwrap();
+    }
+
+    dot.push('}');
+    dot
+}
+
+fn render_mermaid(graph: &DependencyGraph) -> String {
+    fn quote(value: &str) -> String {
+        format!("\"{}\"", value.replace('"', "#quot;"))
+    }
+
+    // mermaid ids cannot contain some characters in package names so we use the index
+    let id = |name: &str| match graph.nodes().iter().position(|x| x.name() == name) {
+        Some(index) => format!("n{index}"),
+        None => "project".to_owned(),
+    };
+
+    let mut mermaid = String::new();
+    writeln!(mermaid, "flowchart LR").unwrap();
+    writeln!(mermaid, "  project[{}]", quote(PROJECT_NODE)).unwrap();
+
+    for node in graph.nodes() {
+        let label = quote(&node_label(node).join("<br>"));
+        if node.kind() == GraphNodeKind::Upm {
+            writeln!(mermaid, "  {}[{label}]", id(node.name())).unwrap();
+        } else {
+            writeln!(mermaid, "  {}({label})", id(node.name())).unwrap();
+        }
+    }
+
+    let mut conflicts = vec![];
+    for (index, edge) in graph.edges().iter().enumerate() {
+        let from = edge.from().map_or("project".to_owned(), id);
+        let label = quote(&edge.range().to_string());
+        writeln!(mermaid, "  {from} -->|{label}| {}", id(edge.to())).unwrap();
+        if edge.is_conflict() {
+            conflicts.push(index.to_string());
+        }
+    }
+
+    writeln!(mermaid, "  classDef root stroke-width:3px").unwrap();
+    writeln!(mermaid, "  classDef missing stroke:red,stroke-dasharray:4").unwrap();
+    writeln!(mermaid, "  classDef legacy stroke-dasharray:4").unwrap();
+    for (class, filter) in [
+        ("root", (|x| x.is_root()) as fn(&GraphNode) -> bool),
+        ("missing", |x| x.kind() == GraphNodeKind::Missing),
+        ("legacy", |x| x.replaced_by().is_some()),
+    ] {
+        let ids = (graph.nodes().iter())
+            .filter(|x| filter(x))
+            .map(|x| id(x.name()))
+            .collect::<Vec<_>>();
+        if !ids.is_empty() {
+            writeln!(mermaid, "  class {} {class}", ids.join(",")).unwrap();
+        }
+    }
+    if !conflicts.is_empty() {
+        writeln!(
+            mermaid,
+            "  linkStyle {} stroke:red,color:red",
+            conflicts.join(",")
+        )
+        .unwrap();
+    }
+
+    mermaid.pop();
+    mermaid
+}
@@ -0,0 +1,193 @@
+use super::{
+    load_env, load_unity, print_prompt_install, prompt_install, save_unity,
+    update_project_last_modified, ChangesResult, EnvArgs, PlanArgs, ResultExt,
+};
+use clap::Parser;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::path::Path;
+use vrc_get_vpm::unity_project::LockSnapshot;
+use vrc_get_vpm::version::Version;
+
+/// Show the history of the changes to the packages in the project
+///
+/// The history is recorded in Packages/vrc-get-history every time vrc-get changes
+/// the locked packages. The latest entry is numbered 1.
+#[derive(Parser)]
+#[command(author, version)]
+pub struct History {
+    /// Path to project dir. by default CWD or parents of CWD will be used
+    #[arg(short = 'p', long = "project")]
+    project: Option<Box<Path>>,
+}
+
+#[derive(Serialize)]
+struct HistoryResult<'a> {
+    entries: Vec<EntryInfo<'a>>,
+}
+
+#[derive(Serialize)]
+struct EntryInfo<'a> {
+    number: usize,
+    time: u64,
+    reason: Option<&'a str>,
+    changes: Vec<LockChange<'a>>,
+}
+
+#[derive(Serialize)]
+struct LockChange<'a> {
+    name: &'a str,
+    before: Option<&'a Version>,
+    after: Option<&'a Version>,
+}
+
+impl History {
+    pub async fn run(self) {
+        let unity = load_unity(self.project).await;
+
+        let history = unity.history().await.exit_context("reading history");
+
+        let result = HistoryResult {
+            entries: (history.iter().rev().enumerate())
+                .map(|(index, entry)| EntryInfo {
+                    number: index + 1,
+                    time: entry.time_millis(),
+                    reason: entry.reason(),
+                    changes: lock_changes(entry.before(), entry.after()),
+                })
+                .collect(),
+        };
+
+        if result.entries.is_empty() {
+            human_println!("no history recorded");
+        }
+
+        for entry in &result.entries {
+            human_println!(
+                "{}: {} {}",
+                entry.number,
+                format_time(entry.time),
+                entry.reason.unwrap_or("(unknown)")
+            );
+            for change in &entry.changes {
+                match (change.before, change.after) {
+                    (None, Some(after)) => human_println!("  + {}@{after}", change.name),
+                    (Some(before), None) => human_println!("  - {}@{before}", change.name),
+                    (Some(before), Some(after)) => {
+                        human_println!("  ~ {}: {before} -> {after}", change.name)
+                    }
+                    (None, None) => {}
+                }
+            }
+        }
+
+        crate::output::set_result(&result);
+    }
+}
+
+/// Restore the locked packages before the changes in the history
+///
+/// `vrc-get undo` restores the state before the latest change, and `vrc-get undo 3` restores
+/// the state before the last three changes. Undoing is recorded as a new history entry
+/// so it can be undone again.
+#[derive(Parser)]
+#[command(author, version)]
+pub struct Undo {
+    /// The number of the entry in `vrc-get history` to restore the state before
+    #[arg(default_value_t = 1)]
+    number: usize,
+
+    /// Path to project dir. by default CWD or parents of CWD will be used
+    #[arg(short = 'p', long = "project")]
+    project: Option<Box<Path>>,
+    #[command(flatten)]
+    env_args: EnvArgs,
+    #[command(flatten)]
+    plan_args: PlanArgs,
+
+    /// skip confirm
+    #[arg(short, long)]
+    yes: bool,
+}
+
+impl Undo {
+    pub async fn run(self) {
+        let env = load_env(&self.env_args).await;
+        let mut unity = load_unity(self.project).await;
+
+        let history = unity.history().await.exit_context("reading history");
+        let Some(entry) =
+            (self.number.checked_sub(1)).and_then(|index| history.iter().rev().nth(index))
+        else {
+            exit_with!(NotFound, "history entry {} not found", self.number);
+        };
+
+        human_println!(
+            "restoring the state before {} {}",
+            format_time(entry.time_millis()),
+            entry.reason().unwrap_or("(unknown)")
+        );
+
+        let changes = unity
+            .restore_lock_request(&env, entry.before())
+            .await
+            .exit_context("collecting packages to be restored");
+
+        let planned = print_prompt_install(&changes);
+        self.plan_args.save_or_dry_run(&unity, &changes).await;
+        (self.plan_args.overwrite)
+            .check_modified(&unity, &env, &changes)
+            .await;
+
+        prompt_install(self.yes);
+
+        unity
+            .apply_pending_changes(&env, changes)
+            .await
+            .exit_context("restoring packages");
+
+        save_unity(&mut unity).await;
+        crate::output::set_result(ChangesResult::new(planned, true));
+        update_project_last_modified(env, unity.project_dir()).await;
+    }
+}
+
+fn lock_changes<'a>(before: &'a LockSnapshot, after: &'a LockSnapshot) -> Vec<LockChange<'a>> {
+    let names = (before.locked().chain(after.locked()))
+        .map(|(name, _)| name)
+        .collect::<BTreeSet<_>>();
+
+    names
+        .into_iter()
+        .map(|name| LockChange {
+            name,
+            before: before.get_locked(name),
+            after: after.get_locked(name),
+        })
+        .filter(|change| change.before != change.after)
+        .collect()
+}
+
+/// Formats the milliseconds since the unix epoch as `YYYY-MM-DD hh:mm:ss` in UTC.
+fn format_time(millis: u64) -> String {
+    let (year, month, day, hour, minute, second) = date_time(millis / 1000);
+    format!("{year:04}-{month:02}-{day:02} {hour:02}:{minute:02}:{second:02} UTC")
+}
+
+/// Converts the seconds since the unix epoch to year, month, day, hour, minute, and second in UTC.
+pub(super) fn date_time(secs: u64) -> (i64, i64, i64, u64, u64, u64) {
+    let (days, secs) = ((secs / 86400) as i64, secs % 86400);
+
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+
+    (year, month, day, secs / 3600, secs / 60 % 60, secs % 60)
+}
@@ -0,0 +1,64 @@
+use super::{load_unity, save_unity};
+use clap::Parser;
+use std::path::Path;
+
+/// Hold the package at the current version
+///
+/// Held packages are skipped by `vrc-get upgrade` without package name and shown as held in
+/// `vrc-get outdated`. Upgrading a held package explicitly requires `--ignore-hold`.
+/// Holds are stored in Packages/vrc-get-project.json.
+#[derive(Parser)]
+#[command(author, version)]
+pub struct Hold {
+    /// Name of the package
+    #[arg()]
+    name: String,
+    /// The reason to hold the package, shown in `vrc-get outdated`
+    #[arg(long)]
+    reason: Option<String>,
+
+    /// Path to project dir. by default CWD or parents of CWD will be used
+    #[arg(short = 'p', long = "project")]
+    project: Option<Box<Path>>,
+}
+
+impl Hold {
+    pub async fn run(self) {
+        let mut unity = load_unity(self.project).await;
+
+        let Some(locked) = unity.get_locked(&self.name) else {
+            exit_with!(NotFound, "package {} is not installed", self.name);
+        };
+        human_println!("holding {} at {}", self.name, locked.version());
+
+        unity.hold_package(&self.name, self.reason.map(Into::into));
+
+        save_unity(&mut unity).await;
+    }
+}
+
+/// Remove the hold of the package
+#[derive(Parser)]
+#[command(author, version)]
+pub struct Unhold {
+    /// Name of the package
+    #[arg()]
+    name: String,
+
+    /// Path to project dir. by default CWD or parents of CWD will be used
+    #[arg(short = 'p', long = "project")]
+    project: Option<Box<Path>>,
+}
+
+impl Unhold {
+    pub async fn run(self) {
+        let mut unity = load_unity(self.project).await;
+
+        if !unity.unhold_package(&self.name) {
+            exit_with!(NotFound, "package {} is not held", self.name);
+        }
+        human_println!("removed hold of {}", self.name);
+
+        save_unity(&mut unity).await;
+    }
+}
@@ -1,11 +1,12 @@
 use super::UnityProject;
-use crate::commands::{load_env, load_unity};
+use crate::commands::{load_env, load_unity, DeprecationInfo};
 use clap::{Parser, Subcommand};
 use itertools::Itertools;
 use serde::Serialize;
 use std::collections::HashSet;
 use std::num::NonZeroU32;
 use std::path::Path;
+use vrc_get_vpm::unity_compatibility::maximum_unity;
 use vrc_get_vpm::version::{UnityVersion, Version, VersionRange};
 use vrc_get_vpm::PackageCollection;
 
@@ -39,7 +40,8 @@ impl Project {
     pub async fn run(self) {
         let unity = load_unity(self.project).await;
 
-        match self.json_format.map(|x| x.get()).unwrap_or_default() {
+        let default_format = if crate::output::is_json() { 1 } else { 0 };
+        match self.json_format.map(|x| x.get()).unwrap_or(default_format) {
             0 => {
                 Self::human_readable(&unity).await;
             }
@@ -60,15 +62,16 @@ impl Project {
         eprintln!();
         eprintln!("Locked Packages:");
         for locked in unity.locked_packages() {
-            if let Some(installed) = unity
-                .get_installed_package(locked.name())
-                .map(|x| x.version())
-            {
+            if let Some(installed) = unity.get_installed_package(locked.name()) {
+                let deprecated = match installed.deprecation() {
+                    Some(deprecation) => format!(" ({})", DeprecationInfo::new(deprecation)),
+                    None => String::new(),
+                };
                 eprintln!(
-                    "{package} version {version} with installed version {installed}",
+                    "{package} version {version} with installed version {installed}{deprecated}",
                     package = locked.name(),
                     version = locked.version(),
-                    installed = installed,
+                    installed = installed.version(),
                 );
             } else {
                 eprintln!(
@@ -93,75 +96,98 @@ impl Project {
     }
 
     pub async fn version1(unity: &UnityProject) {
-        #[derive(Serialize)]
-        struct Project<'a> {
-            unity_version: Option<UnityVersion>,
-            packages: &'a [PackageInfo<'a>],
-        }
+        let project = project_info_v1(unity);
 
-        #[derive(Serialize)]
-        struct PackageInfo<'a> {
-            name: &'a str,
-            installed: Option<&'a Version>,
-            locked: Option<&'a Version>,
-            requested: Vec<&'a VersionRange>,
+        if crate::output::is_json() {
+            crate::output::set_result(&project);
+        } else {
+            println!("{}", serde_json::to_string(&project).unwrap());
         }
+    }
+}
 
-        let mut packages = vec![];
+/// Creates the project information in the format of `--json-format 1`
+pub(super) fn project_info_v1(unity: &UnityProject) -> serde_json::Value {
+    #[derive(Serialize)]
+    struct Project<'a> {
+        unity_version: Option<UnityVersion>,
+        packages: &'a [PackageInfo<'a>],
+    }
 
-        for locked in unity.locked_packages() {
-            packages.push(PackageInfo {
-                name: locked.name(),
-                installed: unity
-                    .get_installed_package(locked.name())
-                    .map(|x| x.version()),
-                locked: Some(locked.version()),
-                requested: vec![], // TODO: add requests from locked packages
-            });
-        }
+    #[derive(Serialize)]
+    struct PackageInfo<'a> {
+        name: &'a str,
+        installed: Option<&'a Version>,
+        locked: Option<&'a Version>,
+        requested: Vec<&'a VersionRange>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        deprecated: Option<DeprecationInfo<'a>>,
+    }
 
-        for (package, installed) in unity.unlocked_packages() {
-            if let Some(installed) = installed {
-                packages.push(PackageInfo {
-                    name: package,
-                    installed: Some(installed.version()),
-                    locked: None,
-                    requested: vec![],
-                });
-            }
-        }
+    // the deprecation of the installed package.json
+    let deprecated = |name: &str| {
+        (unity.get_installed_package(name))
+            .and_then(|x| x.deprecation())
+            .map(DeprecationInfo::new)
+    };
 
-        let unlocked_names: HashSet<_> = unity
-            .unlocked_packages()
-            .iter()
-            .filter_map(|(_, pkg)| pkg.as_ref())
-            .map(|x| x.name())
-            .collect();
+    let mut packages = vec![];
+
+    for locked in unity.locked_packages() {
+        packages.push(PackageInfo {
+            name: locked.name(),
+            installed: unity
+                .get_installed_package(locked.name())
+                .map(|x| x.version()),
+            locked: Some(locked.version()),
+            requested: vec![], // TODO: add requests from locked packages
+            deprecated: deprecated(locked.name()),
+        });
+    }
 
-        let unlocked_dependencies = unity
-            .unlocked_packages()
-            .iter()
-            .filter_map(|(_, pkg)| pkg.as_ref())
-            .flat_map(|pkg| pkg.vpm_dependencies())
-            .filter(|(k, _)| !unity.is_locked(k.as_ref()))
-            .filter(|(k, _)| !unlocked_names.contains(k.as_ref()))
-            .into_group_map();
-        for (package, requested) in unlocked_dependencies {
+    for (package, installed) in unity.unlocked_packages() {
+        if let Some(installed) = installed {
             packages.push(PackageInfo {
                 name: package,
-                installed: None,
+                installed: Some(installed.version()),
                 locked: None,
-                requested,
+                requested: vec![],
+                deprecated: installed.deprecation().map(DeprecationInfo::new),
             });
         }
+    }
 
-        let project = Project {
-            unity_version: unity.unity_version(),
-            packages: packages.as_slice(),
-        };
+    let unlocked_names: HashSet<_> = unity
+        .unlocked_packages()
+        .iter()
+        .filter_map(|(_, pkg)| pkg.as_ref())
+        .map(|x| x.name())
+        .collect();
 
-        println!("{}", serde_json::to_string(&project).unwrap());
+    let unlocked_dependencies = unity
+        .unlocked_packages()
+        .iter()
+        .filter_map(|(_, pkg)| pkg.as_ref())
+        .flat_map(|pkg| pkg.vpm_dependencies())
+        .filter(|(k, _)| !unity.is_locked(k.as_ref()))
+        .filter(|(k, _)| !unlocked_names.contains(k.as_ref()))
+        .into_group_map();
+    for (package, requested) in unlocked_dependencies {
+        packages.push(PackageInfo {
+            name: package,
+            installed: None,
+            locked: None,
+            requested,
+            deprecated: None,
+        });
     }
+
+    let project = Project {
+        unity_version: unity.unity_version(),
+        packages: packages.as_slice(),
+    };
+
+    serde_json::to_value(project).expect("serializing project info")
 }
 
 /// Show project information
@@ -183,6 +209,7 @@ impl Package {
         let env = load_env(&self.env_args).await;
 
         let format_version = match self.json_format.map(|x| x.get()).unwrap_or_default() {
+            0 if crate::output::is_json() => 1,
             0 => {
                 eprintln!("warning: no --json-format is specified! using lastest version 1");
                 1
@@ -195,10 +222,39 @@ impl Package {
 
         let versions: Vec<_> = env
             .find_packages(&self.package)
-            .map(|x| PackageVersionInfo {
-                version: x.version(),
-                // since 1.5.0
-                is_yanked: x.is_yanked(),
+            .map(|x| {
+                let json = x.package_json();
+                PackageVersionInfo {
+                    version: x.version(),
+                    // since 1.5.0
+                    is_yanked: x.is_yanked(),
+                    // since 1.8.0
+                    display_name: json.display_name(),
+                    description: json.description(),
+                    package_type: json.package_type(),
+                    keywords: json.keywords(),
+                    author: json.author().map(|author| PackageAuthorInfo {
+                        name: author.name(),
+                        email: author.email(),
+                        url: author.url(),
+                    }),
+                    license: json.license(),
+                    licenses_url: json.licenses_url().map(|x| x.as_str()),
+                    documentation_url: json.documentation_url().map(|x| x.as_str()),
+                    changelog_url: json.changelog_url().map(|x| x.as_str()),
+                    unity: json.unity().map(|x| format!("{}.{}", x.major(), x.minor())),
+                    unity_release: json.unity_release(),
+                    max_unity: maximum_unity(json).map(|x| x.to_string()),
+                    hide_in_editor: json.hide_in_editor(),
+                    samples: (json.samples().iter())
+                        .map(|sample| PackageSampleInfo {
+                            display_name: sample.display_name(),
+                            description: sample.description(),
+                            path: sample.path(),
+                        })
+                        .collect(),
+                    deprecated: json.deprecation().map(DeprecationInfo::new),
+                }
             })
             .collect();
 
@@ -211,12 +267,46 @@ impl Package {
         struct PackageVersionInfo<'a> {
             version: &'a Version,
             is_yanked: bool,
+            display_name: Option<&'a str>,
+            description: Option<&'a str>,
+            #[serde(rename = "type")]
+            package_type: Option<&'a str>,
+            keywords: &'a [Box<str>],
+            author: Option<PackageAuthorInfo<'a>>,
+            license: Option<&'a str>,
+            licenses_url: Option<&'a str>,
+            documentation_url: Option<&'a str>,
+            changelog_url: Option<&'a str>,
+            unity: Option<String>,
+            unity_release: Option<&'a str>,
+            max_unity: Option<String>,
+            hide_in_editor: Option<bool>,
+            samples: Vec<PackageSampleInfo<'a>>,
+            deprecated: Option<DeprecationInfo<'a>>,
+        }
+
+        #[derive(Serialize)]
+        struct PackageAuthorInfo<'a> {
+            name: &'a str,
+            email: Option<&'a str>,
+            url: Option<&'a str>,
+        }
+
+        #[derive(Serialize)]
+        struct PackageSampleInfo<'a> {
+            display_name: &'a str,
+            description: Option<&'a str>,
+            path: &'a str,
         }
 
         let package_info = PackageInfo {
             versions: versions.as_slice(),
         };
 
-        println!("{}", serde_json::to_string(&package_info).unwrap());
+        if crate::output::is_json() {
+            crate::output::set_result(&package_info);
+        } else {
+            println!("{}", serde_json::to_string(&package_info).unwrap());
+        }
     }
 }
@@ -1,10 +1,10 @@
 use crate::commands::{
-    confirm_prompt, load_env, load_unity, update_project_last_modified, EnvArgs, ResultExt,
+    confirm_prompt, load_env, load_unity, print_prompt_install, prompt_install, save_unity,
+    update_project_last_modified, ChangesResult, EnvArgs, PlanArgs, ResultExt,
 };
 use clap::{Parser, Subcommand};
 use log::{info, warn};
 use std::path::{Path, PathBuf};
-use std::process::exit;
 use tokio::process::Command;
 
 /// Migrate Unity Project
@@ -14,9 +14,10 @@ pub enum Migrate {
     #[command(subcommand)]
     Unity(Unity),
     Vpm(Vpm),
+    Replaced(Replaced),
 }
 
-multi_command!(Migrate is Unity, Vpm);
+multi_command!(Migrate is Unity, Vpm, Replaced);
 
 #[derive(Subcommand)]
 #[command(author, version)]
@@ -48,11 +49,11 @@ pub struct Unity2022 {
 impl Unity2022 {
     pub async fn run(self) {
         warn!("migrate unity-to-2022 is unstable command.");
-        println!("You're migrating your project to Unity 2022 in-place.");
-        println!("It's hard to undo this command.");
-        println!("You MUST create backup of your project before running this command.");
+        human_println!("You're migrating your project to Unity 2022 in-place.");
+        human_println!("It's hard to undo this command.");
+        human_println!("You MUST create backup of your project before running this command.");
         if !confirm_prompt("Do you want to continue?") {
-            exit(1);
+            exit_with!(Aborted, "aborted by user");
         }
 
         let mut project = load_unity(self.project).await;
@@ -75,7 +76,7 @@ impl Unity2022 {
             use vrc_get_vpm::VRCHAT_RECOMMENDED_2022_UNITY;
             let Some(found) = env.find_most_suitable_unity(VRCHAT_RECOMMENDED_2022_UNITY)
                 .exit_context("getting unity 2022 path") else {
-                exit_with!("Unity 2022 not found. please load from unity hub with `vrc-get vcc unity update` or specify path with `--unity` option.")
+                exit_with!(NotFound, "Unity 2022 not found. please load from unity hub with `vrc-get vcc unity update` or specify path with `--unity` option.")
             };
 
             if found.version() != Some(VRCHAT_RECOMMENDED_2022_UNITY) {
@@ -120,11 +121,11 @@ pub struct Vpm {
 impl Vpm {
     pub async fn run(self) {
         warn!("migrate vpm is unstable command.");
-        println!("You're migrating your project to vpm in-place.");
-        println!("It's hard to undo this command.");
-        println!("You MUST create backup of your project before running this command.");
+        human_println!("You're migrating your project to vpm in-place.");
+        human_println!("It's hard to undo this command.");
+        human_println!("You MUST create backup of your project before running this command.");
         if !confirm_prompt("Do you want to continue?") {
-            exit(1);
+            exit_with!(Aborted, "aborted by user");
         }
 
         let mut project = load_unity(self.project).await;
@@ -142,3 +143,72 @@ impl Vpm {
         update_project_last_modified(env, project.project_dir()).await;
     }
 }
+
+/// Replace deprecated packages with the packages in `replacedBy` of them
+///
+/// The packages are deprecated with `vrc-get.deprecated` in package.json.
+/// Deprecated packages without `replacedBy` are kept as is.
+#[derive(Parser)]
+pub struct Replaced {
+    /// Include prerelease
+    #[arg(long = "prerelease")]
+    prerelease: bool,
+
+    /// Path to project dir. by default CWD or parents of CWD will be used
+    #[arg(short = 'p', long = "project")]
+    project: Option<Box<Path>>,
+    #[command(flatten)]
+    env_args: EnvArgs,
+    #[command(flatten)]
+    plan_args: PlanArgs,
+
+    /// skip confirm
+    #[arg(short, long)]
+    yes: bool,
+}
+
+impl Replaced {
+    pub async fn run(self) {
+        let env = load_env(&self.env_args).await;
+        let mut unity = load_unity(self.project).await;
+
+        for deprecated in unity.deprecated_packages(&env) {
+            let deprecation = deprecated.deprecation();
+            let replaced_by = match deprecation.replaced_by() {
+                Some(replacement) => format!(" (replaced by {})", replacement.name()),
+                None => " (no replacement)".to_owned(),
+            };
+            human_println!(
+                "{} version {} is deprecated{}: {}",
+                deprecated.name(),
+                deprecated.version(),
+                replaced_by,
+                deprecation.message().unwrap_or("no message")
+            );
+        }
+
+        let changes = unity
+            .migrate_replaced_request(&env, self.prerelease)
+            .await
+            .exit_context("collecting packages to be replaced");
+
+        let planned = print_prompt_install(&changes);
+        self.plan_args.save_or_dry_run(&unity, &changes).await;
+        (self.plan_args.overwrite)
+            .check_modified(&unity, &env, &changes)
+            .await;
+
+        if !changes.package_changes().is_empty() {
+            prompt_install(self.yes);
+        }
+
+        unity
+            .apply_pending_changes(&env, changes)
+            .await
+            .exit_context("replacing packages");
+
+        save_unity(&mut unity).await;
+        crate::output::set_result(ChangesResult::new(planned, true));
+        update_project_last_modified(env, unity.project_dir()).await;
+    }
+}
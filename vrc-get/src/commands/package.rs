@@ -0,0 +1,170 @@
+use super::ResultExt;
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::ffi::OsStr;
+use std::path::{Component, Path, PathBuf};
+use vrc_get_vpm::io::DefaultProjectIo;
+use vrc_get_vpm::unity_project::{
+    scan_legacy_assets, top_level_legacy_assets, LegacyAssetInfo, UnityPackage,
+};
+
+/// Commands for package authors
+#[derive(Subcommand)]
+#[command(author, version)]
+pub enum Package {
+    LegacyScan(LegacyScan),
+}
+
+multi_command!(Package is LegacyScan);
+
+/// Generate legacyFolders and legacyFiles of package.json from old version of the package
+///
+/// The source is a .unitypackage file or a folder in Assets of a legacy project.
+/// By default, all files are emitted as legacyFiles so that the assets of other packages
+/// in the same folders are never removed.
+#[derive(Parser)]
+#[command(author, version)]
+pub struct LegacyScan {
+    /// Path to .unitypackage file or folder in Assets of legacy project
+    #[arg()]
+    source: PathBuf,
+
+    /// Path to package.json to merge legacyFolders and legacyFiles into.
+    /// Without this, the entries are printed
+    #[arg(long, value_name = "FILE")]
+    package_json: Option<PathBuf>,
+
+    /// Emit the top-level folders as legacyFolders instead of all files.
+    /// Removing those folders removes everything in them, so use this only if
+    /// the folders are owned by the old package. Shared folders like Assets/Plugins are never emitted
+    #[arg(long)]
+    top_level_folders: bool,
+}
+
+#[derive(Serialize)]
+struct LegacyScanResult {
+    #[serde(rename = "legacyFolders")]
+    legacy_folders: Map<String, Value>,
+    #[serde(rename = "legacyFiles")]
+    legacy_files: Map<String, Value>,
+}
+
+impl LegacyScan {
+    pub async fn run(self) {
+        let assets = if self.source.extension() == Some(OsStr::new("unitypackage")) {
+            let archive = tokio::fs::read(&self.source)
+                .await
+                .exit_context("reading unitypackage");
+            UnityPackage::read(archive.as_slice())
+                .await
+                .exit_context("reading unitypackage")
+                .legacy_assets()
+        } else {
+            let (project, folder) = split_project_path(&self.source);
+            scan_legacy_assets(&DefaultProjectIo::new(project), &folder).await
+        };
+
+        let selected = if self.top_level_folders {
+            top_level_legacy_assets(&assets)
+        } else {
+            assets.iter().filter(|x| !x.is_folder()).collect::<Vec<_>>()
+        };
+
+        if selected.is_empty() {
+            exit_with!(NotFound, "no assets with .meta files found");
+        }
+
+        let mut result = LegacyScanResult {
+            legacy_folders: Map::new(),
+            legacy_files: Map::new(),
+        };
+
+        for asset in selected {
+            warn_invalid_path(asset);
+            let map = if asset.is_folder() {
+                &mut result.legacy_folders
+            } else {
+                &mut result.legacy_files
+            };
+            map.insert(asset.path().into(), asset.guid().into());
+        }
+
+        crate::output::set_result(&result);
+
+        match &self.package_json {
+            None => {
+                if !crate::output::is_json() {
+                    println!("{}", serde_json::to_string_pretty(&result).unwrap());
+                }
+            }
+            Some(path) => {
+                merge_into_package_json(path, result).await;
+                human_println!("updated {}", path.display());
+            }
+        }
+    }
+}
+
+fn warn_invalid_path(asset: &LegacyAssetInfo) {
+    if !asset.is_valid_path() {
+        log::warn!(
+            "{} is not in Assets or Packages so it can only be found by GUID",
+            asset.path()
+        );
+    }
+}
+
+/// Splits the path to a folder in the legacy project into the project root and the relative path.
+fn split_project_path(path: &Path) -> (Box<Path>, PathBuf) {
+    let path = path.canonicalize().exit_context("resolving source folder");
+
+    let components = path.components().collect::<Vec<_>>();
+    let Some(assets_index) = components
+        .iter()
+        .rposition(|x| *x == Component::Normal(OsStr::new("Assets")))
+    else {
+        exit_with!("{} is not in Assets folder of a project", path.display());
+    };
+
+    let project = components[..assets_index].iter().collect::<PathBuf>();
+    let relative = components[assets_index..].iter().collect::<PathBuf>();
+    (project.into_boxed_path(), relative)
+}
+
+async fn merge_into_package_json(path: &Path, scanned: LegacyScanResult) {
+    let json = tokio::fs::read(path)
+        .await
+        .exit_context("reading package.json");
+    let mut json =
+        serde_json::from_slice::<Map<String, Value>>(&json).exit_context("parsing package.json");
+
+    for (key, entries) in [
+        ("legacyFolders", scanned.legacy_folders),
+        ("legacyFiles", scanned.legacy_files),
+    ] {
+        if entries.is_empty() {
+            continue;
+        }
+
+        let existing = json.entry(key).or_insert_with(|| Value::Object(Map::new()));
+        let Some(existing) = existing.as_object_mut() else {
+            exit_with!("{key} of package.json is not an object");
+        };
+
+        for (path, guid) in entries {
+            if let Some(old) = existing.get(&path) {
+                if old.as_str().is_some_and(|old| Some(old) != guid.as_str()) {
+                    log::warn!("replacing GUID of {path} in {key}: {old} -> {guid}");
+                }
+            }
+            existing.insert(path, guid);
+        }
+    }
+
+    let mut json = serde_json::to_string_pretty(&json).unwrap();
+    json.push('\n');
+    tokio::fs::write(path, json)
+        .await
+        .exit_context("writing package.json");
+}
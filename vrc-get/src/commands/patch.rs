@@ -0,0 +1,58 @@
+use super::{load_env, load_unity, EnvArgs, ResultExt};
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Manage local patches for installed packages
+///
+/// Patches are stored in Packages/vrc-get-patches and applied automatically
+/// every time the package is installed.
+#[derive(Subcommand)]
+#[command(author, version)]
+pub enum Patch {
+    Create(Create),
+}
+
+multi_command!(Patch is Create);
+
+/// Save the local modifications to the installed package as a patch
+///
+/// The modifications are compared with the package in the repositories.
+/// If there are no modifications, the existing patch for the version is removed.
+#[derive(Parser)]
+#[command(author, version)]
+pub struct Create {
+    /// Name of the package
+    #[arg()]
+    name: String,
+
+    /// Path to project dir. by default CWD or parents of CWD will be used
+    #[arg(short = 'p', long = "project")]
+    project: Option<Box<Path>>,
+    #[command(flatten)]
+    env_args: EnvArgs,
+}
+
+#[derive(Serialize)]
+struct CreateResult {
+    patch: Option<PathBuf>,
+}
+
+impl Create {
+    pub async fn run(self) {
+        let env = load_env(&self.env_args).await;
+        let unity = load_unity(self.project).await;
+
+        let patch = unity
+            .create_package_patch(&env, &self.name)
+            .await
+            .exit_context("creating patch");
+
+        match &patch {
+            Some(path) => human_println!("saved patch to {}", path.display()),
+            None => human_println!("{} has no local modifications", self.name),
+        }
+
+        crate::output::set_result(CreateResult { patch });
+    }
+}
@@ -0,0 +1,115 @@
+use super::{load_unity, save_unity};
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+use std::path::Path;
+use vrc_get_vpm::version::VersionRange;
+
+/// Manage resolutions which override the version ranges required by dependents
+///
+/// Resolutions are stored in Packages/vrc-get-project.json, not in vpm-manifest.json,
+/// so they don't affect VCC. vrc-get warns every time a resolution overrides a range.
+#[derive(Subcommand)]
+#[command(author, version)]
+pub enum Resolution {
+    Add(Add),
+    #[command(alias = "rm")]
+    Remove(Remove),
+    List(List),
+}
+
+multi_command!(Resolution is Add, Remove, List);
+
+/// Force the version range of the package regardless of dependents' ranges
+///
+/// The range is used when vrc-get resolves the package next time,
+/// for example with `vrc-get install` or `vrc-get upgrade`.
+#[derive(Parser)]
+#[command(author, version)]
+pub struct Add {
+    /// Name of the package
+    #[arg()]
+    name: String,
+    /// Version range to use instead of the ranges required by dependents. e.g. `1.3.0`, `^1.3`
+    #[arg()]
+    range: VersionRange,
+
+    /// Path to project dir. by default CWD or parents of CWD will be used
+    #[arg(short = 'p', long = "project")]
+    project: Option<Box<Path>>,
+}
+
+impl Add {
+    pub async fn run(self) {
+        let mut unity = load_unity(self.project).await;
+
+        human_println!("resolving {} to {}", self.name, self.range);
+        unity.set_resolution(&self.name, self.range);
+
+        save_unity(&mut unity).await;
+    }
+}
+
+/// Remove the resolution for the package
+#[derive(Parser)]
+#[command(author, version)]
+pub struct Remove {
+    /// Name of the package
+    #[arg()]
+    name: String,
+
+    /// Path to project dir. by default CWD or parents of CWD will be used
+    #[arg(short = 'p', long = "project")]
+    project: Option<Box<Path>>,
+}
+
+impl Remove {
+    pub async fn run(self) {
+        let mut unity = load_unity(self.project).await;
+
+        if !unity.remove_resolution(&self.name) {
+            exit_with!(NotFound, "no resolution for {}", self.name);
+        }
+        human_println!("removed resolution for {}", self.name);
+
+        save_unity(&mut unity).await;
+    }
+}
+
+/// List resolutions in the project
+#[derive(Parser)]
+#[command(author, version)]
+pub struct List {
+    /// Path to project dir. by default CWD or parents of CWD will be used
+    #[arg(short = 'p', long = "project")]
+    project: Option<Box<Path>>,
+}
+
+#[derive(Serialize)]
+struct ListResult<'a> {
+    resolutions: Vec<ResolutionInfo<'a>>,
+}
+
+#[derive(Serialize)]
+struct ResolutionInfo<'a> {
+    name: &'a str,
+    range: String,
+}
+
+impl List {
+    pub async fn run(self) {
+        let unity = load_unity(self.project).await;
+
+        let resolutions = (unity.resolutions())
+            .map(|(name, range)| ResolutionInfo {
+                name,
+                range: range.to_string(),
+            })
+            .collect::<Vec<_>>();
+
+        for resolution in &resolutions {
+            human_println!("{}: {}", resolution.name, resolution.range);
+        }
+
+        crate::output::set_result(ListResult { resolutions });
+    }
+}
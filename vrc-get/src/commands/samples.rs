@@ -0,0 +1,123 @@
+use super::{load_unity, ResultExt};
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+use std::path::Path;
+
+/// List or import samples in `samples` of installed packages
+///
+/// Like the Package Manager window of unity, samples are copied to
+/// `Assets/Samples/<package displayName>/<version>/<sample displayName>`.
+#[derive(Subcommand)]
+#[command(author, version)]
+pub enum Samples {
+    List(List),
+    Import(Import),
+}
+
+multi_command!(Samples is List, Import);
+
+/// List samples of the installed package
+#[derive(Parser)]
+#[command(author, version)]
+pub struct List {
+    /// Name of the package
+    #[arg()]
+    package: String,
+
+    /// Path to project dir. by default CWD or parents of CWD will be used
+    #[arg(short = 'p', long = "project")]
+    project: Option<Box<Path>>,
+}
+
+#[derive(Serialize)]
+struct ListResult<'a> {
+    samples: Vec<SampleResult<'a>>,
+}
+
+#[derive(Serialize)]
+struct SampleResult<'a> {
+    display_name: &'a str,
+    description: Option<&'a str>,
+    path: &'a str,
+    import_path: &'a Path,
+    imported: bool,
+}
+
+impl List {
+    pub async fn run(self) {
+        let unity = load_unity(self.project).await;
+
+        let samples = unity
+            .list_samples(&self.package)
+            .await
+            .exit_context("listing samples");
+
+        let samples = (samples.iter())
+            .map(|info| SampleResult {
+                display_name: info.sample().display_name(),
+                description: info.sample().description(),
+                path: info.sample().path(),
+                import_path: info.import_path(),
+                imported: info.is_imported(),
+            })
+            .collect::<Vec<_>>();
+
+        if samples.is_empty() {
+            human_println!("{} has no samples", self.package);
+        }
+        for sample in &samples {
+            if sample.imported {
+                human_println!("{} (imported)", sample.display_name);
+            } else {
+                human_println!("{}", sample.display_name);
+            }
+            if let Some(description) = sample.description {
+                human_println!("  {description}");
+            }
+        }
+
+        crate::output::set_result(ListResult { samples });
+    }
+}
+
+/// Import the sample of the installed package to Assets
+#[derive(Parser)]
+#[command(author, version)]
+pub struct Import {
+    /// Name of the package
+    #[arg()]
+    package: String,
+    /// The displayName or the name of the folder of the sample
+    #[arg()]
+    sample: String,
+
+    /// Replace the sample already imported
+    #[arg(long)]
+    overwrite: bool,
+
+    /// Path to project dir. by default CWD or parents of CWD will be used
+    #[arg(short = 'p', long = "project")]
+    project: Option<Box<Path>>,
+}
+
+#[derive(Serialize)]
+struct ImportResult<'a> {
+    import_path: &'a Path,
+}
+
+impl Import {
+    pub async fn run(self) {
+        let unity = load_unity(self.project).await;
+
+        let import_path = unity
+            .import_sample(&self.package, &self.sample, self.overwrite)
+            .await
+            .exit_context("importing sample");
+
+        human_println!("imported {} to {}", self.sample, import_path.display());
+
+        crate::output::set_result(ImportResult {
+            import_path: &import_path,
+        });
+    }
+}
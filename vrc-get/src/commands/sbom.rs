@@ -0,0 +1,402 @@
+use super::{load_env, load_unity, EnvArgs};
+use clap::{Parser, ValueEnum};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::path::Path;
+use vrc_get_vpm::unity_project::UpmDependency;
+use vrc_get_vpm::{PackageAuthor, PackageCollection, PackageJsonLike, VersionSelector};
+
+/// Export the software bill of materials of the project
+///
+/// All locked VPM packages and UPM dependencies are listed with the version, the repository,
+/// the download url, `zipSHA256`, the license, and the author.
+///
+/// The output is deterministic for the same project and repositories.
+/// The creation time of SPDX document is `SOURCE_DATE_EPOCH` if set, or the unix epoch otherwise.
+#[derive(Parser)]
+#[command(author, version)]
+pub struct Sbom {
+    /// Path to project dir. by default CWD or parents of CWD will be used
+    #[arg(short = 'p', long = "project")]
+    project: Option<Box<Path>>,
+
+    /// The format of the bill of materials
+    #[arg(long, value_enum, default_value_t)]
+    format: SbomFormat,
+
+    #[command(flatten)]
+    env_args: EnvArgs,
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, ValueEnum)]
+enum SbomFormat {
+    /// CycloneDX 1.5 json
+    #[default]
+    Cyclonedx,
+    /// SPDX 2.3 json
+    Spdx,
+}
+
+/// The package listed in the bill of materials.
+struct Component<'a> {
+    /// `vpm` or `upm`
+    source: &'static str,
+    name: &'a str,
+    version: Option<String>,
+    repository_url: Option<&'a str>,
+    download_url: Option<String>,
+    zip_sha_256: Option<&'a str>,
+    license: Option<&'a str>,
+    author: Option<&'a PackageAuthor>,
+    documentation_url: Option<&'a str>,
+}
+
+impl Sbom {
+    pub async fn run(self) {
+        let env = load_env(&self.env_args).await;
+        let unity = load_unity(self.project).await;
+
+        let mut components = vec![];
+
+        for locked in unity.locked_packages() {
+            let selector = VersionSelector::specific_version(locked.version());
+            let package = env.find_package_by_name(locked.name(), selector);
+            // prefer the information in the repository since zipSHA256 and url are only there
+            let json: Option<&dyn PackageJsonLike> = match package {
+                Some(package) => Some(package.package_json()),
+                None => (unity.get_installed_package(locked.name()))
+                    .filter(|json| json.version() == locked.version())
+                    .map(|json| json as &dyn PackageJsonLike),
+            };
+
+            components.push(Component {
+                source: "vpm",
+                name: locked.name(),
+                version: Some(locked.version().to_string()),
+                repository_url: (package.and_then(|x| x.repo()))
+                    .and_then(|repo| repo.url())
+                    .map(|url| url.as_str()),
+                download_url: json.and_then(|x| x.url()).map(|url| url.to_string()),
+                zip_sha_256: json.and_then(|x| x.zip_sha_256()),
+                license: json.and_then(|x| x.license()),
+                author: json.and_then(|x| x.author()),
+                documentation_url: (json.and_then(|x| x.documentation_url()))
+                    .map(|url| url.as_str()),
+            });
+        }
+
+        for (name, dependency) in unity.upm_dependencies() {
+            let (version, download_url) = if let UpmDependency::Version(version) = dependency {
+                (Some(version.to_string()), None)
+            } else {
+                // git url or local path
+                (None, Some(dependency.to_string()))
+            };
+            components.push(Component {
+                source: "upm",
+                name,
+                version,
+                repository_url: None,
+                download_url,
+                zip_sha_256: None,
+                license: None,
+                author: None,
+                documentation_url: None,
+            });
+        }
+
+        components.sort_by(|a, b| (a.source, a.name).cmp(&(b.source, b.name)));
+
+        let project_name = (unity.project_dir().file_name())
+            .map(|x| x.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "project".to_owned());
+
+        let document = if self.format == SbomFormat::Spdx {
+            spdx(&project_name, &components)
+        } else {
+            cyclonedx(&project_name, &components)
+        };
+
+        if !crate::output::is_json() {
+            println!("{}", serde_json::to_string_pretty(&document).unwrap());
+        }
+        crate::output::set_result(document);
+    }
+}
+
+fn author_string(author: &PackageAuthor) -> String {
+    match author.email() {
+        Some(email) => format!("{} <{email}>", author.name()),
+        None => author.name().to_owned(),
+    }
+}
+
+fn cyclonedx(project_name: &str, components: &[Component]) -> Value {
+    let components = (components.iter())
+        .map(|component| {
+            let bom_ref = match &component.version {
+                Some(version) => format!("{}:{}@{version}", component.source, component.name),
+                None => format!("{}:{}", component.source, component.name),
+            };
+            let mut json = json!({
+                "type": "library",
+                "bom-ref": bom_ref,
+                "name": component.name,
+            });
+            if let Some(version) = &component.version {
+                json["version"] = json!(version);
+            }
+            if let Some(author) = component.author {
+                json["author"] = json!(author_string(author));
+            }
+            if let Some(license) = component.license {
+                json["licenses"] = json!([{ "license": { "name": license } }]);
+            }
+            if let Some(sha256) = component.zip_sha_256 {
+                json["hashes"] = json!([{ "alg": "SHA-256", "content": sha256 }]);
+            }
+
+            let mut references = vec![];
+            if let Some(url) = &component.download_url {
+                references.push(json!({ "type": "distribution", "url": url }));
+            }
+            if let Some(url) = component.documentation_url {
+                references.push(json!({ "type": "documentation", "url": url }));
+            }
+            if let Some(url) = component.repository_url {
+                references
+                    .push(json!({ "type": "other", "url": url, "comment": "VPM repository" }));
+            }
+            if let Some(url) = (component.author).and_then(|x| x.url()) {
+                references.push(json!({ "type": "website", "url": url }));
+            }
+            if !references.is_empty() {
+                json["externalReferences"] = Value::Array(references);
+            }
+
+            json["properties"] = json!([{ "name": "vrc-get:source", "value": component.source }]);
+            json
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "bomFormat": "CycloneDX",
+        "specVersion": "1.5",
+        "version": 1,
+        "metadata": {
+            "tools": [{ "name": "vrc-get", "version": env!("CARGO_PKG_VERSION") }],
+            "component": { "type": "application", "bom-ref": "project", "name": project_name },
+        },
+        "components": components,
+    })
+}
+
+fn spdx(project_name: &str, components: &[Component]) -> Value {
+    fn sanitize(name: &str) -> String {
+        name.replace(
+            |c: char| !c.is_ascii_alphanumeric() && c != '.' && c != '-',
+            "-",
+        )
+    }
+
+    let created = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|x| x.trim().parse::<u64>().ok())
+        .unwrap_or(0);
+    let (year, month, day, hour, minute, second) = super::history::date_time(created);
+
+    let mut packages = vec![json!({
+        "SPDXID": "SPDXRef-Project",
+        "name": project_name,
+        "downloadLocation": "NOASSERTION",
+        "filesAnalyzed": false,
+    })];
+    let mut relationships = vec![json!({
+        "spdxElementId": "SPDXRef-DOCUMENT",
+        "relationshipType": "DESCRIBES",
+        "relatedSpdxElement": "SPDXRef-Project",
+    })];
+
+    let mut ids = HashSet::new();
+    for component in components {
+        // the names only different in the sanitized characters like `a_b` and `a-b` would conflict
+        let base_id = format!("SPDXRef-{}-{}", component.source, sanitize(component.name));
+        let id = (1..)
+            .map(|i| match i {
+                1 => base_id.clone(),
+                i => format!("{base_id}-{i}"),
+            })
+            .find(|id| !ids.contains(id))
+            .unwrap();
+        ids.insert(id.clone());
+
+        let mut json = json!({
+            "SPDXID": id,
+            "name": component.name,
+        });
+        if let Some(version) = &component.version {
+            json["versionInfo"] = json!(version);
+        }
+        json["downloadLocation"] =
+            json!(component.download_url.as_deref().unwrap_or("NOASSERTION"));
+        json["filesAnalyzed"] = json!(false);
+        if let Some(sha256) = component.zip_sha_256 {
+            json["checksums"] = json!([{ "algorithm": "SHA256", "checksumValue": sha256 }]);
+        }
+        if let Some(url) = component.documentation_url {
+            json["homepage"] = json!(url);
+        }
+        if let Some(url) = component.repository_url {
+            json["sourceInfo"] = json!(format!("from VPM repository {url}"));
+        }
+        json["licenseConcluded"] = json!("NOASSERTION");
+        // licenses not written in SPDX expression are not asserted
+        match component.license {
+            Some(license) if is_license_expression(license) => {
+                json["licenseDeclared"] = json!(license);
+            }
+            Some(license) => {
+                json["licenseDeclared"] = json!("NOASSERTION");
+                json["licenseComments"] = json!(format!("declared license: {license}"));
+            }
+            None => json["licenseDeclared"] = json!("NOASSERTION"),
+        }
+        json["copyrightText"] = json!("NOASSERTION");
+        json["supplier"] = match component.author {
+            Some(author) => json!(format!("Person: {}", author_string(author))),
+            None => json!("NOASSERTION"),
+        };
+        packages.push(json);
+
+        relationships.push(json!({
+            "spdxElementId": "SPDXRef-Project",
+            "relationshipType": "DEPENDS_ON",
+            "relatedSpdxElement": id,
+        }));
+    }
+
+    json!({
+        "spdxVersion": "SPDX-2.3",
+        "dataLicense": "CC0-1.0",
+        "SPDXID": "SPDXRef-DOCUMENT",
+        "name": project_name,
+        // the namespace doesn't include random values to keep the output deterministic
+        "documentNamespace": format!("urn:vrc-get:sbom:{}", sanitize(project_name)),
+        "creationInfo": {
+            "created": format!("{year:04}-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}Z"),
+            "creators": [format!("Tool: vrc-get-{}", env!("CARGO_PKG_VERSION"))],
+        },
+        "packages": packages,
+        "relationships": relationships,
+    })
+}
+
+/// Checks if the license is a valid SPDX license expression.
+///
+/// The license identifiers are not checked against the SPDX license list.
+fn is_license_expression(license: &str) -> bool {
+    fn is_id(token: &str) -> bool {
+        !token.is_empty()
+            && (token.chars()).all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-')
+    }
+
+    fn is_license(token: &str) -> bool {
+        if let Some((document, license)) = token.split_once(':') {
+            return (document.strip_prefix("DocumentRef-")).is_some_and(is_id)
+                && (license.strip_prefix("LicenseRef-")).is_some_and(is_id);
+        }
+        is_id(token.strip_suffix('+').unwrap_or(token))
+    }
+
+    fn is_operator(token: &str) -> bool {
+        matches!(token, "AND" | "OR" | "WITH")
+    }
+
+    // compound = simple ("WITH" exception)? | "(" expression ")"
+    // expression = compound (("AND" | "OR") compound)*
+    fn expression<'a>(tokens: &mut std::iter::Peekable<impl Iterator<Item = &'a str>>) -> bool {
+        loop {
+            match tokens.next() {
+                Some("(") => {
+                    if !expression(tokens) || tokens.next() != Some(")") {
+                        return false;
+                    }
+                }
+                Some(token) if !is_operator(token) && is_license(token) => {
+                    if tokens.next_if_eq(&"WITH").is_some()
+                        && !tokens.next().is_some_and(|x| !is_operator(x) && is_id(x))
+                    {
+                        return false;
+                    }
+                }
+                _ => return false,
+            }
+
+            if tokens.next_if(|x| *x == "AND" || *x == "OR").is_none() {
+                return true;
+            }
+        }
+    }
+
+    let spaced = license.replace('(', " ( ").replace(')', " ) ");
+    let mut tokens = spaced.split_whitespace().peekable();
+    expression(&mut tokens) && tokens.next().is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn license_expression() {
+        assert!(is_license_expression("MIT"));
+        assert!(is_license_expression("GPL-2.0+"));
+        assert!(is_license_expression("MIT OR Apache-2.0"));
+        assert!(is_license_expression("(MIT OR Apache-2.0) AND Zlib"));
+        assert!(is_license_expression(
+            "GPL-2.0-or-later WITH Classpath-exception-2.0"
+        ));
+        assert!(is_license_expression("LicenseRef-VRChat"));
+        assert!(is_license_expression("DocumentRef-spdx:LicenseRef-MIT"));
+
+        assert!(!is_license_expression(""));
+        assert!(!is_license_expression("MIT License"));
+        assert!(!is_license_expression("see LICENSE.md"));
+        assert!(!is_license_expression("MIT OR"));
+        assert!(!is_license_expression("(MIT"));
+        assert!(!is_license_expression("MIT)"));
+        assert!(!is_license_expression("MIT WITH"));
+        assert!(!is_license_expression("AND"));
+        assert!(!is_license_expression("MIT_1"));
+    }
+
+    #[test]
+    fn unique_spdx_id() {
+        let component = |name| Component {
+            source: "vpm",
+            name,
+            version: None,
+            repository_url: None,
+            download_url: None,
+            zip_sha_256: None,
+            license: Some("MIT License"),
+            author: None,
+            documentation_url: None,
+        };
+        let document = spdx("project", &[component("a-b"), component("a_b")]);
+
+        let packages = document["packages"].as_array().unwrap();
+        assert_eq!(packages[1]["SPDXID"], "SPDXRef-vpm-a-b");
+        assert_eq!(packages[2]["SPDXID"], "SPDXRef-vpm-a-b-2");
+        assert_eq!(
+            document["relationships"][2]["relatedSpdxElement"],
+            "SPDXRef-vpm-a-b-2"
+        );
+
+        assert_eq!(packages[1]["licenseDeclared"], "NOASSERTION");
+        assert_eq!(
+            packages[1]["licenseComments"],
+            "declared license: MIT License"
+        );
+    }
+}
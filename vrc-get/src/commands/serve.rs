@@ -0,0 +1,750 @@
+//! The JSON-RPC server for editor integrations.
+//!
+//! The server keeps the [`Environment`] loaded and answers JSON-RPC 2.0 requests.
+//! Each message is one line of JSON on stdin or stdout.
+//!
+//! Requests:
+//! - `packages/list` `{}`: the latest versions of all packages
+//! - `packages/search` `{ "queries"?: [string], "keywords"?: [string], "author"?: string }`: the same as `vrc-get search`
+//! - `project/info` `{ "project"?: path }`: the same as `vrc-get info project --json-format 1`
+//! - `project/install` `{ "project"?: path, "packages": [{ "name": string, "version"?: string }], "prerelease"?: bool, "save"?: "version" | "exact" | "caret" | "tilde" }`
+//! - `project/upgrade` `{ "project"?: path, "packages"?: [string], "prerelease"?: bool, "ignore_hold"?: bool, "level"?: "latest" | "minor" | "patch" | "compatible" }`
+//!   without `packages`, held packages are skipped. held packages in `packages` are refused unless `ignore_hold`
+//! - `project/remove` `{ "project"?: path, "packages": [string] }`
+//! - `project/resolve` `{ "project"?: path }`
+//! - `project/apply` `{ "plan_id": number }`
+//! - `repositories/refresh` `{}`
+//! - `shutdown` `{}`: responds and exits the server
+//!
+//! The `project/*` requests except for `project/info` plan the changes and respond
+//! `{ "plan_id": number, "changes": planned changes }`. The plan is applied with `project/apply`,
+//! which fails if the project or the packages are changed since the plan is created.
+//!
+//! Notifications:
+//! - `progress` `{ "message": string }`: sent while processing long requests
+//! - `environment/changed` `{}`: the repositories are reloaded
+//! - `project/changed` `{ "project": path }`: the changes are applied to the project
+//!
+//! Application errors have the code `-32000` and `{ "kind": string }` as data where the kind is
+//! the same as `error.kind` of `--output json`.
+
+use super::{
+    load_env, search_packages, ChangesResult, EnvArgs, Environment, PlannedChanges, SearchResult,
+    UnityProject,
+};
+use crate::output::{ErrorExitCode, ExitCode};
+use clap::Parser;
+use itertools::Itertools;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use vrc_get_vpm::io::DefaultProjectIo;
+use vrc_get_vpm::unity_project::{
+    AddPackageOperation, PendingProjectChanges, SavedPendingChanges, UpgradeLevel,
+};
+use vrc_get_vpm::version::{DependencyRangeStyle, Version};
+use vrc_get_vpm::{PackageCollection, PackageInfo, VersionSelector};
+
+/// Start the JSON-RPC server for editor integrations
+///
+/// The server keeps repositories loaded and answers JSON-RPC 2.0 requests, one JSON per line.
+/// See the documentation of the repository for the list of methods.
+#[derive(Parser)]
+#[command(author, version)]
+pub struct Serve {
+    /// Communicate over stdin and stdout
+    #[arg(long, required = true)]
+    stdio: bool,
+    #[command(flatten)]
+    env_args: EnvArgs,
+}
+
+impl Serve {
+    pub async fn run(self) {
+        debug_assert!(self.stdio);
+
+        let mut server = Server {
+            env: load_env(&self.env_args).await,
+            offline: self.env_args.offline,
+            plans: Plans::default(),
+            out: Output(tokio::io::stdout()),
+        };
+
+        server.serve(BufReader::new(tokio::io::stdin())).await;
+    }
+}
+
+#[derive(Eq, PartialEq)]
+enum Flow {
+    Continue,
+    Exit,
+}
+
+struct Output<W>(W);
+
+impl<W: AsyncWrite + Unpin> Output<W> {
+    async fn send(&mut self, message: Value) {
+        let mut line = serde_json::to_vec(&message).expect("serializing message");
+        line.push(b'\n');
+        // if stdout is closed, we will exit with EOF of stdin
+        if let Err(err) = self.0.write_all(&line).await {
+            log::error!("error writing message: {err}");
+        }
+        self.0.flush().await.ok();
+    }
+
+    async fn notify(&mut self, method: &str, params: impl Serialize) {
+        self.send(json!({ "jsonrpc": "2.0", "method": method, "params": params }))
+            .await;
+    }
+
+    async fn progress(&mut self, message: &str) {
+        self.notify("progress", json!({ "message": message })).await;
+    }
+}
+
+struct Plan {
+    project: Box<Path>,
+    saved: SavedPendingChanges,
+}
+
+#[derive(Default)]
+struct Plans {
+    plans: HashMap<u64, Plan>,
+    last_id: u64,
+}
+
+impl Plans {
+    fn save(&mut self, unity: &UnityProject, changes: &PendingProjectChanges) -> Value {
+        self.last_id += 1;
+        let plan_id = self.last_id;
+
+        self.plans.insert(
+            plan_id,
+            Plan {
+                project: unity.project_dir().into(),
+                saved: unity.save_pending_changes(changes),
+            },
+        );
+
+        let result = PlanResult {
+            plan_id,
+            changes: PlannedChanges::new(changes),
+        };
+        serde_json::to_value(result).expect("serializing plan")
+    }
+}
+
+struct Server<W> {
+    env: Environment,
+    offline: bool,
+    plans: Plans,
+    out: Output<W>,
+}
+
+#[derive(Deserialize)]
+struct Request {
+    jsonrpc: String,
+    #[serde(default)]
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Debug)]
+struct RpcError {
+    code: i32,
+    message: String,
+    data: Option<Value>,
+}
+
+impl RpcError {
+    const PARSE_ERROR: i32 = -32700;
+    const INVALID_REQUEST: i32 = -32600;
+    const METHOD_NOT_FOUND: i32 = -32601;
+    const INVALID_PARAMS: i32 = -32602;
+    const APPLICATION_ERROR: i32 = -32000;
+
+    fn new(code: i32, message: impl Display) -> Self {
+        Self {
+            code,
+            message: message.to_string(),
+            data: None,
+        }
+    }
+
+    fn application(kind: ExitCode, message: impl Display) -> Self {
+        Self {
+            code: Self::APPLICATION_ERROR,
+            message: message.to_string(),
+            data: Some(json!({ "kind": kind })),
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        let mut error = json!({ "code": self.code, "message": self.message });
+        if let Some(data) = &self.data {
+            error["data"] = data.clone();
+        }
+        error
+    }
+}
+
+impl<E: Display + ErrorExitCode> From<E> for RpcError {
+    fn from(value: E) -> Self {
+        Self::application(value.exit_code(), value)
+    }
+}
+
+fn params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    let params = if params.is_null() { json!({}) } else { params };
+    serde_json::from_value(params).map_err(|e| RpcError::new(RpcError::INVALID_PARAMS, e))
+}
+
+#[derive(Deserialize)]
+struct ProjectParams {
+    #[serde(default)]
+    project: Option<PathBuf>,
+}
+
+#[derive(Deserialize)]
+struct SearchParams {
+    #[serde(default)]
+    queries: Vec<String>,
+    #[serde(default)]
+    keywords: Vec<String>,
+    #[serde(default)]
+    author: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct InstallParams {
+    #[serde(default)]
+    project: Option<PathBuf>,
+    packages: Vec<InstallPackage>,
+    #[serde(default)]
+    prerelease: bool,
+    #[serde(default)]
+    save: Option<DependencyRangeStyle>,
+}
+
+#[derive(Deserialize)]
+struct InstallPackage {
+    name: String,
+    #[serde(default)]
+    version: Option<Version>,
+}
+
+#[derive(Deserialize)]
+struct UpgradeParams {
+    #[serde(default)]
+    project: Option<PathBuf>,
+    #[serde(default)]
+    packages: Option<Vec<String>>,
+    #[serde(default)]
+    prerelease: bool,
+    #[serde(default)]
+    ignore_hold: bool,
+    #[serde(default)]
+    level: Option<UpgradeLevel>,
+}
+
+#[derive(Deserialize)]
+struct RemoveParams {
+    #[serde(default)]
+    project: Option<PathBuf>,
+    packages: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct ApplyParams {
+    plan_id: u64,
+    /// overwrite packages with local modifications
+    #[serde(default)]
+    force: bool,
+}
+
+#[derive(Serialize)]
+struct PlanResult {
+    plan_id: u64,
+    changes: PlannedChanges,
+}
+
+async fn load_project(path: Option<PathBuf>) -> Result<UnityProject, RpcError> {
+    let io = match path {
+        Some(path) => DefaultProjectIo::new(path.into_boxed_path()),
+        None => DefaultProjectIo::find_project_parent(std::env::current_dir()?)?,
+    };
+    Ok(UnityProject::load(io).await?)
+}
+
+fn find_package<'env>(
+    env: &'env Environment,
+    name: &str,
+    selector: VersionSelector,
+) -> Result<PackageInfo<'env>, RpcError> {
+    env.find_package_by_name(name, selector).ok_or_else(|| {
+        RpcError::application(ExitCode::NotFound, format!("package {name} not found"))
+    })
+}
+
+impl<W: AsyncWrite + Unpin> Server<W> {
+    /// Processes the requests until the end of input or `shutdown`.
+    async fn serve(&mut self, input: impl AsyncBufRead + Unpin) {
+        let mut lines = input.lines();
+
+        loop {
+            let line = match lines.next_line().await {
+                Ok(Some(line)) => line,
+                Ok(None) => break,
+                Err(err) => {
+                    log::error!("error reading request: {err}");
+                    break;
+                }
+            };
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            if self.process(&line).await == Flow::Exit {
+                break;
+            }
+        }
+    }
+
+    async fn process(&mut self, line: &str) -> Flow {
+        let request = match serde_json::from_str::<Value>(line) {
+            Ok(request) => request,
+            Err(err) => {
+                let error = RpcError::new(RpcError::PARSE_ERROR, err);
+                self.respond(Value::Null, Err(error)).await;
+                return Flow::Continue;
+            }
+        };
+
+        let request = match serde_json::from_value::<Request>(request.clone()) {
+            Ok(request) if request.jsonrpc == "2.0" => request,
+            Ok(_) | Err(_) => {
+                let id = request.get("id").cloned().unwrap_or(Value::Null);
+                let error = RpcError::new(RpcError::INVALID_REQUEST, "invalid request");
+                self.respond(id, Err(error)).await;
+                return Flow::Continue;
+            }
+        };
+
+        let flow = if request.method == "shutdown" {
+            Flow::Exit
+        } else {
+            Flow::Continue
+        };
+
+        let result = self.handle(&request.method, request.params).await;
+
+        // requests without id are notifications and we must not respond to them
+        if let Some(id) = request.id {
+            self.respond(id, result).await;
+        }
+
+        flow
+    }
+
+    async fn respond(&mut self, id: Value, result: Result<Value, RpcError>) {
+        let message = match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(error) => json!({ "jsonrpc": "2.0", "id": id, "error": error.to_json() }),
+        };
+        self.out.send(message).await;
+    }
+
+    async fn handle(&mut self, method: &str, params: Value) -> Result<Value, RpcError> {
+        match method {
+            "packages/list" => self.list_packages(),
+            "packages/search" => self.search_packages(self::params(params)?),
+            "project/info" => self.project_info(self::params(params)?).await,
+            "project/install" => self.install(self::params(params)?).await,
+            "project/upgrade" => self.upgrade(self::params(params)?).await,
+            "project/remove" => self.remove(self::params(params)?).await,
+            "project/resolve" => self.resolve(self::params(params)?).await,
+            "project/apply" => self.apply(self::params(params)?).await,
+            "repositories/refresh" => self.refresh().await,
+            "shutdown" => Ok(Value::Null),
+            _ => Err(RpcError::new(
+                RpcError::METHOD_NOT_FOUND,
+                format!("method {method} not found"),
+            )),
+        }
+    }
+
+    fn list_packages(&self) -> Result<Value, RpcError> {
+        let packages = self
+            .env
+            .find_whole_all_packages(VersionSelector::latest_for(None, true), |_| true);
+        let result = (packages.into_iter())
+            .map(SearchResult::new)
+            .collect::<Vec<_>>();
+        Ok(serde_json::to_value(result)?)
+    }
+
+    fn search_packages(&self, params: SearchParams) -> Result<Value, RpcError> {
+        let found = search_packages(
+            &self.env,
+            &params.queries,
+            &params.keywords,
+            params.author.as_deref(),
+        );
+        let result = (found.into_iter())
+            .map(SearchResult::new)
+            .collect::<Vec<_>>();
+        Ok(serde_json::to_value(result)?)
+    }
+
+    async fn project_info(&self, params: ProjectParams) -> Result<Value, RpcError> {
+        let unity = load_project(params.project).await?;
+        Ok(super::info::project_info_v1(&unity))
+    }
+
+    async fn install(&mut self, params: InstallParams) -> Result<Value, RpcError> {
+        let mut unity = load_project(params.project).await?;
+        unity.set_dependency_range_style(
+            params
+                .save
+                .unwrap_or_else(|| self.env.dependency_range_style().clone()),
+        );
+
+        let packages = (params.packages.iter())
+            .map(|package| {
+                let selector = match &package.version {
+                    None => VersionSelector::latest_for(unity.unity_version(), params.prerelease),
+                    Some(version) => VersionSelector::specific_version(version),
+                };
+                find_package(&self.env, &package.name, selector)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let changes = unity
+            .add_package_request(
+                &self.env,
+                &packages,
+                AddPackageOperation::InstallToDependencies,
+                params.prerelease,
+            )
+            .await?;
+
+        Ok(self.plans.save(&unity, &changes))
+    }
+
+    async fn upgrade(&mut self, params: UpgradeParams) -> Result<Value, RpcError> {
+        let unity = load_project(params.project).await?;
+
+        let selector = VersionSelector::latest_for(unity.unity_version(), params.prerelease);
+        let packages = match &params.packages {
+            Some(names) => {
+                if !params.ignore_hold {
+                    if let Some(name) = names.iter().find(|name| unity.is_held(name)) {
+                        return Err(RpcError::application(
+                            ExitCode::Conflict,
+                            format!("package {name} is held"),
+                        ));
+                    }
+                }
+                match params.level.filter(|&x| x != UpgradeLevel::Latest) {
+                    None => (names.iter())
+                        .map(|name| find_package(&self.env, name, selector))
+                        .collect::<Result<Vec<_>, _>>()?,
+                    Some(level) => (names.iter())
+                        .filter_map(|name| {
+                            unity.find_upgrade(&self.env, name, level, params.prerelease)
+                        })
+                        .collect(),
+                }
+            }
+            None => {
+                let not_held =
+                    (unity.locked_packages()).filter(|locked| !unity.is_held(locked.name()));
+                match params.level.filter(|&x| x != UpgradeLevel::Latest) {
+                    None => not_held
+                        .map(|locked| find_package(&self.env, locked.name(), selector))
+                        .collect::<Result<Vec<_>, _>>()?,
+                    Some(level) => not_held
+                        .filter_map(|locked| {
+                            unity.find_upgrade(&self.env, locked.name(), level, params.prerelease)
+                        })
+                        .collect(),
+                }
+            }
+        };
+
+        let changes = unity
+            .add_package_request(
+                &self.env,
+                &packages,
+                AddPackageOperation::UpgradeLocked,
+                params.prerelease,
+            )
+            .await?;
+
+        if !params.ignore_hold {
+            let held = unity.held_changes(&changes);
+            if !held.is_empty() {
+                return Err(RpcError::application(
+                    ExitCode::Conflict,
+                    format!(
+                        "upgrading held packages: {}",
+                        held.iter().map(|x| x.name()).join(", ")
+                    ),
+                ));
+            }
+        }
+
+        Ok(self.plans.save(&unity, &changes))
+    }
+
+    async fn remove(&mut self, params: RemoveParams) -> Result<Value, RpcError> {
+        let unity = load_project(params.project).await?;
+
+        let names = params
+            .packages
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        let changes = unity.remove_request(&names).await?;
+
+        Ok(self.plans.save(&unity, &changes))
+    }
+
+    async fn resolve(&mut self, params: ProjectParams) -> Result<Value, RpcError> {
+        let unity = load_project(params.project).await?;
+
+        let changes = unity.resolve_request(&self.env).await?;
+
+        Ok(self.plans.save(&unity, &changes))
+    }
+
+    async fn apply(&mut self, params: ApplyParams) -> Result<Value, RpcError> {
+        let Some(plan) = self.plans.plans.get(&params.plan_id) else {
+            return Err(RpcError::application(
+                ExitCode::NotFound,
+                format!("plan {} not found", params.plan_id),
+            ));
+        };
+
+        let mut unity = load_project(Some(plan.project.to_path_buf())).await?;
+
+        let changes = unity.restore_pending_changes(&self.env, &plan.saved)?;
+        let planned = PlannedChanges::new(&changes);
+
+        if !params.force {
+            let modified = unity.overwritten_modifications(&changes).await?;
+            if !modified.is_empty() {
+                // the plan is kept so that the client can retry with force
+                let names = modified
+                    .iter()
+                    .map(|x| x.name())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                return Err(RpcError::application(
+                    ExitCode::Conflict,
+                    format!("the following packages have local modifications: {names}"),
+                ));
+            }
+        }
+
+        let plan = self.plans.plans.remove(&params.plan_id).unwrap();
+
+        self.out.progress("applying changes").await;
+        unity.apply_pending_changes(&self.env, changes).await?;
+        unity.save().await?;
+
+        self.update_project_last_modified(&plan.project).await;
+
+        self.out
+            .notify("project/changed", json!({ "project": plan.project }))
+            .await;
+
+        Ok(serde_json::to_value(ChangesResult::new(planned, true))?)
+    }
+
+    #[cfg(feature = "experimental-vcc")]
+    async fn update_project_last_modified(&mut self, project_dir: &Path) {
+        let result = async {
+            self.env.update_project_last_modified(project_dir)?;
+            self.env.save().await
+        }
+        .await;
+
+        if let Err(err) = result {
+            log::error!("error updating project updated_at on vcc: {err}");
+        }
+    }
+
+    #[cfg(not(feature = "experimental-vcc"))]
+    async fn update_project_last_modified(&mut self, _: &Path) {}
+
+    async fn refresh(&mut self) -> Result<Value, RpcError> {
+        self.out.progress("loading repositories").await;
+
+        self.env.reload().await?;
+        self.env.load_package_infos(!self.offline).await?;
+        self.env.save().await?;
+
+        // plans may refer to the packages no longer available
+        self.plans.plans.clear();
+
+        self.out.notify("environment/changed", json!({})).await;
+
+        Ok(Value::Null)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vrc_get_vpm::io::DefaultEnvironmentIo;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("vrc-get-serve-{name}-{}", std::process::id()));
+        std::fs::remove_dir_all(&dir).ok();
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write(path: &Path, content: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    /// Creates the project with `com.anatawa12.gists` installed.
+    fn create_project(dir: &Path) {
+        write(
+            &dir.join("Packages/manifest.json"),
+            r#"{"dependencies": {}}"#,
+        );
+        write(
+            &dir.join("Packages/vpm-manifest.json"),
+            r#"{
+                "dependencies": {"com.anatawa12.gists": {"version": "1.0.0"}},
+                "locked": {"com.anatawa12.gists": {"version": "1.0.0", "dependencies": {}}}
+            }"#,
+        );
+        write(
+            &dir.join("Packages/com.anatawa12.gists/package.json"),
+            r#"{"name": "com.anatawa12.gists", "version": "1.0.0"}"#,
+        );
+    }
+
+    /// Runs the server with the requests and returns the messages sent by the server.
+    async fn serve(name: &str, requests: &[Value]) -> Vec<Value> {
+        let env_dir = temp_dir(&format!("{name}-env"));
+        let io = DefaultEnvironmentIo::new(env_dir.clone().into_boxed_path());
+        let mut server = Server {
+            env: Environment::load(None, io).await.unwrap(),
+            offline: true,
+            plans: Plans::default(),
+            out: Output(Vec::new()),
+        };
+
+        let input = (requests.iter())
+            .map(|x| format!("{x}\n"))
+            .collect::<String>();
+        server.serve(input.as_bytes()).await;
+        std::fs::remove_dir_all(env_dir).ok();
+
+        (server.out.0.split(|&x| x == b'\n'))
+            .filter(|x| !x.is_empty())
+            .map(|x| serde_json::from_slice(x).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn plan_round_trip() {
+        let project = temp_dir("plan");
+        create_project(&project);
+
+        let messages = serve(
+            "plan",
+            &[
+                json!({"jsonrpc": "2.0", "id": 1, "method": "project/remove", "params": {
+                    "project": project, "packages": ["com.anatawa12.gists"],
+                }}),
+                json!({"jsonrpc": "2.0", "id": 2, "method": "project/apply", "params": {"plan_id": 1}}),
+                json!({"jsonrpc": "2.0", "id": 3, "method": "project/apply", "params": {"plan_id": 1}}),
+            ],
+        )
+        .await;
+
+        assert_eq!(messages[0]["id"], 1);
+        assert_eq!(messages[0]["result"]["plan_id"], 1);
+        assert_eq!(
+            messages[0]["result"]["changes"]["remove"][0]["name"],
+            "com.anatawa12.gists"
+        );
+
+        assert_eq!(messages[1]["method"], "progress");
+        assert_eq!(messages[2]["method"], "project/changed");
+        assert_eq!(messages[3]["id"], 2);
+        assert_eq!(messages[3]["result"]["applied"], true);
+        assert!(!project.join("Packages/com.anatawa12.gists").exists());
+
+        // the plan can be applied only once
+        assert_eq!(messages[4]["id"], 3);
+        assert_eq!(messages[4]["error"]["code"], RpcError::APPLICATION_ERROR);
+        assert_eq!(messages[4]["error"]["data"]["kind"], "not_found");
+        assert_eq!(messages.len(), 5);
+
+        std::fs::remove_dir_all(project).ok();
+    }
+
+    #[tokio::test]
+    async fn unknown_method() {
+        let messages = serve(
+            "unknown",
+            &[json!({"jsonrpc": "2.0", "id": "a", "method": "project/unknown"})],
+        )
+        .await;
+
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0]["id"], "a");
+        assert_eq!(messages[0]["error"]["code"], RpcError::METHOD_NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn bad_params() {
+        let messages = serve(
+            "params",
+            &[
+                json!({"jsonrpc": "2.0", "id": 1, "method": "project/apply", "params": {}}),
+                json!({"jsonrpc": "2.0", "id": 2, "method": "project/remove", "params": {"packages": "a"}}),
+            ],
+        )
+        .await;
+
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0]["error"]["code"], RpcError::INVALID_PARAMS);
+        assert_eq!(messages[1]["error"]["code"], RpcError::INVALID_PARAMS);
+    }
+
+    #[tokio::test]
+    async fn notification() {
+        let messages = serve(
+            "notification",
+            &[
+                json!({"jsonrpc": "2.0", "method": "packages/list"}),
+                json!({"jsonrpc": "2.0", "method": "project/unknown"}),
+                json!({"jsonrpc": "2.0", "id": 1, "method": "shutdown"}),
+                json!({"jsonrpc": "2.0", "id": 2, "method": "packages/list"}),
+            ],
+        )
+        .await;
+
+        // no responses for notifications even for errors, and no requests after shutdown
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0]["id"], 1);
+        assert_eq!(messages[0]["result"], Value::Null);
+    }
+}
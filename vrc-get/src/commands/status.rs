@@ -0,0 +1,97 @@
+use super::{load_unity, ResultExt};
+use clap::Parser;
+use serde::Serialize;
+use std::path::Path;
+use vrc_get_vpm::unity_project::PackageModifications;
+use vrc_get_vpm::version::Version;
+
+/// Show local modifications to the installed packages
+///
+/// The files in the package folders are compared with the hashes recorded when vrc-get
+/// installed the packages. Packages installed without the hashes are reported as unknown.
+#[derive(Parser)]
+#[command(author, version)]
+pub struct Status {
+    /// Path to project dir. by default CWD or parents of CWD will be used
+    #[arg(short = 'p', long = "project")]
+    project: Option<Box<Path>>,
+}
+
+#[derive(Serialize)]
+struct StatusResult<'a> {
+    packages: Vec<PackageStatus<'a>>,
+}
+
+#[derive(Serialize)]
+struct PackageStatus<'a> {
+    name: &'a str,
+    version: &'a Version,
+    status: &'static str,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    modified: &'a [Box<str>],
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    added: &'a [Box<str>],
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    deleted: &'a [Box<str>],
+}
+
+impl Status {
+    pub async fn run(self) {
+        let unity = load_unity(self.project).await;
+
+        let mut installed = unity.installed_packages().collect::<Vec<_>>();
+        installed.sort_by_key(|(name, _)| *name);
+
+        let mut modifications = Vec::with_capacity(installed.len());
+        for (name, _) in &installed {
+            let found = unity
+                .package_modifications(name)
+                .await
+                .exit_context("checking local modifications");
+            modifications.push(found);
+        }
+
+        let result = StatusResult {
+            packages: (installed.iter().zip(&modifications))
+                .map(|(&(name, json), modifications)| {
+                    package_status(name, json.version(), modifications)
+                })
+                .collect(),
+        };
+
+        for package in &result.packages {
+            human_println!("{}@{}: {}", package.name, package.version, package.status);
+            for (mark, paths) in [
+                ('M', package.modified),
+                ('A', package.added),
+                ('D', package.deleted),
+            ] {
+                for path in paths {
+                    human_println!("  {mark} {path}");
+                }
+            }
+        }
+
+        crate::output::set_result(&result);
+    }
+}
+
+fn package_status<'a>(
+    name: &'a str,
+    version: &'a Version,
+    modifications: &'a Option<PackageModifications>,
+) -> PackageStatus<'a> {
+    let (status, modified, added, deleted): (_, &[_], &[_], &[_]) = match modifications {
+        None => ("unknown", &[], &[], &[]),
+        Some(x) if !x.is_modified() => ("clean", &[], &[], &[]),
+        Some(x) => ("modified", x.modified(), x.added(), x.deleted()),
+    };
+    PackageStatus {
+        name,
+        version,
+        status,
+        modified,
+        added,
+        deleted,
+    }
+}
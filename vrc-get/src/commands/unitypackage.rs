@@ -0,0 +1,248 @@
+use super::{confirm_prompt, load_unity, ResultExt};
+use clap::Parser;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use vrc_get_vpm::unity_project::{ImportAssetStatus, ImportFilter, UnityPackage};
+
+/// Import assets in .unitypackage file to the project
+///
+/// The GUIDs of assets are preserved. Assets conflicting with existing assets in the project
+/// are not imported.
+#[derive(Parser)]
+#[command(author, version)]
+pub struct Import {
+    /// Path to .unitypackage file
+    #[arg()]
+    file: PathBuf,
+
+    /// Path to project dir. by default CWD or parents of CWD will be used
+    #[arg(short = 'p', long = "project")]
+    project: Option<Box<Path>>,
+
+    /// Import only the asset or assets in the folder. can be specified multiple times
+    #[arg(long, value_name = "PATH")]
+    include: Vec<String>,
+
+    /// Do not import the asset or assets in the folder. can be specified multiple times
+    #[arg(long, value_name = "PATH")]
+    exclude: Vec<String>,
+
+    /// List the assets to be imported without importing
+    #[arg(long)]
+    list: bool,
+
+    /// skip confirm
+    #[arg(short, long)]
+    yes: bool,
+}
+
+#[derive(Serialize)]
+struct ImportResult<'a> {
+    assets: Vec<ImportedAsset<'a>>,
+    applied: bool,
+}
+
+#[derive(Serialize)]
+struct ImportedAsset<'a> {
+    path: &'a str,
+    guid: &'a str,
+    is_folder: bool,
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    conflicts_with: Option<&'a Path>,
+}
+
+fn status_name(status: &ImportAssetStatus) -> &'static str {
+    match status {
+        ImportAssetStatus::New => "new",
+        ImportAssetStatus::Update => "update",
+        ImportAssetStatus::GuidConflict(_) => "guid_conflict",
+        ImportAssetStatus::PathConflict => "path_conflict",
+        ImportAssetStatus::OutsideAssets => "outside_assets",
+        _ => "unknown",
+    }
+}
+
+impl Import {
+    pub async fn run(self) {
+        let unity = load_unity(self.project).await;
+
+        let archive = tokio::fs::read(&self.file)
+            .await
+            .exit_context("reading unitypackage");
+        let package = UnityPackage::read(archive.as_slice())
+            .await
+            .exit_context("reading unitypackage");
+
+        let mut filter = ImportFilter::new();
+        for include in &self.include {
+            filter = filter.include(include);
+        }
+        for exclude in &self.exclude {
+            filter = filter.exclude(exclude);
+        }
+
+        let import = unity
+            .import_unitypackage_request(&package, &filter)
+            .await
+            .exit_context("collecting assets to be imported");
+
+        if import.assets().is_empty() {
+            exit_with!(NotFound, "no assets to import");
+        }
+
+        let mut result = ImportResult {
+            assets: (import.assets().iter())
+                .map(|x| ImportedAsset {
+                    path: x.asset().path(),
+                    guid: x.asset().guid(),
+                    is_folder: x.asset().is_folder(),
+                    status: status_name(x.status()),
+                    conflicts_with: if let ImportAssetStatus::GuidConflict(path) = x.status() {
+                        Some(path.as_ref())
+                    } else {
+                        None
+                    },
+                })
+                .collect(),
+            applied: false,
+        };
+
+        for asset in &result.assets {
+            match asset.conflicts_with {
+                Some(path) => human_println!(
+                    "{status:>14} {path} (same guid as {conflict})",
+                    status = asset.status,
+                    path = asset.path,
+                    conflict = path.display(),
+                ),
+                None => human_println!(
+                    "{status:>14} {path}",
+                    status = asset.status,
+                    path = asset.path
+                ),
+            }
+        }
+
+        if self.list {
+            crate::output::set_result(&result);
+            return;
+        }
+
+        if import.has_conflicts() {
+            log::warn!("some assets conflict with existing assets and will not be imported");
+        }
+
+        if self.yes {
+            human_println!("--yes is set. skipping confirm");
+        } else if !confirm_prompt("Do you want to import those assets?") {
+            crate::output::set_result(&result);
+            exit_with!(Aborted, "aborted by user");
+        }
+
+        unity
+            .import_unitypackage(&import)
+            .await
+            .exit_context("importing assets");
+
+        result.applied = true;
+        crate::output::set_result(&result);
+        human_println!("imported assets from {}", self.file.display());
+    }
+}
+
+/// Export assets in the project to .unitypackage file
+///
+/// All assets must have .meta files to preserve GUIDs.
+#[derive(Parser)]
+#[command(author, version)]
+pub struct ExportUnitypackage {
+    /// Path to asset or folder to be exported like Assets/MyTool. can be specified multiple times
+    #[arg(long, value_name = "PATH", required_unless_present = "package")]
+    path: Vec<PathBuf>,
+
+    /// Name of the package installed in Packages folder to be exported
+    #[arg(long, value_name = "NAME", conflicts_with = "path")]
+    package: Option<String>,
+
+    /// The folder the package is imported into, like Assets/MyTool. Assets/<NAME> by default.
+    /// Use Packages/<NAME> to import the package as an embedded package
+    #[arg(long, value_name = "PATH", requires = "package")]
+    destination: Option<PathBuf>,
+
+    /// Path to .unitypackage file to be created
+    #[arg(short = 'o', long = "out", value_name = "FILE")]
+    out: PathBuf,
+
+    /// Path to project dir. by default CWD or parents of CWD will be used
+    #[arg(short = 'p', long = "project")]
+    project: Option<Box<Path>>,
+}
+
+#[derive(Serialize)]
+struct ExportResult<'a> {
+    output: &'a Path,
+    assets: Vec<ExportedAsset<'a>>,
+}
+
+#[derive(Serialize)]
+struct ExportedAsset<'a> {
+    path: &'a str,
+    guid: &'a str,
+    is_folder: bool,
+}
+
+impl ExportUnitypackage {
+    pub async fn run(self) {
+        let unity = load_unity(self.project).await;
+
+        let package = if let Some(name) = &self.package {
+            let destination =
+                (self.destination.clone()).unwrap_or_else(|| PathBuf::from("Assets").join(name));
+            unity
+                .export_package_unitypackage(name, &destination)
+                .await
+                .exit_context("collecting assets to be exported")
+        } else {
+            let paths = self.path.iter().map(PathBuf::as_path).collect::<Vec<_>>();
+            unity
+                .export_unitypackage(&paths)
+                .await
+                .exit_context("collecting assets to be exported")
+        };
+
+        if package.assets().is_empty() {
+            exit_with!(NotFound, "no assets to export");
+        }
+
+        let mut archive = Vec::new();
+        package
+            .write(&mut archive)
+            .await
+            .exit_context("creating unitypackage");
+        tokio::fs::write(&self.out, archive)
+            .await
+            .exit_context("writing unitypackage");
+
+        let result = ExportResult {
+            output: &self.out,
+            assets: (package.assets().iter())
+                .map(|x| ExportedAsset {
+                    path: x.path(),
+                    guid: x.guid(),
+                    is_folder: x.is_folder(),
+                })
+                .collect(),
+        };
+
+        for asset in &result.assets {
+            human_println!("exported {}", asset.path);
+        }
+        human_println!(
+            "exported {} assets to {}",
+            result.assets.len(),
+            self.out.display()
+        );
+        crate::output::set_result(&result);
+    }
+}
@@ -1,10 +1,20 @@
-use crate::commands::{load_env, ResultExt};
+use crate::commands::{load_env, EnvArgs, Environment, ResultExt, UnityProject};
 use clap::{Parser, Subcommand};
 use log::warn;
+use serde::Serialize;
+use serde_json::Value;
 use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fmt;
 use std::path::Path;
+use std::process::Stdio;
+use std::str::FromStr;
+use tokio::task::JoinSet;
+use vrc_get_vpm::environment::UserProject;
 use vrc_get_vpm::io::DefaultProjectIo;
-use vrc_get_vpm::{unity_hub, UnityProject};
+use vrc_get_vpm::version::{Version, VersionRange};
+use vrc_get_vpm::{unity_hub, PackageCollection, VersionSelector};
 
 /// Experimental VCC commands
 #[derive(Subcommand)]
@@ -13,6 +23,8 @@ pub enum Vcc {
     #[command(subcommand)]
     Project(Project),
     #[command(subcommand)]
+    Package(Package),
+    #[command(subcommand)]
     Unity(Unity),
 }
 
@@ -23,7 +35,7 @@ impl Vcc {
     }
 }
 
-multi_command!(fn run_inner Vcc is Project, Unity);
+multi_command!(fn run_inner Vcc is Project, Package, Unity);
 
 /// Vcc Project Commands
 #[derive(Subcommand)]
@@ -32,9 +44,10 @@ pub enum Project {
     List(ProjectList),
     Add(ProjectAdd),
     Remove(ProjectRemove),
+    Each(ProjectEach),
 }
 
-multi_command!(Project is List, Add, Remove);
+multi_command!(Project is List, Add, Remove, Each);
 
 /// List projects
 #[derive(Parser)]
@@ -60,6 +73,28 @@ impl ProjectList {
 
         projects.sort_by_key(|x| Reverse(x.last_modified().as_millis_since_epoch()));
 
+        if crate::output::is_json() {
+            #[derive(Serialize)]
+            struct ProjectInfo<'a> {
+                name: &'a str,
+                path: &'a str,
+                unity_version: Option<String>,
+                target: String,
+                favorite: bool,
+            }
+
+            let result = (projects.iter())
+                .map(|project| ProjectInfo {
+                    name: project.name(),
+                    path: project.path(),
+                    unity_version: project.unity_version().map(|x| x.to_string()),
+                    target: project.project_type().to_string(),
+                    favorite: project.favorite(),
+                })
+                .collect::<Vec<_>>();
+            return crate::output::set_result(result);
+        }
+
         for project in projects.iter() {
             let path = project.path();
             // TODO: use '/' for unix
@@ -97,7 +132,7 @@ impl ProjectAdd {
                 .exit_context("loading specified project");
 
         if !project.is_valid().await {
-            return eprintln!("Invalid project at {}", self.path);
+            exit_with!("Invalid project at {}", self.path);
         }
 
         env.migrate_from_settings_json()
@@ -130,7 +165,7 @@ impl ProjectRemove {
             .into_iter()
             .find(|x| x.path() == self.path.as_ref())
         else {
-            return println!("No project found at {}", self.path);
+            exit_with!(NotFound, "No project found at {}", self.path);
         };
 
         env.migrate_from_settings_json()
@@ -143,6 +178,546 @@ impl ProjectRemove {
     }
 }
 
+/// Run the vrc-get command for each project
+///
+/// The command is run as `vrc-get --output json <COMMAND>... --project <path>` for each project
+/// matching the filters, and the results are summarized when all commands finish.
+/// The commands cannot ask for confirmation so pass `--yes` to the commands which change projects.
+/// The repositories are fetched once by this command, so the commands run with `--no-update`,
+/// or with `--offline` if this command is `--offline`.
+///
+/// Example: `vrc-get vcc project each --filter type=avatars -- upgrade com.vrchat.avatars --yes`
+#[derive(Parser)]
+#[command(author, version)]
+pub struct ProjectEach {
+    /// Run only for projects matching all of the filters.
+    ///
+    /// The filter is `<key>=<pattern>` where key is one of `name`, `path`, `type`, `unity`,
+    /// or `favorite`. `*` in the pattern matches any string. e.g. `type=avatars,unity=2022.*`
+    #[arg(long, value_delimiter = ',')]
+    filter: Vec<ProjectFilter>,
+    /// The number of commands run in parallel
+    #[arg(short, long, default_value_t = 4)]
+    jobs: usize,
+    #[command(flatten)]
+    env_args: super::EnvArgs,
+
+    /// The vrc-get command to run for each project
+    #[arg(last = true, required = true)]
+    command: Vec<OsString>,
+}
+
+#[derive(Clone, Copy)]
+enum FilterKey {
+    Name,
+    Path,
+    Type,
+    Unity,
+    Favorite,
+}
+
+#[derive(Clone)]
+struct ProjectFilter {
+    key: FilterKey,
+    pattern: String,
+}
+
+impl FromStr for ProjectFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((key, pattern)) = s.split_once('=') else {
+            return Err(format!("filter must be <key>=<pattern>: {s}"));
+        };
+        let key = match key.trim() {
+            "name" => FilterKey::Name,
+            "path" => FilterKey::Path,
+            "type" => FilterKey::Type,
+            "unity" => FilterKey::Unity,
+            "favorite" => FilterKey::Favorite,
+            key => return Err(format!("unknown filter key: {key}")),
+        };
+        Ok(Self {
+            key,
+            pattern: pattern.trim().to_ascii_lowercase(),
+        })
+    }
+}
+
+impl ProjectFilter {
+    fn matches(&self, project: &UserProject) -> bool {
+        let value = match self.key {
+            FilterKey::Name => project.name().to_owned(),
+            FilterKey::Path => project.path().to_owned(),
+            FilterKey::Type => project.project_type().to_string(),
+            FilterKey::Unity => (project.unity_version())
+                .map(|x| x.to_string())
+                .unwrap_or_default(),
+            FilterKey::Favorite => project.favorite().to_string(),
+        };
+        wildcard_match(&self.pattern, &value.to_ascii_lowercase())
+    }
+}
+
+/// Matches the value with the pattern where `*` matches any string.
+fn wildcard_match(pattern: &str, value: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = value.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts = parts.collect::<Vec<_>>();
+    let Some(last) = parts.pop() else {
+        // no '*' in the pattern
+        return rest.is_empty();
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+#[derive(Serialize)]
+struct EachResult {
+    projects: Vec<ProjectResult>,
+}
+
+#[derive(Serialize)]
+struct ProjectResult {
+    name: String,
+    path: String,
+    exit_code: Option<i32>,
+    success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl ProjectEach {
+    pub async fn run(self) {
+        let mut env = load_env(&self.env_args).await;
+
+        env.migrate_from_settings_json()
+            .await
+            .exit_context("migrating from settings.json");
+
+        env.sync_with_real_projects(false)
+            .await
+            .exit_context("syncing with real projects");
+
+        let mut projects = env.get_projects().exit_context("getting projects");
+        projects.retain(|project| self.filter.iter().all(|filter| filter.matches(project)));
+        projects.sort_by(|a, b| a.name().cmp(b.name()));
+
+        if projects.is_empty() {
+            exit_with!(NotFound, "no projects match the filters");
+        }
+
+        let exe = std::env::current_exe().exit_context("getting path to vrc-get");
+        let jobs = self.jobs.max(1);
+        let mut command = self.command;
+        command.extend(env_args_for(&command, &self.env_args).map(OsString::from));
+
+        let mut running = JoinSet::new();
+        let mut results = Vec::with_capacity(projects.len());
+        for (index, project) in projects.iter().enumerate() {
+            if running.len() >= jobs {
+                results.extend(running.join_next().await.map(|x| x.unwrap()));
+            }
+            human_println!("running for {}", project.name());
+            running.spawn(run_for_project(
+                index,
+                exe.clone(),
+                command.clone(),
+                project.name().to_owned(),
+                project.path().to_owned(),
+            ));
+        }
+        while let Some(result) = running.join_next().await {
+            results.push(result.unwrap());
+        }
+        results.sort_by_key(|(index, _)| *index);
+
+        let result = EachResult {
+            projects: results.into_iter().map(|(_, result)| result).collect(),
+        };
+
+        print_summary(&result.projects);
+
+        let failed = result.projects.iter().filter(|x| !x.success).count();
+        let total = result.projects.len();
+        crate::output::set_result(&result);
+        if failed != 0 {
+            exit_with!("command failed for {failed} of {total} projects");
+        }
+    }
+}
+
+/// Returns the environment options to pass to the command if the command accepts them.
+fn env_args_for(command: &[OsString], env_args: &EnvArgs) -> impl Iterator<Item = &'static str> {
+    use clap::CommandFactory;
+
+    let mut parsed = super::Cli::command();
+    for arg in command {
+        let Some(sub) = arg.to_str().and_then(|x| parsed.find_subcommand(x)) else {
+            break;
+        };
+        parsed = sub.clone();
+    }
+
+    let accepts = parsed.get_arguments().any(|x| x.get_id() == "offline");
+    let flag = if env_args.offline {
+        "--offline"
+    } else {
+        "--no-update"
+    };
+    let specified = command
+        .iter()
+        .any(|x| x == "--offline" || x == "--no-update");
+
+    (accepts && !specified).then_some(flag).into_iter()
+}
+
+async fn run_for_project(
+    index: usize,
+    exe: std::path::PathBuf,
+    command: Vec<OsString>,
+    name: String,
+    path: String,
+) -> (usize, ProjectResult) {
+    let output = tokio::process::Command::new(exe)
+        .args(["--output", "json"])
+        .args(command)
+        .arg("--project")
+        .arg(&path)
+        .stdin(Stdio::null())
+        .output()
+        .await;
+
+    let mut result = ProjectResult {
+        name,
+        path,
+        exit_code: None,
+        success: false,
+        result: None,
+        error: None,
+    };
+
+    let output = match output {
+        Ok(output) => output,
+        Err(e) => {
+            result.error = Some(format!("failed to run vrc-get: {e}"));
+            return (index, result);
+        }
+    };
+
+    result.exit_code = output.status.code();
+    result.success = output.status.success();
+
+    match serde_json::from_slice::<Value>(&output.stdout) {
+        Ok(mut document) => {
+            result.result = document.get_mut("result").map(Value::take);
+            result.error = (document.pointer("/error/message"))
+                .and_then(Value::as_str)
+                .map(ToOwned::to_owned);
+        }
+        Err(_) if !result.success => {
+            // usually the error of the command line arguments, which is printed to stderr
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            result.error = Some(match stderr.lines().find(|x| x.starts_with("error:")) {
+                Some(line) => line.to_owned(),
+                None => format!("exited with {}", output.status),
+            });
+        }
+        Err(_) => {}
+    }
+
+    (index, result)
+}
+
+fn print_summary(projects: &[ProjectResult]) {
+    struct Detail<'a>(&'a ProjectResult);
+
+    impl fmt::Display for Detail<'_> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            if let Some(error) = &self.0.error {
+                return f.write_str(error);
+            }
+            let Some(Value::Object(result)) = &self.0.result else {
+                return Ok(());
+            };
+            if let Some(changes) = result.get("changes") {
+                let count = |key: &str| {
+                    changes
+                        .get(key)
+                        .and_then(Value::as_array)
+                        .map_or(0, Vec::len)
+                };
+                return write!(
+                    f,
+                    "{} to install, {} to remove{}",
+                    count("install"),
+                    count("remove"),
+                    if result.get("applied") == Some(&Value::Bool(true)) {
+                        ""
+                    } else {
+                        " (not applied)"
+                    }
+                );
+            }
+            if let Some((key, Value::Array(values))) = result.iter().find(|(_, x)| x.is_array()) {
+                return write!(f, "{} {key}", values.len());
+            }
+            Ok(())
+        }
+    }
+
+    let name_width = (projects.iter().map(|x| x.name.len()))
+        .chain(["PROJECT".len()])
+        .max()
+        .unwrap_or_default();
+
+    human_println!("{:name_width$}  {:6}  DETAIL", "PROJECT", "STATUS");
+    for project in projects {
+        let status = if project.success { "ok" } else { "failed" };
+        human_println!(
+            "{:name_width$}  {status:6}  {}",
+            project.name,
+            Detail(project)
+        );
+    }
+}
+
+/// Loads the unity projects registered to vcc, sorted by the name.
+///
+/// The projects failed to load are skipped with warning.
+async fn load_unity_projects(env: &mut Environment) -> Vec<(UserProject, UnityProject)> {
+    env.migrate_from_settings_json()
+        .await
+        .exit_context("migrating from settings.json");
+
+    env.sync_with_real_projects(false)
+        .await
+        .exit_context("syncing with real projects");
+
+    let mut projects = env.get_projects().exit_context("getting projects");
+    projects.sort_by(|a, b| a.name().cmp(b.name()));
+
+    let mut loaded = Vec::with_capacity(projects.len());
+    for project in projects {
+        match UnityProject::load(DefaultProjectIo::new(Path::new(project.path()).into())).await {
+            Ok(unity) => loaded.push((project, unity)),
+            Err(e) => warn!("failed to load project {}: {e}", project.name()),
+        }
+    }
+    loaded
+}
+
+/// Vcc Package Commands
+#[derive(Subcommand)]
+#[command(author, version)]
+pub enum Package {
+    Usages(PackageUsages),
+}
+
+multi_command!(Package is Usages);
+
+/// Show the projects using the package and the versions locked in them
+///
+/// The latest version is the latest version compatible with the unity version of each project.
+#[derive(Parser)]
+#[command(author, version)]
+pub struct PackageUsages {
+    /// Name of the package
+    #[arg()]
+    name: String,
+    /// Only show the projects using the versions in the range
+    #[arg(long = "version-range")]
+    version_range: Option<VersionRange>,
+    /// Include prerelease
+    #[arg(long = "prerelease")]
+    prerelease: bool,
+
+    #[command(flatten)]
+    env_args: EnvArgs,
+}
+
+#[derive(Serialize)]
+struct UsagesResult<'a> {
+    package: &'a str,
+    projects: Vec<ProjectUsage<'a>>,
+}
+
+#[derive(Serialize)]
+struct ProjectUsage<'a> {
+    name: &'a str,
+    path: &'a str,
+    version: &'a Version,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    latest_version: Option<&'a Version>,
+    outdated: bool,
+}
+
+impl PackageUsages {
+    pub async fn run(self) {
+        let mut env = load_env(&self.env_args).await;
+        let projects = load_unity_projects(&mut env).await;
+
+        let mut usages = vec![];
+        for (project, unity) in &projects {
+            let Some(locked) = unity.get_locked(&self.name) else {
+                continue;
+            };
+            if let Some(range) = &self.version_range {
+                if !range.match_pre(locked.version(), true) {
+                    continue;
+                }
+            }
+
+            let selector = VersionSelector::latest_for(unity.unity_version(), self.prerelease);
+            let latest = (env.find_package_by_name(&self.name, selector)).map(|x| x.version());
+
+            usages.push(ProjectUsage {
+                name: project.name(),
+                path: project.path(),
+                version: locked.version(),
+                latest_version: latest,
+                outdated: latest.is_some_and(|latest| locked.version() < latest),
+            });
+        }
+
+        if usages.is_empty() {
+            human_println!("no projects use {}", self.name);
+        } else {
+            print_table(
+                ["PROJECT", "VERSION", "LATEST"],
+                usages.iter().map(|usage| {
+                    [
+                        usage.name.to_owned(),
+                        usage.version.to_string(),
+                        usage.latest_version.map_or("-".into(), ToString::to_string),
+                    ]
+                }),
+            );
+            human_println!(
+                "{} of {} projects use {}",
+                usages.len(),
+                projects.len(),
+                self.name
+            );
+        }
+
+        crate::output::set_result(UsagesResult {
+            package: &self.name,
+            projects: usages,
+        });
+    }
+}
+
+#[derive(Serialize)]
+struct OutdatedPackageUsages<'a> {
+    package_name: &'a str,
+    projects: Vec<OutdatedProject<'a>>,
+}
+
+#[derive(Serialize)]
+struct OutdatedProject<'a> {
+    name: &'a str,
+    path: &'a str,
+    installed_version: &'a Version,
+    latest_version: &'a Version,
+    held: bool,
+}
+
+/// The implementation of `vrc-get outdated --all-projects`.
+///
+/// The outdated packages are grouped by the package so we can see which projects need upgrade.
+pub(super) async fn outdated_all_projects(env_args: &EnvArgs, prerelease: bool) {
+    let mut env = load_env(env_args).await;
+    let projects = load_unity_projects(&mut env).await;
+
+    let mut packages = BTreeMap::<&str, Vec<OutdatedProject>>::new();
+    for (project, unity) in &projects {
+        let selector = VersionSelector::latest_for(unity.unity_version(), prerelease);
+        for locked in unity.locked_packages() {
+            let Some(latest) = env.find_package_by_name(locked.name(), selector) else {
+                continue;
+            };
+            if locked.version() >= latest.version() {
+                continue;
+            }
+            packages
+                .entry(locked.name())
+                .or_default()
+                .push(OutdatedProject {
+                    name: project.name(),
+                    path: project.path(),
+                    installed_version: locked.version(),
+                    latest_version: latest.version(),
+                    held: unity.is_held(locked.name()),
+                });
+        }
+    }
+
+    let result = (packages.into_iter())
+        .map(|(package_name, projects)| OutdatedPackageUsages {
+            package_name,
+            projects,
+        })
+        .collect::<Vec<_>>();
+
+    if result.is_empty() {
+        human_println!("all packages are up to date in {} projects", projects.len());
+    } else {
+        print_table(
+            ["PACKAGE", "PROJECT", "INSTALLED", "LATEST"],
+            result.iter().flat_map(|package| {
+                package.projects.iter().map(|project| {
+                    [
+                        package.package_name.to_owned(),
+                        project.name.to_owned(),
+                        project.installed_version.to_string(),
+                        if project.held {
+                            format!("{} (held)", project.latest_version)
+                        } else {
+                            project.latest_version.to_string()
+                        },
+                    ]
+                })
+            }),
+        );
+    }
+
+    crate::output::set_result(result);
+}
+
+fn print_table<const N: usize>(header: [&str; N], rows: impl Iterator<Item = [String; N]>) {
+    let rows = rows.collect::<Vec<_>>();
+    let mut widths = header.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let print_row = |row: &[String; N]| {
+        let line = (row.iter().zip(widths))
+            .map(|(cell, width)| format!("{cell:width$}"))
+            .collect::<Vec<_>>()
+            .join("  ");
+        human_println!("{}", line.trim_end());
+    };
+
+    print_row(&header.map(str::to_owned));
+    for row in &rows {
+        print_row(row);
+    }
+}
+
 /// Vcc Unity Management Commands
 #[derive(Subcommand)]
 #[command(author, version)]
@@ -173,6 +748,22 @@ impl UnityList {
 
         unity_installations.sort_by_key(|x| Reverse(x.version()));
 
+        if crate::output::is_json() {
+            #[derive(Serialize)]
+            struct UnityInfo<'a> {
+                version: Option<String>,
+                path: &'a str,
+            }
+
+            let result = (unity_installations.iter())
+                .map(|unity| UnityInfo {
+                    version: unity.version().map(|x| x.to_string()),
+                    path: unity.path(),
+                })
+                .collect::<Vec<_>>();
+            return crate::output::set_result(result);
+        }
+
         for unity in unity_installations.iter() {
             if let Some(unity_version) = unity.version() {
                 println!("version {} at {}", unity_version, unity.path());
@@ -204,7 +795,7 @@ impl UnityAdd {
             .await
             .exit_context("adding unity installation");
 
-        println!("Added version {} at {}", unity_version, self.path);
+        human_println!("Added version {} at {}", unity_version, self.path);
 
         env.save().await.exit_context("saving environment");
     }
@@ -229,7 +820,7 @@ impl UnityRemove {
             .into_iter()
             .find(|x| x.path() == self.path.as_ref())
         else {
-            return eprintln!("No unity installation found at {}", self.path);
+            exit_with!(NotFound, "No unity installation found at {}", self.path);
         };
 
         env.remove_unity_installation(&unity)
@@ -259,7 +850,7 @@ impl UnityUpdate {
             .find_unity_hub()
             .await
             .exit_context("loading unity hub path")
-            .unwrap_or_else(|| exit_with!("Unity Hub not found"));
+            .unwrap_or_else(|| exit_with!(NotFound, "Unity Hub not found"));
 
         let paths_from_hub = unity_hub::get_unity_from_unity_hub(unity_hub_path.as_ref())
             .await
@@ -6,11 +6,15 @@ use clap::Parser;
 use reqwest::Client;
 
 mod commands;
+mod output;
 
 #[tokio::main]
 async fn main() {
+    let cli = commands::Cli::parse();
+    output::set_format(cli.output);
     init_log();
-    commands::Command::parse().run().await;
+    cli.command.run().await;
+    output::finish();
 }
 
 fn init_log() {
@@ -40,7 +44,29 @@ fn init_log() {
             writeln!(buf, "{render}{prefix}{render_reset} {}", record.args())
         });
     }
-    builder.init();
+    let logger = builder.build();
+    log::set_max_level(logger.filter());
+    log::set_boxed_logger(Box::new(CollectingLogger(logger))).expect("initializing logger");
+}
+
+/// The logger records warnings and errors to the json output in addition to printing them.
+struct CollectingLogger(env_logger::Logger);
+
+impl log::Log for CollectingLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        self.0.enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.0.matches(record) && record.level() <= log::Level::Warn && output::is_json() {
+            output::add_warning(record.level(), record.args().to_string());
+        }
+        self.0.log(record)
+    }
+
+    fn flush(&self) {
+        self.0.flush()
+    }
 }
 
 pub(crate) fn create_client(offline: bool) -> Option<Client> {
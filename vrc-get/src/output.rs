@@ -0,0 +1,324 @@
+//! Output format and exit code handling for vrc-get.
+//!
+//! With `--output json`, every command emits exactly one JSON document to stdout when it finishes.
+//! The document has the following shape:
+//!
+//! ```json
+//! {
+//!   "format_version": 1,
+//!   "success": true,
+//!   "result": { /* command specific */ },
+//!   "error": { "code": 3, "kind": "not_found", "message": "..." },
+//!   "warnings": [{ "level": "warn", "message": "..." }]
+//! }
+//! ```
+//!
+//! `error` is only present if `success` is false.
+//! `result` may be present even if the command failed, for example when the user aborted applying
+//! the planned changes.
+
+use clap::ValueEnum;
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt::Display;
+use std::io;
+use std::sync::{Mutex, OnceLock};
+use vrc_get_vpm::environment::AddRepositoryErr;
+use vrc_get_vpm::unity_project::{
+    AddPackageErr, CreatePatchErr, ExportUnityPackageErr, ImportSampleErr, MigrateUnity2022Error,
+    MigrateVpmError, RemovePackageErr, ResolvePackageErr, RestoreChangesErr,
+};
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human readable text output
+    #[default]
+    Human,
+    /// One machine-readable JSON document on stdout
+    Json,
+}
+
+/// The exit codes of vrc-get.
+///
+/// Those values are stable and can be used in scripts.
+/// Please note that `2` is used by the argument parser for usage errors.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExitCode {
+    /// Errors not categorized to others
+    Error = 1,
+    /// The package, project, repository, or file is not found
+    NotFound = 3,
+    /// The requested changes conflict with the current state
+    Conflict = 4,
+    /// Failed to communicate with remote servers
+    Network = 5,
+    /// The user (or non-interactive environment) declined the confirmation
+    Aborted = 6,
+    /// Failed to read or write files
+    Io = 7,
+}
+
+static OUTPUT_FORMAT: OnceLock<OutputFormat> = OnceLock::new();
+static DOCUMENT: Mutex<Document> = Mutex::new(Document::new());
+
+#[derive(Serialize)]
+struct Document {
+    format_version: u32,
+    success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<ErrorInfo>,
+    warnings: Vec<Warning>,
+}
+
+impl Document {
+    const fn new() -> Self {
+        Self {
+            format_version: 1,
+            success: true,
+            result: None,
+            error: None,
+            warnings: Vec::new(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ErrorInfo {
+    code: i32,
+    kind: ExitCode,
+    message: String,
+}
+
+#[derive(Serialize)]
+struct Warning {
+    level: &'static str,
+    message: String,
+}
+
+pub(crate) fn set_format(format: OutputFormat) {
+    OUTPUT_FORMAT.set(format).ok();
+}
+
+pub(crate) fn is_json() -> bool {
+    OUTPUT_FORMAT.get().copied().unwrap_or_default() == OutputFormat::Json
+}
+
+/// Records a log message to the warnings of the document.
+pub(crate) fn add_warning(level: log::Level, message: String) {
+    let level = match level {
+        log::Level::Error => "error",
+        log::Level::Warn => "warn",
+        log::Level::Info => "info",
+        log::Level::Debug => "debug",
+        log::Level::Trace => "trace",
+    };
+    (DOCUMENT.lock().unwrap().warnings).push(Warning { level, message });
+}
+
+/// Sets (or replaces) the result of the command.
+///
+/// This does nothing with human readable output.
+pub(crate) fn set_result(result: impl Serialize) {
+    if is_json() {
+        let value = serde_json::to_value(result).expect("serializing result");
+        DOCUMENT.lock().unwrap().result = Some(value);
+    }
+}
+
+/// Prints the document if the output format is json.
+pub(crate) fn finish() {
+    if is_json() {
+        let document = DOCUMENT.lock().unwrap();
+        println!("{}", serde_json::to_string(&*document).unwrap());
+    }
+}
+
+/// Prints the document and exits successfully.
+pub(crate) fn exit_success() -> ! {
+    finish();
+    std::process::exit(0)
+}
+
+pub(crate) fn exit_with(code: ExitCode, message: impl Display) -> ! {
+    if is_json() {
+        {
+            let mut document = DOCUMENT.lock().unwrap();
+            document.success = false;
+            document.error = Some(ErrorInfo {
+                code: code as i32,
+                kind: code,
+                message: message.to_string(),
+            });
+        }
+        finish();
+    } else {
+        eprintln!("{message}");
+    }
+    std::process::exit(code as i32)
+}
+
+/// The errors that can be mapped to the exit code.
+pub(crate) trait ErrorExitCode {
+    fn exit_code(&self) -> ExitCode;
+}
+
+impl ErrorExitCode for io::Error {
+    fn exit_code(&self) -> ExitCode {
+        if self
+            .get_ref()
+            .map(|inner| inner.is::<reqwest::Error>())
+            .unwrap_or(false)
+        {
+            return ExitCode::Network;
+        }
+
+        let kind = self.kind();
+        if kind == io::ErrorKind::NotFound {
+            ExitCode::NotFound
+        } else if matches!(
+            kind,
+            io::ErrorKind::ConnectionRefused
+                | io::ErrorKind::ConnectionReset
+                | io::ErrorKind::ConnectionAborted
+                | io::ErrorKind::NotConnected
+                | io::ErrorKind::TimedOut
+        ) {
+            ExitCode::Network
+        } else {
+            ExitCode::Io
+        }
+    }
+}
+
+impl ErrorExitCode for AddPackageErr {
+    fn exit_code(&self) -> ExitCode {
+        match self {
+            AddPackageErr::DependencyNotFound { .. }
+            | AddPackageErr::UpgradingNonLockedPackage { .. }
+            | AddPackageErr::DowngradingNonLockedPackage { .. } => ExitCode::NotFound,
+            AddPackageErr::UpgradingWithDowngrade { .. } => ExitCode::Conflict,
+            _ => ExitCode::Error,
+        }
+    }
+}
+
+impl ErrorExitCode for ResolvePackageErr {
+    fn exit_code(&self) -> ExitCode {
+        match self {
+            ResolvePackageErr::DependencyNotFound { .. } => ExitCode::NotFound,
+            _ => ExitCode::Error,
+        }
+    }
+}
+
+impl ErrorExitCode for RestoreChangesErr {
+    fn exit_code(&self) -> ExitCode {
+        match self {
+            RestoreChangesErr::ProjectChanged | RestoreChangesErr::PackageChanged { .. } => {
+                ExitCode::Conflict
+            }
+            RestoreChangesErr::PackageNotFound { .. } => ExitCode::NotFound,
+            RestoreChangesErr::UnsupportedFormat(_) | RestoreChangesErr::InvalidChanges => {
+                ExitCode::Error
+            }
+            _ => ExitCode::Error,
+        }
+    }
+}
+
+impl ErrorExitCode for serde_json::Error {
+    fn exit_code(&self) -> ExitCode {
+        ExitCode::Error
+    }
+}
+
+impl ErrorExitCode for RemovePackageErr {
+    fn exit_code(&self) -> ExitCode {
+        match self {
+            RemovePackageErr::Io(err) => err.exit_code(),
+            RemovePackageErr::NotInstalled(_) => ExitCode::NotFound,
+            RemovePackageErr::ConflictsWith(_) => ExitCode::Conflict,
+        }
+    }
+}
+
+impl ErrorExitCode for AddRepositoryErr {
+    fn exit_code(&self) -> ExitCode {
+        match self {
+            AddRepositoryErr::Io(err) => err.exit_code(),
+            AddRepositoryErr::AlreadyAdded => ExitCode::Conflict,
+            AddRepositoryErr::OfflineMode => ExitCode::Network,
+        }
+    }
+}
+
+impl ErrorExitCode for ExportUnityPackageErr {
+    fn exit_code(&self) -> ExitCode {
+        match self {
+            ExportUnityPackageErr::Io(err) => err.exit_code(),
+            ExportUnityPackageErr::NotFound(_) | ExportUnityPackageErr::NotInstalled(_) => {
+                ExitCode::NotFound
+            }
+            ExportUnityPackageErr::MissingMeta(_) | ExportUnityPackageErr::BadDestination(_) => {
+                ExitCode::Error
+            }
+            _ => ExitCode::Error,
+        }
+    }
+}
+
+impl ErrorExitCode for ImportSampleErr {
+    fn exit_code(&self) -> ExitCode {
+        match self {
+            ImportSampleErr::Io(err) => err.exit_code(),
+            ImportSampleErr::NotInstalled(_)
+            | ImportSampleErr::SampleNotFound { .. }
+            | ImportSampleErr::SampleFolderNotFound(_) => ExitCode::NotFound,
+            ImportSampleErr::AlreadyImported(_) => ExitCode::Conflict,
+            ImportSampleErr::BadSamplePath(_) => ExitCode::Error,
+            _ => ExitCode::Error,
+        }
+    }
+}
+
+impl ErrorExitCode for CreatePatchErr {
+    fn exit_code(&self) -> ExitCode {
+        match self {
+            CreatePatchErr::Io(err) => err.exit_code(),
+            CreatePatchErr::NotInstalled(_) | CreatePatchErr::PackageNotFound { .. } => {
+                ExitCode::NotFound
+            }
+            CreatePatchErr::BinaryFile(_) => ExitCode::Error,
+            _ => ExitCode::Error,
+        }
+    }
+}
+
+impl ErrorExitCode for MigrateVpmError {
+    fn exit_code(&self) -> ExitCode {
+        match self {
+            MigrateVpmError::VpmPackageNotFound(_) => ExitCode::NotFound,
+            MigrateVpmError::AddPackageErr(err) => err.exit_code(),
+            MigrateVpmError::Io(err) => err.exit_code(),
+            MigrateVpmError::ProjectTypeMismatch(_) | MigrateVpmError::UnityVersionMismatch => {
+                ExitCode::Error
+            }
+            _ => ExitCode::Error,
+        }
+    }
+}
+
+impl ErrorExitCode for MigrateUnity2022Error {
+    fn exit_code(&self) -> ExitCode {
+        match self {
+            MigrateUnity2022Error::VpmPackageNotFound(_) => ExitCode::NotFound,
+            MigrateUnity2022Error::AddPackageErr(err) => err.exit_code(),
+            MigrateUnity2022Error::Io(err) => err.exit_code(),
+            MigrateUnity2022Error::UnityVersionMismatch => ExitCode::Error,
+            _ => ExitCode::Error,
+        }
+    }
+}